@@ -0,0 +1,57 @@
+//! Benchmark of the Kyber key encapsulation mechanism.
+//!
+//! Run it with `cargo bench --bench kyber`. Every operation of every parameter set is executed
+//! `ITERATIONS` times and the mean duration of one call is reported.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+use cryptum::ByteArray;
+use cryptum::kyber::{KyberKEM, KyberKEM1024, KyberKEM512, KyberKEM768};
+
+const ITERATIONS: u32 = 200;
+const SHARED_SECRET_LENGTH: u8 = 32;
+
+/// This function runs `operation` `ITERATIONS` times and returns the mean duration of one call.
+fn measure<T>(mut operation: impl FnMut() -> T) -> Duration {
+    // Warming up the caches before measuring
+    for _ in 0..ITERATIONS / 10 {
+        black_box(operation());
+    }
+
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        black_box(operation());
+    }
+    start.elapsed() / ITERATIONS
+}
+
+fn bench_kem(name: &str, kem: &dyn KyberKEM) {
+    let (public_key, private_key) = kem.keygen(ByteArray::random(64)).unwrap();
+    let (ciphertext, _) = kem.encrypt(public_key.clone(), ByteArray::random(32), SHARED_SECRET_LENGTH).unwrap();
+
+    let keygen = measure(|| kem.keygen(ByteArray::random(64)).unwrap());
+    let encaps = measure(|| {
+        kem.encrypt(public_key.clone(), ByteArray::random(32), SHARED_SECRET_LENGTH).unwrap()
+    });
+    let decaps = measure(|| {
+        kem.decrypt(ciphertext.clone(), private_key.clone(), SHARED_SECRET_LENGTH).unwrap()
+    });
+
+    let prepared_public_key = kem.prepare_public_key(public_key).unwrap();
+    let prepared_private_key = kem.prepare_private_key(private_key).unwrap();
+    let encaps_prepared = measure(|| {
+        kem.encapsulate_prepared(&prepared_public_key, ByteArray::random(32), SHARED_SECRET_LENGTH).unwrap()
+    });
+    let decaps_prepared = measure(|| {
+        kem.decapsulate_prepared(&prepared_private_key, ciphertext.clone(), SHARED_SECRET_LENGTH).unwrap()
+    });
+
+    println!("{:<12} keygen {:>10.1?}   encaps {:>10.1?}   decaps {:>10.1?}", name, keygen, encaps, decaps);
+    println!("{:<12} prepared keys               encaps {:>10.1?}   decaps {:>10.1?}", "", encaps_prepared, decaps_prepared);
+}
+
+fn main() {
+    bench_kem("Kyber512", &KyberKEM512::init());
+    bench_kem("Kyber768", &KyberKEM768::init());
+    bench_kem("Kyber1024", &KyberKEM1024::init());
+}
@@ -1,4 +1,6 @@
+use zeroize::Zeroize;
 
+#[allow(dead_code)]
 pub trait GaloisField {
     fn is_zero(&self) -> bool;
     fn zero() -> Self;
@@ -34,6 +36,13 @@ impl <const P: usize>From<i32> for GaloisFieldCore<P> {
 }
 
 
+impl <const P: usize>Zeroize for GaloisFieldCore<P> {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+
 impl <const P: usize>Default for GaloisFieldCore<P> {
     fn default() -> Self {
         0.into()
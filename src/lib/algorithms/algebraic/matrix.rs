@@ -5,12 +5,12 @@ type MatrixContent<P> = Vec<Row<P>>;
 
 fn check_matrix_content<P: RingElement>(content: &MatrixContent<P>) {
     // Checking that matrix contains some rows
-    if content.len() == 0 {
+    if content.is_empty() {
         panic!("Matrix should content some rows")
     }
 
     let columns_numbers: Vec<usize> = content.iter().map(|row| row.len()).collect();
-    let base_number = columns_numbers.get(0).unwrap();
+    let base_number = columns_numbers.first().unwrap();
 
 
     // Checking that all numbers are the same
@@ -38,7 +38,7 @@ impl <P: RingElement>From<MatrixContent<P>> for Matrix<P> {
     fn from(value: MatrixContent<P>) -> Self {
         check_matrix_content(&value);
         let number_rows = value.len() as u8;
-        let number_columns = value.get(0).unwrap().len() as u8;
+        let number_columns = value.first().unwrap().len() as u8;
 
         Self {
             data: value,
@@ -1,7 +1,11 @@
 use std::cmp::{max};
 use std::ops::{Index};
+use zeroize::Zeroize;
 use crate::algorithms::algebraic::galois_field::GaloisField;
 
+// Schoolbook operations are not used by Kyber and Dilithium which rely on the NTT, but they allow
+// checking the latter in unit tests.
+#[allow(dead_code)]
 pub trait RingElement {
     fn degree(&self) -> Option<usize>;
     fn zero() -> Self;
@@ -19,6 +23,13 @@ where C: GaloisField + Default {
     degree: Option<usize>
 }
 
+impl <C, const N: usize> Zeroize for Polynomial<C, N> where C: GaloisField + Default + Zeroize {
+    fn zeroize(&mut self) {
+        self.coefficients.iter_mut().zeroize();
+        self.degree = None;
+    }
+}
+
 impl <C, const N: usize> From<[C; N]> for Polynomial<C, N> where C: GaloisField + Default + Copy + Clone + From<i32>{
     fn from(value: [C; N]) -> Self {
         let degree= Self::find_degree(&value);
@@ -60,7 +71,7 @@ impl <C, const N: usize>  Polynomial<C, N> where C: GaloisField + Default + Copy
         }
 
         // In case degree is 0, we should check if we should create the zero polynomial
-        if degree == 0 && coefficients.get(0).unwrap().value() == 0 {
+        if degree == 0 && coefficients.first().unwrap().value() == 0 {
             None
         } else {
             Some(degree)
@@ -71,7 +82,20 @@ impl <C, const N: usize>  Polynomial<C, N> where C: GaloisField + Default + Copy
         self.coefficients.as_slice()
     }
 
+    pub fn as_array(&self) -> &[C; N] {
+        &self.coefficients
+    }
+
+    /// This function modifies the coefficients in place and then updates the degree.
+    /// Input:
+    ///     update: A function receiving the coefficients of the polynomial
+    pub fn update_coefficients(&mut self, update: impl FnOnce(&mut [C; N])) {
+        update(&mut self.coefficients);
+        self.degree = Self::find_degree(&self.coefficients);
+    }
+
 
+    #[allow(dead_code)]
     fn poly_euclidean_division(poly: &[C], divisor: &[C]) -> Vec<C> {
         let poly_degree = Self::find_degree(poly);
         let divisor_degree = Self::find_degree(divisor);
@@ -96,7 +120,7 @@ impl <C, const N: usize>  Polynomial<C, N> where C: GaloisField + Default + Copy
             let poly_coefficient = poly_copy[poly_degree];
 
             // Determining lambda_value and lambda_degree
-            let neg = -1 * poly_coefficient.value() as i32;
+            let neg = -(poly_coefficient.value() as i32);
             let lambda_value: C = neg.into();
             let lambda_degree = poly_degree - divisor_degree;
 
@@ -195,8 +219,8 @@ impl <C, const N: usize> RingElement for Polynomial<C, N> where C: GaloisField +
         let mut coefficients = [C::default(); N];
         let max_degree = max(self.degree.unwrap(), other.degree.unwrap());
 
-        for i in 0..=max_degree {
-            coefficients[i] = self.coefficients[i].add(&other.coefficients[i]);
+        for (i, coefficient) in coefficients.iter_mut().enumerate().take(max_degree + 1) {
+            *coefficient = self.coefficients[i].add(&other.coefficients[i]);
         }
 
         coefficients.into()
@@ -211,8 +235,8 @@ impl <C, const N: usize> RingElement for Polynomial<C, N> where C: GaloisField +
 
         let max_degree = max(self.degree.unwrap_or(0), other.degree.unwrap_or(0));
 
-        for i in 0..=max_degree {
-            coefficients[i] = self.coefficients[i].sub(&other.coefficients[i]);
+        for (i, coefficient) in coefficients.iter_mut().enumerate().take(max_degree + 1) {
+            *coefficient = self.coefficients[i].sub(&other.coefficients[i]);
         }
 
         coefficients.into()
@@ -283,11 +307,11 @@ mod tests {
         );
         let out_poly = f_poly.mul(&g_poly);
 
-        assert_eq!(out_poly.is_zero(), true);
+        assert!(out_poly.is_zero());
 
         let out_poly = g_poly.mul(&f_poly);
 
-        assert_eq!(out_poly.is_zero(), true);
+        assert!(out_poly.is_zero());
     }
 
     #[test]
@@ -367,8 +391,8 @@ mod tests {
         assert_eq!(remainder[0], 4.into());
         assert_eq!(remainder[1], 1.into());
 
-        for i in 2..remainder.len() {
-            assert!(remainder[i].is_zero())
+        for coefficient in remainder.iter().skip(2) {
+            assert!(coefficient.is_zero())
         }
     }
 
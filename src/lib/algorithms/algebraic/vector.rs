@@ -1,7 +1,8 @@
 use std::ops::{Add, Index};
+use zeroize::Zeroize;
 use crate::algorithms::algebraic::polynomial::RingElement;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Vector<C: RingElement> {
     coefficients: Vec<C>,
     n: usize
@@ -15,6 +16,12 @@ impl <C: RingElement> From<Vec<C>> for Vector<C> {
     }
 }
 
+impl <C: RingElement + Zeroize> Zeroize for Vector<C> {
+    fn zeroize(&mut self) {
+        self.coefficients.zeroize();
+    }
+}
+
 impl <C: RingElement>Index<usize> for Vector<C> {
     type Output = C;
 
@@ -1,8 +1,12 @@
+use std::fmt::{Debug, Formatter};
 use rand::{RngCore, thread_rng};
+use zeroize::{Zeroize, Zeroizing};
 use crate::algorithms::utils::bits::{bits_to_byte, byte_to_bits};
+use crate::algorithms::utils::constant_time::ct_eq;
 use crate::CryptumResult;
 
-#[derive(Debug, PartialEq, Clone)]
+// The clones are wiped by their own Drop, as the original
+#[derive(Clone)]
 pub struct ByteArray {
     values: Vec<u8>
 }
@@ -28,34 +32,39 @@ impl ByteArray {
         let mut data = Vec::with_capacity(data_length);
 
         for item in items {
-            data.extend_from_slice(&item.get_bytes())
+            data.extend_from_slice(item.get_bytes())
         }
         Self {values: data}
     }
 
-    pub fn to_bits(&self) -> Vec<u8> {
-        let mut output = vec![];
+    /// This function returns the bits of the bytes, least significant bit first. As the bytes may
+    /// be a private key, the bits are wiped when they are dropped.
+    pub fn to_bits(&self) -> Zeroizing<Vec<u8>> {
+        let mut output = Zeroizing::new(Vec::with_capacity(8 * self.length()));
+        let mut bits = Zeroizing::new([0u8; 8]);
 
         for byte in self.get_bytes() {
-            let mut bits = [0u8; 8];
             byte_to_bits(*byte, &mut bits);
-            output.extend_from_slice(&bits);
+            output.extend_from_slice(bits.as_slice());
         }
         output
     }
 
-    pub fn from_bits(bits: Vec<u8>) -> Self {
+    /// This function packs bits into bytes, least significant bit first. The bits are wiped
+    /// once they are packed.
+    pub fn from_bits(mut bits: Vec<u8>) -> Self {
         // Checking that bits has the correct length
-        if bits.len() % 8 != 0 {
+        if !bits.len().is_multiple_of(8) {
             panic!("bits has an incorrect length")
         }
-        let output_length = bits.len() % 8;
+        let output_length = bits.len() / 8;
         let mut bytes = Vec::with_capacity(output_length);
 
         for chunk in bits.chunks_exact(8) {
             let byte = bits_to_byte(chunk);
             bytes.push(byte);
         }
+        bits.zeroize();
 
         bytes.into()
     }
@@ -74,8 +83,8 @@ impl ByteArray {
         (bytes_1.into(), bytes_2.into())
     }
 
-    pub fn to_hex(self) -> String {
-        hex::encode(self.values)
+    pub fn to_hex(&self) -> String {
+        hex::encode(&self.values)
     }
 
     pub fn from_hex(data: String) -> CryptumResult<ByteArray> {
@@ -85,6 +94,36 @@ impl ByteArray {
 
 }
 
+impl Zeroize for ByteArray {
+    fn zeroize(&mut self) {
+        self.values.zeroize();
+    }
+}
+
+// Byte arrays carry keys, seeds and shared secrets, so they are compared in constant time. Only
+// their lengths, which are public, may leak through the timing of the comparison.
+impl PartialEq for ByteArray {
+    fn eq(&self, other: &Self) -> bool {
+        ct_eq(self, other).into()
+    }
+}
+
+// Byte arrays carry keys, seeds and shared secrets, so only their length is printed. The public
+// values can be printed with to_hex.
+impl Debug for ByteArray {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ByteArray([REDACTED; {} bytes])", self.length())
+    }
+}
+
+// Byte arrays carry keys, seeds and shared secrets, including the temporary copies made while
+// decoding them, so their content is always wiped when they are dropped.
+impl Drop for ByteArray {
+    fn drop(&mut self) {
+        self.values.zeroize();
+    }
+}
+
 impl From<&[u8]> for ByteArray {
     fn from(value: &[u8]) -> Self {
         Self {
@@ -111,7 +150,9 @@ impl From<Vec<u8>> for ByteArray {
 
 #[cfg(test)]
 mod tests {
+    use zeroize::Zeroize;
     use crate::algorithms::byte_array::ByteArray;
+    use crate::algorithms::dilithium::{Dilithium, Dilithium2};
 
     #[test]
     fn test_random_byte_array_creation() {
@@ -150,7 +191,16 @@ mod tests {
     fn test_to_bits() {
         let data = ByteArray::from([2, 3].as_slice());
         let bits = data.to_bits();
-        assert_eq!(bits, [0, 1, 0, 0, 0, 0, 0, 0, 1, 1, 0, 0, 0, 0, 0, 0])
+        assert_eq!(*bits, [0, 1, 0, 0, 0, 0, 0, 0, 1, 1, 0, 0, 0, 0, 0, 0])
+    }
+
+    #[test]
+    fn test_eq() {
+        let data = ByteArray::from([1u8, 2, 3].as_slice());
+
+        assert_eq!(data, ByteArray::from([1u8, 2, 3].as_slice()));
+        assert_ne!(data, ByteArray::from([1u8, 2, 4].as_slice()));
+        assert_ne!(data, ByteArray::from([1u8, 2].as_slice()));
     }
 
     #[test]
@@ -160,4 +210,31 @@ mod tests {
         let expected_length = 3;
         assert_eq!(bytes_array.length(), expected_length)
     }
+
+    #[test]
+    fn test_debug_of_a_key_is_redacted() {
+        let (_, private_key) = Dilithium2::init().keygen(ByteArray::random(32)).unwrap();
+        let debug = format!("{:?}", private_key);
+
+        assert_eq!(debug, "ByteArray([REDACTED; 2528 bytes])");
+        assert!(!debug.contains(&private_key.to_hex()[..8]));
+    }
+
+    #[test]
+    fn test_zeroize_byte_array() {
+        let mut bytes = ByteArray::from([0x41u8; 64].as_slice());
+        let pointer = bytes.values.as_ptr();
+        let capacity = bytes.values.capacity();
+
+        bytes.zeroize();
+
+        // The buffer is wiped in place: it is neither reallocated nor shrunk
+        assert_eq!(bytes.length(), 0);
+        assert_eq!(bytes.values.as_ptr(), pointer);
+        assert_eq!(bytes.values.capacity(), capacity);
+
+        // SAFETY: the buffer is still allocated and zeroize has written every byte of its capacity
+        let buffer = unsafe { std::slice::from_raw_parts(pointer, capacity) };
+        assert!(buffer.iter().all(|&byte| byte == 0), "buffer = {:?}", buffer);
+    }
 }
@@ -0,0 +1,6 @@
+pub const DILITHIUM_N_VALUE: usize = 256;
+pub const DILITHIUM_Q_VALUE: usize = 8380417;
+pub const DILITHIUM_D_VALUE: usize = 13;
+pub const DILITHIUM_SEED_LENGTH: usize = 32;
+pub const DILITHIUM_CRH_LENGTH: usize = 64;
+pub const DILITHIUM_T1_BITS: usize = 10;
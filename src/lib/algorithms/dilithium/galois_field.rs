@@ -0,0 +1,4 @@
+use crate::algorithms::algebraic::galois_field::GaloisFieldCore;
+use crate::algorithms::dilithium::constants::DILITHIUM_Q_VALUE;
+
+pub type GF8380417 = GaloisFieldCore<DILITHIUM_Q_VALUE>;
@@ -0,0 +1,232 @@
+use std::fs;
+use std::path::PathBuf;
+use crate::algorithms::byte_array::ByteArray;
+use crate::algorithms::dilithium::constants::DILITHIUM_SEED_LENGTH;
+use crate::algorithms::dilithium::sign::Dilithium;
+use crate::algorithms::utils::drbg::{AesCtrDrbg, DRBG_SEED_LENGTH};
+
+/// This structure corresponds to one entry of a `PQCsignKAT_*.rsp` file.
+#[derive(Debug)]
+struct KatEntry {
+    count: usize,
+    seed: ByteArray,
+    message: ByteArray,
+    public_key: ByteArray,
+    private_key: ByteArray,
+    signed_message: ByteArray
+}
+
+/// This function parses the content of a `PQCsignKAT_*.rsp` file. Every entry starts with a
+/// `count` line followed by the `seed`, `mlen`, `msg`, `pk`, `sk`, `smlen` and `sm` values. The
+/// `mlen` and `smlen` lines are skipped as they only repeat the lengths of `msg` and `sm`.
+///
+/// Input:
+///     content: The content of the rsp file
+/// Output:
+///     The list of the entries of the file
+fn parse_rsp(content: &str) -> Vec<KatEntry> {
+    let mut entries: Vec<KatEntry> = Vec::new();
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (name, value) = line
+            .split_once('=')
+            .unwrap_or_else(|| panic!("Invalid line in rsp file: {}", line));
+        let (name, value) = (name.trim(), value.trim());
+
+        if name == "count" {
+            entries.push(KatEntry {
+                count: value.parse().expect("Invalid count in rsp file"),
+                seed: ByteArray::empty(),
+                message: ByteArray::empty(),
+                public_key: ByteArray::empty(),
+                private_key: ByteArray::empty(),
+                signed_message: ByteArray::empty()
+            });
+            continue;
+        }
+
+        let entry = entries.last_mut().expect("The rsp file should start with a count");
+
+        if name == "mlen" || name == "smlen" {
+            continue;
+        }
+
+        let value = ByteArray::from_hex(value.to_string()).expect("Invalid hex value in rsp file");
+
+        match name {
+            "seed" => entry.seed = value,
+            "msg" => entry.message = value,
+            "pk" => entry.public_key = value,
+            "sk" => entry.private_key = value,
+            "sm" => entry.signed_message = value,
+            _ => panic!("Unknown field {} in rsp file", name)
+        }
+    }
+
+    entries
+}
+
+/// This function replays a KAT entry the same way as the `PQCgenKAT_sign` program: the DRBG is
+/// seeded with the seed of the entry and the key generation draws its 32-bytes seed from it. The
+/// signature is deterministic so it does not consume any randomness. The signed message is the
+/// signature followed by the message.
+///
+/// Input:
+///     dilithium: The parameter set under test
+///     entry: The entry to replay
+fn replay(dilithium: &dyn Dilithium, entry: &KatEntry) {
+    let seed: [u8; DRBG_SEED_LENGTH] = entry.seed
+        .get_bytes()
+        .try_into()
+        .unwrap_or_else(|_| panic!("Invalid seed length for count {}", entry.count));
+    let mut drbg = AesCtrDrbg::init(&seed);
+
+    let (public_key, private_key) = dilithium.keygen(drbg.random_bytes(DILITHIUM_SEED_LENGTH).into()).unwrap();
+
+    assert_eq!(public_key, entry.public_key, "pk mismatch for count {}", entry.count);
+    assert_eq!(private_key, entry.private_key, "sk mismatch for count {}", entry.count);
+
+    let signature = dilithium.sign(private_key, entry.message.clone()).unwrap();
+
+    assert_eq!(
+        ByteArray::concat(&[&signature, &entry.message]),
+        entry.signed_message,
+        "sm mismatch for count {}",
+        entry.count
+    );
+    assert!(
+        dilithium.verify(public_key, entry.message.clone(), signature).unwrap(),
+        "verification failed for count {}",
+        entry.count
+    );
+}
+
+/// This function replays every entry of a rsp file located in the `tests/kat` directory. The files
+/// have the format of the round 3.1 files, whose counts start from 0, but were generated with this
+/// implementation. The official files can replace them.
+fn replay_file(dilithium: &dyn Dilithium, file_name: &str) {
+    let path: PathBuf = [env!("CARGO_MANIFEST_DIR"), "tests", "kat", file_name].iter().collect();
+    let content = fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("Unable to read {}: {}", path.display(), e));
+
+    let entries = parse_rsp(&content);
+    assert!(!entries.is_empty(), "No entry found in {}", path.display());
+
+    for (count, entry) in entries.iter().enumerate() {
+        assert_eq!(entry.count, count, "Missing entry in {}", path.display());
+        replay(dilithium, entry);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hex_literal::hex;
+    use crate::algorithms::byte_array::ByteArray;
+    use crate::algorithms::dilithium::kat::{parse_rsp, replay_file};
+    use crate::algorithms::dilithium::sign::{Dilithium, Dilithium2, Dilithium3, Dilithium5};
+    use crate::algorithms::utils::drbg::AesCtrDrbg;
+    use crate::algorithms::utils::hash::sha3_256;
+
+    /// This function checks the first entry of a parameter set against the SHA3-256 digests of its
+    /// pk, sk and sm values, so that a change of the rsp files cannot go unnoticed.
+    fn check_first_entry(dilithium: &dyn Dilithium, expected_digests: [[u8; 32]; 3]) {
+        let seed = hex!("061550234D158C5EC95595FE04EF7A25767F2E24CC2BC479D09D86DC9ABCFDE7056A8C266F9EF97ED08541DBD2E1FFA1");
+        let message: ByteArray = hex!("D81C4D8D734FCBFBEADE3D3F8A039FAA2A2C9957E835AD55B22E75BF57BB556AC8").as_slice().into();
+        let mut drbg = AesCtrDrbg::init(&seed);
+
+        let (public_key, private_key) = dilithium.keygen(drbg.random_bytes(32).into()).unwrap();
+        let signature = dilithium.sign(private_key.clone(), message.clone()).unwrap();
+        let signed_message = ByteArray::concat(&[&signature, &message]);
+
+        let [public_key_digest, private_key_digest, signed_message_digest] = expected_digests;
+        assert_eq!(sha3_256(public_key.get_bytes()), public_key_digest, "pk mismatch");
+        assert_eq!(sha3_256(private_key.get_bytes()), private_key_digest, "sk mismatch");
+        assert_eq!(sha3_256(signed_message.get_bytes()), signed_message_digest, "sm mismatch");
+    }
+
+    #[test]
+    fn test_parse_rsp() {
+        let content = "# Dilithium2\n\n\
+            count = 0\n\
+            seed = 0102\n\
+            mlen = 1\n\
+            msg = 03\n\
+            pk = 04\n\
+            sk = 05\n\
+            smlen = 2\n\
+            sm = 0603\n\n\
+            count = 1\n\
+            seed = 07\n";
+
+        let entries = parse_rsp(content);
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].count, 0);
+        assert_eq!(entries[0].seed, ByteArray::from([1u8, 2].as_slice()));
+        assert_eq!(entries[0].message, ByteArray::from(3u8));
+        assert_eq!(entries[0].public_key, ByteArray::from(4u8));
+        assert_eq!(entries[0].private_key, ByteArray::from(5u8));
+        assert_eq!(entries[0].signed_message, ByteArray::from([6u8, 3].as_slice()));
+        assert_eq!(entries[1].count, 1);
+        assert_eq!(entries[1].seed, ByteArray::from(7u8));
+    }
+
+    #[test]
+    fn test_dilithium2_first_public_key() {
+        // Beginning of the public key of the first entry of the round 3.1 PQCsignKAT_2528.rsp file:
+        // rho followed by the first coefficients of t_1
+        let seed = hex!("061550234D158C5EC95595FE04EF7A25767F2E24CC2BC479D09D86DC9ABCFDE7056A8C266F9EF97ED08541DBD2E1FFA1");
+        let mut drbg = AesCtrDrbg::init(&seed);
+
+        let (public_key, _) = Dilithium2::init().keygen(drbg.random_bytes(32).into()).unwrap();
+        let (prefix, _) = public_key.split_at(48);
+
+        let expected_prefix: ByteArray = hex!(
+            "1C0EE1111B08003F28E65E8B3BDEB037CF8F221DFCDAF5950EDB38D506D85BEF"
+            "6177E3DE0D4F1EF5847735947B56D08E"
+        ).as_slice().into();
+
+        assert_eq!(prefix, expected_prefix);
+    }
+
+    // The digests of the count 0 entries of the generated files. Only the beginning of the
+    // Dilithium2 public key is checked against the published value, by the test above.
+    #[test]
+    fn test_first_entries() {
+        check_first_entry(&Dilithium2::init(), [
+            hex!("3965EC9C424D72743AEB461873FE1EF579742444E7BDDD1BCBE8939A27E95B8C"),
+            hex!("4B752F9A786FD581BCA02E81237FFF4B68FDA2DF6BE88796CD970CC0AEBB64D2"),
+            hex!("5F693E42DF1C46FFC5E9A50055A6370C95DAA4037BD5D148BB59C2AB438EA021")
+        ]);
+        check_first_entry(&Dilithium3::init(), [
+            hex!("B3A8BFE06CE9C75208C94CAF2494462779FD61EDFD98F546ED8A97141A43BEF3"),
+            hex!("4E313F50E09D123942EFE69993EFA519FF30C8CD44D71D5BA17E73AFBD474394"),
+            hex!("3C6C5BED89C05A4548A8C31929E10462A29CDA2A8594DFB6CC27A62327240BF6")
+        ]);
+        check_first_entry(&Dilithium5::init(), [
+            hex!("4CB13352C600D29720C556928528E1429F9DE7B5C1E5A7E3918C5BC0B4502A33"),
+            hex!("9831A3FC26F812EE1932C125A4805154ADBC2DA0D7A3CE4C6D085D8CC6B92CA0"),
+            hex!("0FABA9EDCC06194EC8E0B7856B1B7CB1E77F51BE008EDA14E2A3BF54F374B04D")
+        ]);
+    }
+
+    #[test]
+    fn test_kat_dilithium2() {
+        replay_file(&Dilithium2::init(), "PQCsignKAT_2528.rsp");
+    }
+
+    #[test]
+    fn test_kat_dilithium3() {
+        replay_file(&Dilithium3::init(), "PQCsignKAT_4000.rsp");
+    }
+
+    #[test]
+    fn test_kat_dilithium5() {
+        replay_file(&Dilithium5::init(), "PQCsignKAT_4864.rsp");
+    }
+}
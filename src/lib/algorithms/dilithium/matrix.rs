@@ -0,0 +1,20 @@
+use crate::algorithms::algebraic::matrix::Matrix;
+use crate::algorithms::dilithium::polynomial::PolyRQ;
+use crate::algorithms::dilithium::vector::VectorRQ;
+
+pub type MatrixRQ = Matrix<PolyRQ>;
+
+impl MatrixRQ {
+    pub fn multiply_vec(&self, other: &VectorRQ) -> VectorRQ {
+        let matrix_shape = self.get_shape();
+        let mut polynomials = Vec::with_capacity(matrix_shape.0 as usize);
+
+        for i in 0..matrix_shape.0 {
+            let row = self.get_row(i as usize);
+            let polynomial = other.dot_ntt(&VectorRQ::from(row.clone()));
+            polynomials.push(polynomial);
+        }
+
+        polynomials.into()
+    }
+}
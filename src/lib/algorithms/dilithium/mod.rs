@@ -0,0 +1,13 @@
+mod constants;
+mod galois_field;
+mod polynomial;
+mod vector;
+mod matrix;
+mod ntt;
+mod rounding;
+mod sign;
+#[cfg(test)]
+mod kat;
+
+pub use sign::{Dilithium2, Dilithium3, Dilithium5, Dilithium};
+pub use constants::DILITHIUM_SEED_LENGTH;
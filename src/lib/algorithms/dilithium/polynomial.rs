@@ -0,0 +1,182 @@
+use crate::algorithms::algebraic::galois_field::GaloisField;
+use crate::algorithms::algebraic::polynomial::{Polynomial, RingElement};
+use crate::algorithms::byte_array::ByteArray;
+use crate::algorithms::dilithium::constants::DILITHIUM_N_VALUE;
+use crate::algorithms::dilithium::galois_field::GF8380417;
+use crate::algorithms::dilithium::ntt::{NTT, ntt_inv_rec, ntt_rec};
+use crate::algorithms::dilithium::rounding::centered;
+
+pub type PolyRQ = Polynomial<GF8380417, DILITHIUM_N_VALUE>;
+
+impl NTT for PolyRQ {
+    fn inverse_ntt(self) -> Self {
+        // Checking if the polynomial is not zero
+        if self.is_zero() {
+            return PolyRQ::zero();
+        }
+
+        let mut coefficients = [GF8380417::default(); DILITHIUM_N_VALUE];
+        coefficients.copy_from_slice(self.get_coefficients());
+
+        ntt_inv_rec(&mut coefficients, 1, DILITHIUM_N_VALUE / 2);
+
+        coefficients.into()
+    }
+
+    fn to_ntt(self) -> Self {
+        // Checking if the polynomial is not zero
+        if self.is_zero() {
+            return PolyRQ::zero();
+        }
+
+        let mut coefficients = [GF8380417::default(); DILITHIUM_N_VALUE];
+        coefficients.copy_from_slice(self.get_coefficients());
+
+        ntt_rec(&mut coefficients, 1, DILITHIUM_N_VALUE / 2);
+
+        coefficients.into()
+    }
+}
+
+impl PolyRQ {
+    /// As the NTT goes down to polynomials of degree 0, multiplying two polynomials in the NTT
+    /// domain simply consists of multiplying their coefficients pairwise.
+    pub fn multiply_ntt(&self, other: &Self) -> Self {
+        let mut coefficients = [GF8380417::default(); DILITHIUM_N_VALUE];
+
+        for (i, coefficient) in coefficients.iter_mut().enumerate() {
+            *coefficient = self[i].mul(&other[i]);
+        }
+
+        coefficients.into()
+    }
+
+    /// This function returns the infinity norm of the polynomial, i.e. the greatest absolute value
+    /// of its centered coefficients.
+    pub fn infinity_norm(&self) -> usize {
+        self.get_coefficients()
+            .iter()
+            .map(|&coefficient| centered(coefficient).unsigned_abs() as usize)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// This function maps each coefficient of the polynomial with the given function.
+    pub fn map<F: Fn(GF8380417) -> GF8380417>(&self, func: F) -> Self {
+        let mut coefficients = [GF8380417::default(); DILITHIUM_N_VALUE];
+
+        for (i, coefficient) in coefficients.iter_mut().enumerate() {
+            *coefficient = func(self[i]);
+        }
+
+        coefficients.into()
+    }
+
+    /// This function packs the coefficients of the polynomial using l_value bits each.
+    pub fn encode(&self, l_value: usize) -> ByteArray {
+        let mut bits = Vec::with_capacity(DILITHIUM_N_VALUE * l_value);
+
+        for coefficient in self.get_coefficients() {
+            let value = coefficient.value();
+
+            for shift in 0..l_value {
+                bits.push(((value >> shift) & 1) as u8)
+            }
+        }
+
+        ByteArray::from_bits(bits)
+    }
+
+    /// This function packs the coefficients of a polynomial whose centered coefficients lie in
+    /// [-bound + 1, bound] by storing bound - coefficient on l_value bits, as done by the authors.
+    pub fn encode_with_bound(&self, l_value: usize, bound: usize) -> ByteArray {
+        let bound = GF8380417::from(bound);
+        self.map(|coefficient| bound.sub(&coefficient)).encode(l_value)
+    }
+
+    pub fn decode(bytes: &ByteArray, l_value: usize) -> Self {
+        // Checking length of bytes
+        let expected_length = DILITHIUM_N_VALUE * l_value / 8;
+        if bytes.length() != expected_length {
+            panic!("Invalid length for 'bytes'. Expected {} found {}", expected_length, bytes.length())
+        }
+
+        let bits = bytes.to_bits();
+        let mut coefficients = [GF8380417::default(); DILITHIUM_N_VALUE];
+
+        for (i, coefficient) in coefficients.iter_mut().enumerate() {
+            let mut value = 0_usize;
+
+            for j in 0..l_value {
+                value |= (bits[i * l_value + j] as usize) << j;
+            }
+
+            *coefficient = GF8380417::from(value);
+        }
+
+        coefficients.into()
+    }
+
+    pub fn decode_with_bound(bytes: &ByteArray, l_value: usize, bound: usize) -> Self {
+        let bound = GF8380417::from(bound);
+        Self::decode(bytes, l_value).map(|value| bound.sub(&value))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::algorithms::algebraic::polynomial::RingElement;
+    use crate::algorithms::dilithium::galois_field::GF8380417;
+    use crate::algorithms::dilithium::ntt::NTT;
+    use crate::algorithms::dilithium::polynomial::PolyRQ;
+
+    #[test]
+    fn test_ntt_inversion() {
+        let poly = PolyRQ::from_degrees(
+            &[0, 1, 2, 128, 210, 255],
+            &[1.into(), 2.into(), 3.into(), 9.into(), 10.into(), (-7).into()]
+        );
+        let from_ntt_poly = poly.clone().to_ntt().inverse_ntt();
+
+        assert_eq!(from_ntt_poly, poly)
+    }
+
+    #[test]
+    fn test_multiply_ntt() {
+        let f_poly = PolyRQ::from_degrees(
+            &[0, 3, 200, 255],
+            &[5.into(), (-2).into(), 7.into(), 1.into()]
+        );
+        let g_poly = PolyRQ::from_degrees(
+            &[1, 100, 254],
+            &[3.into(), 4.into(), (-1).into()]
+        );
+
+        let expected_poly = f_poly.mul(&g_poly);
+        let poly = f_poly.to_ntt().multiply_ntt(&g_poly.to_ntt()).inverse_ntt();
+
+        assert_eq!(poly, expected_poly)
+    }
+
+    #[test]
+    fn test_infinity_norm() {
+        let poly = PolyRQ::from_degrees(
+            &[0, 1],
+            &[GF8380417::from(-5), GF8380417::from(3_usize)]
+        );
+        assert_eq!(poly.infinity_norm(), 5)
+    }
+
+    #[test]
+    fn test_encode_decode_with_bound() {
+        let poly = PolyRQ::from_degrees(
+            &[0, 1, 2],
+            &[GF8380417::from(-1), GF8380417::from(2_usize), GF8380417::from(-2)]
+        );
+        let bytes = poly.encode_with_bound(3, 2);
+
+        assert_eq!(bytes.length(), 96);
+        assert_eq!(PolyRQ::decode_with_bound(&bytes, 3, 2), poly)
+    }
+}
@@ -0,0 +1,122 @@
+use crate::algorithms::algebraic::galois_field::GaloisField;
+use crate::algorithms::dilithium::constants::{DILITHIUM_D_VALUE, DILITHIUM_Q_VALUE};
+use crate::algorithms::dilithium::galois_field::GF8380417;
+
+/// This function returns the representative of x lying in ]-(q-1)/2, (q-1)/2].
+pub fn centered(x: GF8380417) -> i64 {
+    let value = x.value() as i64;
+
+    if value > (DILITHIUM_Q_VALUE as i64 - 1) / 2 {
+        value - DILITHIUM_Q_VALUE as i64
+    } else {
+        value
+    }
+}
+
+/// This function corresponds to the Power2Round function (p.8 of the article).
+///
+/// Input:
+///     x: An element of Z_q
+/// Output:
+///     A tuple (x_1, x_0) such that x = x_1 * 2^d + x_0 with x_0 in ]-2^(d-1), 2^(d-1)]
+pub fn power_2_round(x: GF8380417) -> (GF8380417, GF8380417) {
+    let value = x.value() as i64;
+    let x_1 = (value + (1 << (DILITHIUM_D_VALUE - 1)) - 1) >> DILITHIUM_D_VALUE;
+    let x_0 = value - (x_1 << DILITHIUM_D_VALUE);
+
+    (GF8380417::from(x_1 as usize), GF8380417::from(x_0 as i32))
+}
+
+/// This function corresponds to the Decompose function (p.8 of the article).
+///
+/// Input:
+///     x: An element of Z_q
+///     gamma_2: The low-order rounding range
+/// Output:
+///     A tuple (x_1, x_0) such that x = x_1 * 2 * gamma_2 + x_0 with x_0 in ]-gamma_2, gamma_2]
+pub fn decompose(x: GF8380417, gamma_2: usize) -> (usize, i64) {
+    let value = x.value() as i64;
+    let alpha = 2 * gamma_2 as i64;
+
+    let mut x_0 = value.rem_euclid(alpha);
+    if x_0 > alpha / 2 {
+        x_0 -= alpha;
+    }
+
+    if value - x_0 == DILITHIUM_Q_VALUE as i64 - 1 {
+        (0, x_0 - 1)
+    } else {
+        (((value - x_0) / alpha) as usize, x_0)
+    }
+}
+
+/// This function computes the hint bit indicating whether adding the low part x_0 to the high
+/// part x_1 changes the high bits. We follow the reference implementation and directly work with
+/// x_0 = w_0 - c.s_2 + c.t_0.
+pub fn make_hint(x_0: i64, x_1: usize, gamma_2: usize) -> bool {
+    let gamma_2 = gamma_2 as i64;
+    x_0 > gamma_2 || x_0 < -gamma_2 || (x_0 == -gamma_2 && x_1 != 0)
+}
+
+/// This function corresponds to the UseHint function (p.8 of the article).
+pub fn use_hint(hint: bool, x: GF8380417, gamma_2: usize) -> usize {
+    let m = (DILITHIUM_Q_VALUE - 1) / (2 * gamma_2);
+    let (x_1, x_0) = decompose(x, gamma_2);
+
+    if !hint {
+        x_1
+    } else if x_0 > 0 {
+        (x_1 + 1) % m
+    } else {
+        (x_1 + m - 1) % m
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::algorithms::algebraic::galois_field::GaloisField;
+    use crate::algorithms::dilithium::constants::{DILITHIUM_D_VALUE, DILITHIUM_Q_VALUE};
+    use crate::algorithms::dilithium::galois_field::GF8380417;
+    use crate::algorithms::dilithium::rounding::{centered, decompose, power_2_round, use_hint};
+
+    const GAMMA_2: usize = (DILITHIUM_Q_VALUE - 1) / 88;
+
+    #[test]
+    fn test_centered() {
+        assert_eq!(centered(GF8380417::from(-3)), -3);
+        assert_eq!(centered(GF8380417::from(3_usize)), 3);
+    }
+
+    #[test]
+    fn test_power_2_round() {
+        for value in [0_usize, 1, 4096, 4097, 123456, DILITHIUM_Q_VALUE - 1] {
+            let (x_1, x_0) = power_2_round(GF8380417::from(value));
+            let x_0 = centered(x_0);
+
+            assert!(x_0 > -(1 << (DILITHIUM_D_VALUE - 1)) && x_0 <= 1 << (DILITHIUM_D_VALUE - 1));
+            assert_eq!((x_1.value() << DILITHIUM_D_VALUE) as i64 + x_0, value as i64);
+        }
+    }
+
+    #[test]
+    fn test_decompose() {
+        for value in [0_usize, 1, GAMMA_2, GAMMA_2 + 1, 5000000, DILITHIUM_Q_VALUE - 1] {
+            let (x_1, x_0) = decompose(GF8380417::from(value), GAMMA_2);
+
+            assert!(x_1 < 44);
+            assert!(x_0 >= -(GAMMA_2 as i64) && x_0 <= GAMMA_2 as i64);
+            assert_eq!(
+                (x_1 as i64 * 2 * GAMMA_2 as i64 + x_0).rem_euclid(DILITHIUM_Q_VALUE as i64),
+                value as i64
+            );
+        }
+    }
+
+    #[test]
+    fn test_use_hint_wraps_around() {
+        let value = GF8380417::from(DILITHIUM_Q_VALUE - 1);
+        assert_eq!(use_hint(false, value, GAMMA_2), 0);
+        assert_eq!(use_hint(true, value, GAMMA_2), 43);
+    }
+}
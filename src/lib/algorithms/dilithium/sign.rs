@@ -0,0 +1,713 @@
+use crate::algorithms::algebraic::galois_field::GaloisField;
+use crate::algorithms::byte_array::ByteArray;
+use crate::algorithms::dilithium::constants::{DILITHIUM_CRH_LENGTH, DILITHIUM_D_VALUE, DILITHIUM_N_VALUE, DILITHIUM_Q_VALUE, DILITHIUM_SEED_LENGTH, DILITHIUM_T1_BITS};
+use crate::algorithms::dilithium::galois_field::GF8380417;
+use crate::algorithms::dilithium::matrix::MatrixRQ;
+use crate::algorithms::dilithium::ntt::NTT;
+use crate::algorithms::dilithium::polynomial::PolyRQ;
+use crate::algorithms::dilithium::rounding::{centered, decompose, make_hint, power_2_round, use_hint};
+use crate::algorithms::dilithium::vector::VectorRQ;
+use crate::algorithms::utils::hash::{shake_128, shake_256};
+use crate::CryptumResult;
+use crate::errors::CryptumError;
+
+const SHAKE_128_RATE: usize = 168;
+const SHAKE_256_RATE: usize = 136;
+
+pub trait Dilithium {
+    fn keygen(&self, seed: ByteArray) -> CryptumResult<(ByteArray, ByteArray)>;
+    fn sign(&self, private_key: ByteArray, message: ByteArray) -> CryptumResult<ByteArray>;
+    fn verify(&self, public_key: ByteArray, message: ByteArray, signature: ByteArray) -> CryptumResult<bool>;
+    fn get_signature_length(&self) -> usize;
+}
+
+
+pub struct DilithiumCore<const V: usize> {
+    k: usize,
+    l: usize,
+    eta: usize,
+    tau: usize,
+    beta: usize,
+    gamma_1: usize,
+    gamma_2: usize,
+    omega: usize
+}
+
+
+impl DilithiumCore<2> {
+    pub fn init() -> Self {
+        Self {
+            k: 4,
+            l: 4,
+            eta: 2,
+            tau: 39,
+            beta: 78,
+            gamma_1: 1 << 17,
+            gamma_2: (DILITHIUM_Q_VALUE - 1) / 88,
+            omega: 80
+        }
+    }
+}
+
+impl DilithiumCore<3> {
+    pub fn init() -> Self {
+        Self {
+            k: 6,
+            l: 5,
+            eta: 4,
+            tau: 49,
+            beta: 196,
+            gamma_1: 1 << 19,
+            gamma_2: (DILITHIUM_Q_VALUE - 1) / 32,
+            omega: 55
+        }
+    }
+}
+
+impl DilithiumCore<5> {
+    pub fn init() -> Self {
+        Self {
+            k: 8,
+            l: 7,
+            eta: 2,
+            tau: 60,
+            beta: 120,
+            gamma_1: 1 << 19,
+            gamma_2: (DILITHIUM_Q_VALUE - 1) / 32,
+            omega: 75
+        }
+    }
+}
+
+
+impl <const V: usize> DilithiumCore<V> {
+    pub fn get_public_key_length(&self) -> usize {
+        DILITHIUM_SEED_LENGTH + self.k * DILITHIUM_N_VALUE * DILITHIUM_T1_BITS / 8
+    }
+
+    pub fn get_private_key_length(&self) -> usize {
+        let eta_bytes = DILITHIUM_N_VALUE * self.eta_bits() / 8;
+        let t0_bytes = DILITHIUM_N_VALUE * DILITHIUM_D_VALUE / 8;
+
+        3 * DILITHIUM_SEED_LENGTH + (self.l + self.k) * eta_bytes + self.k * t0_bytes
+    }
+
+    /// Number of bits used to pack the coefficients of s_1 and s_2
+    fn eta_bits(&self) -> usize {
+        if self.eta == 2 { 3 } else { 4 }
+    }
+
+    /// Number of bits used to pack the coefficients of z
+    fn z_bits(&self) -> usize {
+        if self.gamma_1 == 1 << 17 { 18 } else { 20 }
+    }
+
+    /// Number of bits used to pack the coefficients of w_1
+    fn w1_bits(&self) -> usize {
+        if self.gamma_2 == (DILITHIUM_Q_VALUE - 1) / 88 { 6 } else { 4 }
+    }
+
+    /// This function corresponds to the H function (p.12 of the article) which is a wrapper around
+    /// the shake256 extendable-output function.
+    fn h(&self, data: &[&ByteArray], length: usize) -> ByteArray {
+        shake_256(ByteArray::concat(data).get_bytes(), length).into()
+    }
+
+    /// This function corresponds to the ExpandA function. Each polynomial of the matrix is sampled
+    /// in the NTT domain by rejection sampling on the output of shake128(rho || j || i).
+    fn expand_a(&self, rho: &ByteArray) -> MatrixRQ {
+        let mut matrix_data = Vec::with_capacity(self.k);
+
+        for i in 0..self.k {
+            let mut row_data = Vec::with_capacity(self.l);
+
+            for j in 0..self.l {
+                let seed = ByteArray::concat(&[rho, &(j as u8).into(), &(i as u8).into()]);
+                row_data.push(Self::sample_uniform(&seed));
+            }
+            matrix_data.push(row_data);
+        }
+
+        matrix_data.into()
+    }
+
+    fn sample_uniform(seed: &ByteArray) -> PolyRQ {
+        let mut stream_length = 5 * SHAKE_128_RATE;
+
+        loop {
+            let bytes_stream = shake_128(seed.get_bytes(), stream_length);
+            let mut coefficients = [GF8380417::zero(); DILITHIUM_N_VALUE];
+            let mut j = 0;
+
+            for chunk in bytes_stream.chunks_exact(3) {
+                if j == DILITHIUM_N_VALUE {
+                    break;
+                }
+                let value = (chunk[0] as usize | (chunk[1] as usize) << 8 | (chunk[2] as usize) << 16) & 0x7FFFFF;
+
+                if value < DILITHIUM_Q_VALUE {
+                    coefficients[j] = value.into();
+                    j += 1;
+                }
+            }
+
+            if j == DILITHIUM_N_VALUE {
+                return coefficients.into();
+            }
+            // The stream was too short, as an XOF output is a prefix of any longer output we
+            // simply ask for more bytes.
+            stream_length *= 2;
+        }
+    }
+
+    /// This function samples a polynomial whose coefficients lie in [-eta, eta] by rejection
+    /// sampling on the output of shake256(rho' || nonce).
+    fn sample_eta(&self, rho_prime: &ByteArray, nonce: u16) -> PolyRQ {
+        let seed = ByteArray::concat(&[rho_prime, &nonce.to_le_bytes().as_slice().into()]);
+        let mut stream_length = 2 * SHAKE_256_RATE;
+        let bound = if self.eta == 2 { 15 } else { 9 };
+
+        loop {
+            let bytes_stream = shake_256(seed.get_bytes(), stream_length);
+            let mut coefficients = [GF8380417::zero(); DILITHIUM_N_VALUE];
+            let mut j = 0;
+
+            for &byte in bytes_stream.iter() {
+                for value in [byte & 0x0F, byte >> 4] {
+                    if value < bound && j < DILITHIUM_N_VALUE {
+                        coefficients[j] = GF8380417::from(self.eta as i32 - (value % (2 * self.eta as u8 + 1)) as i32);
+                        j += 1;
+                    }
+                }
+            }
+
+            if j == DILITHIUM_N_VALUE {
+                return coefficients.into();
+            }
+            stream_length *= 2;
+        }
+    }
+
+    /// This function corresponds to the ExpandS function.
+    fn expand_s(&self, rho_prime: &ByteArray) -> (VectorRQ, VectorRQ) {
+        let s_1: Vec<PolyRQ> = (0..self.l).map(|i| self.sample_eta(rho_prime, i as u16)).collect();
+        let s_2: Vec<PolyRQ> = (0..self.k).map(|i| self.sample_eta(rho_prime, (self.l + i) as u16)).collect();
+
+        (s_1.into(), s_2.into())
+    }
+
+    /// This function corresponds to the ExpandMask function. It returns a vector of polynomials
+    /// whose coefficients lie in [-gamma_1 + 1, gamma_1].
+    fn expand_mask(&self, rho_prime: &ByteArray, kappa: usize) -> VectorRQ {
+        let mut polynomials = Vec::with_capacity(self.l);
+
+        for i in 0..self.l {
+            let nonce = (self.l * kappa + i) as u16;
+            let seed = ByteArray::concat(&[rho_prime, &nonce.to_le_bytes().as_slice().into()]);
+            let bytes: ByteArray = shake_256(seed.get_bytes(), DILITHIUM_N_VALUE * self.z_bits() / 8).into();
+
+            polynomials.push(PolyRQ::decode_with_bound(&bytes, self.z_bits(), self.gamma_1));
+        }
+
+        polynomials.into()
+    }
+
+    /// This function corresponds to the SampleInBall function. It returns a polynomial with
+    /// exactly tau coefficients equal to 1 or -1, the other being equal to 0.
+    fn sample_in_ball(&self, seed: &ByteArray) -> PolyRQ {
+        let mut stream_length = SHAKE_256_RATE;
+
+        'sampling: loop {
+            let bytes_stream = shake_256(seed.get_bytes(), stream_length);
+            stream_length *= 2;
+
+            let signs = u64::from_le_bytes(bytes_stream[0..8].try_into().unwrap());
+            let mut coefficients = [GF8380417::zero(); DILITHIUM_N_VALUE];
+            let mut position = 8;
+
+            for (index, i) in (DILITHIUM_N_VALUE - self.tau..DILITHIUM_N_VALUE).enumerate() {
+                let j = loop {
+                    match bytes_stream.get(position) {
+                        None => continue 'sampling,
+                        Some(&byte) => {
+                            position += 1;
+                            if byte as usize <= i {
+                                break byte as usize;
+                            }
+                        }
+                    }
+                };
+
+                coefficients[i] = coefficients[j];
+                coefficients[j] = if (signs >> index) & 1 == 0 {
+                    GF8380417::from(1_usize)
+                } else {
+                    GF8380417::from(-1)
+                };
+            }
+
+            return coefficients.into();
+        }
+    }
+
+    fn encode_hints(&self, hints: &[Vec<bool>]) -> ByteArray {
+        let mut bytes = vec![0u8; self.omega + self.k];
+        let mut index = 0;
+
+        for (i, poly_hints) in hints.iter().enumerate() {
+            for (j, &hint) in poly_hints.iter().enumerate() {
+                if hint {
+                    bytes[index] = j as u8;
+                    index += 1;
+                }
+            }
+            bytes[self.omega + i] = index as u8;
+        }
+
+        bytes.into()
+    }
+
+    /// This function decodes the hints of a signature. It returns None whenever the encoding is
+    /// malformed so that a signature cannot be altered without being rejected.
+    fn decode_hints(&self, bytes: &ByteArray) -> Option<Vec<Vec<bool>>> {
+        let bytes = bytes.get_bytes();
+        let mut hints = vec![vec![false; DILITHIUM_N_VALUE]; self.k];
+        let mut index = 0;
+
+        for (i, poly_hints) in hints.iter_mut().enumerate() {
+            let limit = bytes[self.omega + i] as usize;
+
+            if limit < index || limit > self.omega {
+                return None;
+            }
+
+            for position in index..limit {
+                // Positions should be stored in strictly increasing order
+                if position > index && bytes[position] <= bytes[position - 1] {
+                    return None;
+                }
+                poly_hints[bytes[position] as usize] = true;
+            }
+            index = limit;
+        }
+
+        // The remaining bytes should be equal to 0
+        if bytes[index..self.omega].iter().any(|&byte| byte != 0) {
+            return None;
+        }
+
+        Some(hints)
+    }
+
+    fn w1_encode(&self, w_1: &[Vec<usize>]) -> ByteArray {
+        let polynomials: Vec<PolyRQ> = w_1.iter().map(|coefficients| {
+            let mut poly = [GF8380417::zero(); DILITHIUM_N_VALUE];
+            for (coefficient, &value) in poly.iter_mut().zip(coefficients.iter()) {
+                *coefficient = value.into();
+            }
+            PolyRQ::from(poly)
+        }).collect();
+
+        VectorRQ::from(polynomials).encode(self.w1_bits())
+    }
+
+    fn compute_mu(&self, tr: &ByteArray, message: &ByteArray) -> ByteArray {
+        self.h(&[tr, message], DILITHIUM_CRH_LENGTH)
+    }
+}
+
+impl <const V: usize>Dilithium for DilithiumCore<V> {
+    /// This function corresponds to the KeyGen function (Figure 4). As for Kyber, we take the
+    /// seed as an input so as to be able to test the function.
+    ///
+    /// Input:
+    ///     seed: A 32-bytes array
+    /// Output:
+    ///     - An array of bytes corresponding to the public key
+    ///     - An array of bytes corresponding to the private key
+    fn keygen(&self, seed: ByteArray) -> CryptumResult<(ByteArray, ByteArray)> {
+        // Checking length of seed
+        if seed.length() != DILITHIUM_SEED_LENGTH {
+            return Err(CryptumError::InvalidSeedLength { expected: DILITHIUM_SEED_LENGTH, found: seed.length() });
+        }
+
+        let expanded_seed = self.h(&[&seed], 2 * DILITHIUM_SEED_LENGTH + DILITHIUM_CRH_LENGTH);
+        let (rho, remaining) = expanded_seed.split_at(DILITHIUM_SEED_LENGTH);
+        let (rho_prime, key) = remaining.split_at(DILITHIUM_CRH_LENGTH);
+
+        let a_hat = self.expand_a(&rho);
+        let (s_1, s_2) = self.expand_s(&rho_prime);
+
+        let t = a_hat.multiply_vec(&s_1.clone().to_ntt()).inverse_ntt() + s_2.clone();
+
+        let mut t_1 = Vec::with_capacity(self.k);
+        let mut t_0 = Vec::with_capacity(self.k);
+
+        for i in 0..self.k {
+            let mut high_coefficients = [GF8380417::zero(); DILITHIUM_N_VALUE];
+            let mut low_coefficients = [GF8380417::zero(); DILITHIUM_N_VALUE];
+
+            for (j, coefficient) in t[i].get_coefficients().iter().enumerate() {
+                (high_coefficients[j], low_coefficients[j]) = power_2_round(*coefficient);
+            }
+            t_1.push(PolyRQ::from(high_coefficients));
+            t_0.push(PolyRQ::from(low_coefficients));
+        }
+
+        let public_key = ByteArray::concat(&[&rho, &VectorRQ::from(t_1).encode(DILITHIUM_T1_BITS)]);
+        let tr = self.h(&[&public_key], DILITHIUM_SEED_LENGTH);
+
+        let private_key = ByteArray::concat(&[
+            &rho,
+            &key,
+            &tr,
+            &s_1.encode_with_bound(self.eta_bits(), self.eta),
+            &s_2.encode_with_bound(self.eta_bits(), self.eta),
+            &VectorRQ::from(t_0).encode_with_bound(DILITHIUM_D_VALUE, 1 << (DILITHIUM_D_VALUE - 1))
+        ]);
+
+        Ok((public_key, private_key))
+    }
+
+    /// This function corresponds to the Sign function (Figure 4). The signature is deterministic
+    /// as rho' is derived from the key and the message, like in the reference implementation.
+    ///
+    /// Input:
+    ///     private_key: An array of bytes corresponding to a private key generated by keygen
+    ///     message: The message to sign
+    /// Output:
+    ///     An array of bytes corresponding to the signature
+    fn sign(&self, private_key: ByteArray, message: ByteArray) -> CryptumResult<ByteArray> {
+        // Checking length of private key
+        let expected_private_key_length = self.get_private_key_length();
+        if private_key.length() != expected_private_key_length {
+            return Err(CryptumError::InvalidKeyLength {
+                expected: expected_private_key_length,
+                found: private_key.length()
+            });
+        }
+
+        let s_length = DILITHIUM_N_VALUE * self.eta_bits() / 8;
+
+        let (rho, remaining) = private_key.split_at(DILITHIUM_SEED_LENGTH);
+        let (key, remaining) = remaining.split_at(DILITHIUM_SEED_LENGTH);
+        let (tr, remaining) = remaining.split_at(DILITHIUM_SEED_LENGTH);
+        let (s_1_bytes, remaining) = remaining.split_at(self.l * s_length);
+        let (s_2_bytes, t_0_bytes) = remaining.split_at(self.k * s_length);
+
+        let s_1_hat = VectorRQ::decode_with_bound(&s_1_bytes, self.eta_bits(), self.eta).to_ntt();
+        let s_2_hat = VectorRQ::decode_with_bound(&s_2_bytes, self.eta_bits(), self.eta).to_ntt();
+        let t_0_hat = VectorRQ::decode_with_bound(
+            &t_0_bytes,
+            DILITHIUM_D_VALUE,
+            1 << (DILITHIUM_D_VALUE - 1)
+        ).to_ntt();
+
+        let a_hat = self.expand_a(&rho);
+        let mu = self.compute_mu(&tr, &message);
+        let rho_prime = self.h(&[&key, &mu], DILITHIUM_CRH_LENGTH);
+
+        let mut kappa = 0;
+
+        loop {
+            let y = self.expand_mask(&rho_prime, kappa);
+            kappa += 1;
+
+            let w = a_hat.multiply_vec(&y.clone().to_ntt()).inverse_ntt();
+
+            let mut w_1 = Vec::with_capacity(self.k);
+            let mut w_0 = Vec::with_capacity(self.k);
+
+            for i in 0..self.k {
+                let (high, low): (Vec<usize>, Vec<i64>) = w[i].get_coefficients()
+                    .iter()
+                    .map(|&coefficient| decompose(coefficient, self.gamma_2))
+                    .unzip();
+                w_1.push(high);
+                w_0.push(low);
+            }
+
+            let c_tilde = self.h(&[&mu, &self.w1_encode(&w_1)], DILITHIUM_SEED_LENGTH);
+            let c_hat = self.sample_in_ball(&c_tilde).to_ntt();
+
+            let z = y + s_1_hat.scale_ntt(&c_hat).inverse_ntt();
+            if z.infinity_norm() >= self.gamma_1 - self.beta {
+                continue;
+            }
+
+            let c_s_2 = s_2_hat.scale_ntt(&c_hat).inverse_ntt();
+            let c_t_0 = t_0_hat.scale_ntt(&c_hat).inverse_ntt();
+
+            if c_t_0.infinity_norm() >= self.gamma_2 {
+                continue;
+            }
+
+            let mut is_valid = true;
+            let mut hints = Vec::with_capacity(self.k);
+            let mut hints_count = 0;
+
+            for i in 0..self.k {
+                let mut poly_hints = vec![false; DILITHIUM_N_VALUE];
+
+                for j in 0..DILITHIUM_N_VALUE {
+                    let r_0 = w_0[i][j] - centered(c_s_2[i][j]);
+
+                    if r_0.unsigned_abs() as usize >= self.gamma_2 - self.beta {
+                        is_valid = false;
+                    }
+
+                    poly_hints[j] = make_hint(r_0 + centered(c_t_0[i][j]), w_1[i][j], self.gamma_2);
+                    hints_count += poly_hints[j] as usize;
+                }
+                hints.push(poly_hints);
+            }
+
+            if !is_valid || hints_count > self.omega {
+                continue;
+            }
+
+            return Ok(ByteArray::concat(&[
+                &c_tilde,
+                &z.encode_with_bound(self.z_bits(), self.gamma_1),
+                &self.encode_hints(&hints)
+            ]));
+        }
+    }
+
+    /// This function corresponds to the Verify function (Figure 4).
+    ///
+    /// Input:
+    ///     public_key: An array of bytes corresponding to a public key generated by keygen
+    ///     message: The message that has been signed
+    ///     signature: The signature to check
+    /// Output:
+    ///     true if the signature is valid, false otherwise. An error is returned when the public key
+    ///     does not have the length of the parameter set.
+    fn verify(&self, public_key: ByteArray, message: ByteArray, signature: ByteArray) -> CryptumResult<bool> {
+        // Checking length of public key
+        let expected_public_key_length = self.get_public_key_length();
+        if public_key.length() != expected_public_key_length {
+            return Err(CryptumError::InvalidKeyLength {
+                expected: expected_public_key_length,
+                found: public_key.length()
+            });
+        }
+
+        if signature.length() != self.get_signature_length() {
+            return Ok(false);
+        }
+
+        let (rho, t_1_bytes) = public_key.split_at(DILITHIUM_SEED_LENGTH);
+        let (c_tilde, remaining) = signature.split_at(DILITHIUM_SEED_LENGTH);
+        let (z_bytes, hints_bytes) = remaining.split_at(self.l * DILITHIUM_N_VALUE * self.z_bits() / 8);
+
+        let z = VectorRQ::decode_with_bound(&z_bytes, self.z_bits(), self.gamma_1);
+        let hints = match self.decode_hints(&hints_bytes) {
+            Some(hints) => hints,
+            None => return Ok(false)
+        };
+
+        if z.infinity_norm() >= self.gamma_1 - self.beta {
+            return Ok(false);
+        }
+
+        let t_1_shifted = VectorRQ::decode(&t_1_bytes, DILITHIUM_T1_BITS);
+        let t_1_shifted: Vec<PolyRQ> = (0..self.k)
+            .map(|i| t_1_shifted[i].map(|coefficient| coefficient.mul(&GF8380417::from(1_usize << DILITHIUM_D_VALUE))))
+            .collect();
+
+        let a_hat = self.expand_a(&rho);
+        let tr = self.h(&[&public_key], DILITHIUM_SEED_LENGTH);
+        let mu = self.compute_mu(&tr, &message);
+        let c_hat = self.sample_in_ball(&c_tilde).to_ntt();
+
+        let w_approx = a_hat.multiply_vec(&z.to_ntt())
+            .sub(&VectorRQ::from(t_1_shifted).to_ntt().scale_ntt(&c_hat))
+            .inverse_ntt();
+
+        let w_1: Vec<Vec<usize>> = (0..self.k).map(|i| {
+            w_approx[i].get_coefficients()
+                .iter()
+                .zip(hints[i].iter())
+                .map(|(&coefficient, &hint)| use_hint(hint, coefficient, self.gamma_2))
+                .collect()
+        }).collect();
+
+        Ok(c_tilde == self.h(&[&mu, &self.w1_encode(&w_1)], DILITHIUM_SEED_LENGTH))
+    }
+
+    fn get_signature_length(&self) -> usize {
+        DILITHIUM_SEED_LENGTH + self.l * DILITHIUM_N_VALUE * self.z_bits() / 8 + self.omega + self.k
+    }
+}
+
+
+pub type Dilithium2 = DilithiumCore<2>;
+pub type Dilithium3 = DilithiumCore<3>;
+pub type Dilithium5 = DilithiumCore<5>;
+
+
+#[cfg(test)]
+mod tests {
+    use crate::algorithms::algebraic::galois_field::GaloisField;
+    use crate::algorithms::byte_array::ByteArray;
+    use crate::algorithms::dilithium::constants::DILITHIUM_SEED_LENGTH;
+    use crate::algorithms::dilithium::rounding::centered;
+    use crate::algorithms::dilithium::sign::{Dilithium, Dilithium2, Dilithium3, Dilithium5};
+    use crate::errors::CryptumError;
+
+    #[test]
+    fn test_keygen_length() {
+        let dilithium = Dilithium2::init();
+        let (public_key, private_key) = dilithium.keygen(ByteArray::random(DILITHIUM_SEED_LENGTH)).unwrap();
+
+        assert_eq!(public_key.length(), 1312);
+        assert_eq!(private_key.length(), 2528);
+    }
+
+    #[test]
+    fn test_lengths_of_each_version() {
+        let dilithium_3 = Dilithium3::init();
+        assert_eq!(dilithium_3.get_public_key_length(), 1952);
+        assert_eq!(dilithium_3.get_private_key_length(), 4000);
+        assert_eq!(dilithium_3.get_signature_length(), 3293);
+
+        let dilithium_5 = Dilithium5::init();
+        assert_eq!(dilithium_5.get_public_key_length(), 2592);
+        assert_eq!(dilithium_5.get_private_key_length(), 4864);
+        assert_eq!(dilithium_5.get_signature_length(), 4595);
+    }
+
+    #[test]
+    fn test_keygen_invalid_length_seed() {
+        let dilithium = Dilithium2::init();
+        let result = dilithium.keygen(ByteArray::random(2));
+
+        assert!(matches!(result, Err(CryptumError::InvalidSeedLength { expected: 32, found: 2 })));
+    }
+
+    #[test]
+    fn test_sign_invalid_length_private_key() {
+        let dilithium = Dilithium2::init();
+        let result = dilithium.sign(ByteArray::random(2527), ByteArray::random(10));
+
+        assert!(matches!(result, Err(CryptumError::InvalidKeyLength { expected: 2528, found: 2527 })));
+    }
+
+    #[test]
+    fn test_verify_invalid_length_public_key() {
+        let dilithium = Dilithium2::init();
+        let (public_key, private_key) = dilithium.keygen(ByteArray::random(DILITHIUM_SEED_LENGTH)).unwrap();
+        let message = ByteArray::from(b"Telecom PARIS".as_slice());
+        let signature = dilithium.sign(private_key, message.clone()).unwrap();
+
+        let (truncated_public_key, _) = public_key.split_at(1000);
+        let result = dilithium.verify(truncated_public_key, message, signature);
+
+        assert!(matches!(result, Err(CryptumError::InvalidKeyLength { expected: 1312, found: 1000 })));
+    }
+
+    #[test]
+    fn test_sample_in_ball() {
+        let dilithium = Dilithium2::init();
+        let poly = dilithium.sample_in_ball(&ByteArray::random(DILITHIUM_SEED_LENGTH));
+
+        let weight = poly.get_coefficients().iter().filter(|coefficient| !coefficient.is_zero()).count();
+
+        assert_eq!(weight, 39);
+        assert_eq!(poly.infinity_norm(), 1);
+    }
+
+    #[test]
+    fn test_expand_s() {
+        let dilithium = Dilithium3::init();
+        let (s_1, s_2) = dilithium.expand_s(&ByteArray::random(64));
+
+        assert_eq!(s_1.get_n(), 5);
+        assert_eq!(s_2.get_n(), 6);
+        assert!(s_1.infinity_norm() <= 4);
+        assert!(s_2.infinity_norm() <= 4);
+    }
+
+    #[test]
+    fn test_expand_mask() {
+        let dilithium = Dilithium2::init();
+        let y = dilithium.expand_mask(&ByteArray::random(64), 0);
+
+        for i in 0..y.get_n() {
+            for &coefficient in y[i].get_coefficients() {
+                let value = centered(coefficient);
+                assert!(value > -(1 << 17) && value <= 1 << 17)
+            }
+        }
+    }
+
+    #[test]
+    fn test_sign_is_deterministic() {
+        let dilithium = Dilithium2::init();
+        let (_, private_key) = dilithium.keygen(ByteArray::from([0u8; 32].as_slice())).unwrap();
+        let message = ByteArray::from(b"Telecom PARIS".as_slice());
+
+        let signature = dilithium.sign(private_key.clone(), message.clone()).unwrap();
+
+        assert_eq!(signature.length(), 2420);
+        assert_eq!(signature, dilithium.sign(private_key, message).unwrap());
+    }
+
+    #[test]
+    fn test_sign_verify() {
+        let dilithium = Dilithium2::init();
+        let (public_key, private_key) = dilithium.keygen(ByteArray::random(DILITHIUM_SEED_LENGTH)).unwrap();
+        let message = ByteArray::from(b"Telecom PARIS".as_slice());
+
+        let signature = dilithium.sign(private_key, message.clone()).unwrap();
+
+        assert!(dilithium.verify(public_key, message, signature).unwrap());
+    }
+
+    #[test]
+    fn test_sign_verify_each_version() {
+        let message = ByteArray::random(100);
+
+        let dilithium_3 = Dilithium3::init();
+        let (public_key, private_key) = dilithium_3.keygen(ByteArray::random(DILITHIUM_SEED_LENGTH)).unwrap();
+        let signature = dilithium_3.sign(private_key, message.clone()).unwrap();
+        assert!(dilithium_3.verify(public_key, message.clone(), signature).unwrap());
+
+        let dilithium_5 = Dilithium5::init();
+        let (public_key, private_key) = dilithium_5.keygen(ByteArray::random(DILITHIUM_SEED_LENGTH)).unwrap();
+        let signature = dilithium_5.sign(private_key, message.clone()).unwrap();
+        assert!(dilithium_5.verify(public_key, message, signature).unwrap());
+    }
+
+    #[test]
+    fn test_verify_fails_with_another_message() {
+        let dilithium = Dilithium2::init();
+        let (public_key, private_key) = dilithium.keygen(ByteArray::random(DILITHIUM_SEED_LENGTH)).unwrap();
+
+        let signature = dilithium.sign(private_key, ByteArray::from(b"Telecom PARIS".as_slice())).unwrap();
+
+        assert!(!dilithium.verify(public_key, ByteArray::from(b"Telecom Paris".as_slice()), signature).unwrap());
+    }
+
+    #[test]
+    fn test_verify_fails_with_altered_signature() {
+        let dilithium = Dilithium2::init();
+        let (public_key, private_key) = dilithium.keygen(ByteArray::random(DILITHIUM_SEED_LENGTH)).unwrap();
+        let message = ByteArray::from(b"Telecom PARIS".as_slice());
+
+        let signature = dilithium.sign(private_key, message.clone()).unwrap();
+        let mut signature_bytes = signature.get_bytes().to_vec();
+        signature_bytes[40] ^= 1;
+
+        assert!(!dilithium.verify(public_key, message, signature_bytes.into()).unwrap());
+    }
+
+    #[test]
+    fn test_verify_fails_with_invalid_signature_length() {
+        let dilithium = Dilithium2::init();
+        let (public_key, _) = dilithium.keygen(ByteArray::random(DILITHIUM_SEED_LENGTH)).unwrap();
+
+        assert!(!dilithium.verify(public_key, ByteArray::random(10), ByteArray::random(10)).unwrap());
+    }
+}
@@ -0,0 +1,115 @@
+use crate::algorithms::algebraic::polynomial::RingElement;
+use crate::algorithms::algebraic::vector::Vector;
+use crate::algorithms::byte_array::ByteArray;
+use crate::algorithms::dilithium::ntt::NTT;
+use crate::algorithms::dilithium::polynomial::PolyRQ;
+
+pub type VectorRQ = Vector<PolyRQ>;
+
+impl NTT for VectorRQ {
+    fn inverse_ntt(self) -> Self {
+        let mut polynomials = Vec::with_capacity(self.get_n());
+
+        for i in 0..self.get_n() {
+            polynomials.push(self[i].clone().inverse_ntt());
+        }
+
+        polynomials.into()
+    }
+
+    fn to_ntt(self) -> Self {
+        let mut polynomials = Vec::with_capacity(self.get_n());
+
+        for i in 0..self.get_n() {
+            polynomials.push(self[i].clone().to_ntt());
+        }
+
+        polynomials.into()
+    }
+}
+
+impl VectorRQ {
+    pub fn dot_ntt(&self, other: &Self) -> PolyRQ {
+        // Checking that both vectors have the same number of elements
+        if self.get_n() != other.get_n() {
+            panic!("Vectors don't have the same number of elements")
+        }
+
+        let mut poly = PolyRQ::zero();
+
+        for i in 0..self.get_n() {
+            poly = poly.add(&self[i].multiply_ntt(&other[i]));
+        }
+
+        poly
+    }
+
+    /// This function multiplies each polynomial of the vector by the given polynomial. Both of
+    /// them should be in the NTT domain.
+    pub fn scale_ntt(&self, poly: &PolyRQ) -> Self {
+        let mut polynomials = Vec::with_capacity(self.get_n());
+
+        for i in 0..self.get_n() {
+            polynomials.push(self[i].multiply_ntt(poly));
+        }
+
+        polynomials.into()
+    }
+
+    pub fn sub(&self, other: &Self) -> Self {
+        // Checking that both vectors have the same number of elements
+        if self.get_n() != other.get_n() {
+            panic!("Vectors don't have the same number of elements")
+        }
+
+        let mut polynomials = Vec::with_capacity(self.get_n());
+
+        for i in 0..self.get_n() {
+            polynomials.push(self[i].sub(&other[i]));
+        }
+
+        polynomials.into()
+    }
+
+    pub fn infinity_norm(&self) -> usize {
+        (0..self.get_n()).map(|i| self[i].infinity_norm()).max().unwrap_or(0)
+    }
+
+    pub fn encode(&self, l_value: usize) -> ByteArray {
+        let mut bytes = ByteArray::empty();
+
+        for i in 0..self.get_n() {
+            bytes = ByteArray::concat(&[&bytes, &self[i].encode(l_value)]);
+        }
+
+        bytes
+    }
+
+    pub fn encode_with_bound(&self, l_value: usize, bound: usize) -> ByteArray {
+        let mut bytes = ByteArray::empty();
+
+        for i in 0..self.get_n() {
+            bytes = ByteArray::concat(&[&bytes, &self[i].encode_with_bound(l_value, bound)]);
+        }
+
+        bytes
+    }
+
+    pub fn decode(bytes: &ByteArray, l_value: usize) -> Self {
+        let polynomials = bytes.get_bytes()
+            .chunks_exact(32 * l_value)
+            .map(|chunk| PolyRQ::decode(&chunk.into(), l_value))
+            .collect::<Vec<PolyRQ>>();
+
+        polynomials.into()
+    }
+
+    pub fn decode_with_bound(bytes: &ByteArray, l_value: usize, bound: usize) -> Self {
+        let polynomials = bytes.get_bytes()
+            .chunks_exact(32 * l_value)
+            .map(|chunk| PolyRQ::decode_with_bound(&chunk.into(), l_value, bound))
+            .collect::<Vec<PolyRQ>>();
+
+        polynomials.into()
+    }
+}
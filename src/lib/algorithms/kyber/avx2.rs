@@ -0,0 +1,555 @@
+//! AVX2 backend of the Kyber arithmetic.
+//!
+//! Every function of this module computes exactly the same output as its scalar counterpart, the
+//! coefficients being processed 16 at a time in the 16-bits lanes of a 256-bits register. The
+//! functions must only be called when `is_supported` returns true.
+
+use std::arch::x86_64::*;
+use crate::algorithms::kyber::constants::KYBER_N_VALUE;
+use crate::algorithms::kyber::cpapke::{cbd_eta_scalar, rejection_sample as scalar_rejection_sample};
+use crate::algorithms::kyber::galois_field::{GF3329, MONTGOMERY_R_SQUARE, Q, Q_INVERSE};
+use crate::algorithms::kyber::ntt::{br7, HALF_INVERSE_ZETAS_MONTGOMERY, INVERSE_OF_2_MONTGOMERY, ZETAS_256, ZETAS_MONTGOMERY};
+
+/// 2^16 mod q, i.e. the Montgomery form of 1
+const MONTGOMERY_ONE: i16 = 2285;
+
+/// The zetas of the layers of size 8, 4 and 2 expanded so that the zeta of every coefficient of
+/// the first half of the butterflies can be loaded directly (see `split_blocks`).
+const ZETAS_LAYER_8: [i16; 128] = expand_zetas(&ZETAS_MONTGOMERY, 8);
+const ZETAS_LAYER_4: [i16; 128] = expand_zetas(&ZETAS_MONTGOMERY, 4);
+const ZETAS_LAYER_2: [i16; 128] = expand_zetas(&ZETAS_MONTGOMERY, 2);
+const HALF_INVERSE_ZETAS_LAYER_8: [i16; 128] = expand_zetas(&HALF_INVERSE_ZETAS_MONTGOMERY, 8);
+const HALF_INVERSE_ZETAS_LAYER_4: [i16; 128] = expand_zetas(&HALF_INVERSE_ZETAS_MONTGOMERY, 4);
+const HALF_INVERSE_ZETAS_LAYER_2: [i16; 128] = expand_zetas(&HALF_INVERSE_ZETAS_MONTGOMERY, 2);
+
+/// Montgomery form of the factors applied to the products a_0.b_0 and a_1.b_1 of every pair of
+/// coefficients by the base multiplication: 1 and zeta^(2.br7(i) + 1).
+const BASEMUL_FACTORS: [i16; KYBER_N_VALUE] = precompute_basemul_factors();
+
+/// Shuffle gathering 12 bytes of each 128-bits lane into 8 16-bits lanes containing the bytes
+/// (b_0, b_1), (b_1, b_2), ... of each group of 3 bytes.
+const PARSE_SHUFFLE: [i8; 32] = [
+    0, 1, 1, 2, 3, 4, 4, 5, 6, 7, 7, 8, 9, 10, 10, 11,
+    4, 5, 5, 6, 7, 8, 8, 9, 10, 11, 11, 12, 13, 14, 14, 15
+];
+
+/// Shuffle gathering 12 bytes of each 128-bits lane into 4 32-bits lanes containing 3 bytes each.
+const CBD3_SHUFFLE: [i8; 32] = [
+    0, 1, 2, -1, 3, 4, 5, -1, 6, 7, 8, -1, 9, 10, 11, -1,
+    4, 5, 6, -1, 7, 8, 9, -1, 10, 11, 12, -1, 13, 14, 15, -1
+];
+
+/// For every 8-bits mask, the shuffle moving the 16-bits lanes selected by the mask at the
+/// beginning of a 128-bits register.
+const PARSE_COMPRESS: [[u8; 16]; 256] = precompute_compress_shuffles();
+
+const fn expand_zetas(zetas: &[i16; 128], layer: usize) -> [i16; 128] {
+    // The blocks of a layer use the zetas starting at 128 / layer, and the first half of each
+    // block contains layer coefficients.
+    let mut expanded = [0; 128];
+    let mut i = 0;
+    while i < 128 {
+        expanded[i] = zetas[128 / layer + i / layer];
+        i += 1;
+    }
+    expanded
+}
+
+const fn precompute_basemul_factors() -> [i16; KYBER_N_VALUE] {
+    let mut factors = [0; KYBER_N_VALUE];
+    let mut i = 0;
+    while i < KYBER_N_VALUE / 2 {
+        let zeta = ZETAS_256[2 * br7(i as u8) as usize + 1];
+        factors[2 * i] = MONTGOMERY_ONE;
+        factors[2 * i + 1] = GF3329::new(zeta as u16).to_montgomery();
+        i += 1;
+    }
+    factors
+}
+
+const fn precompute_compress_shuffles() -> [[u8; 16]; 256] {
+    let mut shuffles = [[0x80; 16]; 256];
+    let mut mask = 0;
+    while mask < 256 {
+        let mut count = 0;
+        let mut lane = 0;
+        while lane < 8 {
+            if mask & (1 << lane) != 0 {
+                shuffles[mask][2 * count] = 2 * lane as u8;
+                shuffles[mask][2 * count + 1] = 2 * lane as u8 + 1;
+                count += 1;
+            }
+            lane += 1;
+        }
+        mask += 1;
+    }
+    shuffles
+}
+
+/// This function returns true when the CPU supports the AVX2 instructions.
+pub fn is_supported() -> bool {
+    is_x86_feature_detected!("avx2")
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn load(coefficients: &[GF3329]) -> __m256i {
+    assert!(coefficients.len() >= 16);
+    // GF3329 has the layout of a u16, so 16 elements fill a register
+    _mm256_loadu_si256(coefficients.as_ptr() as *const __m256i)
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn store(coefficients: &mut [GF3329], value: __m256i) {
+    assert!(coefficients.len() >= 16);
+    // Every lane stored by the functions of this module is a canonical representative in [0, q)
+    _mm256_storeu_si256(coefficients.as_mut_ptr() as *mut __m256i, value)
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn load_factors(factors: &[i16]) -> __m256i {
+    assert!(factors.len() >= 16);
+    _mm256_loadu_si256(factors.as_ptr() as *const __m256i)
+}
+
+/// This function maps every lane in (-q, q) to its canonical representative in [0, q).
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn normalize(value: __m256i) -> __m256i {
+    let q = _mm256_set1_epi16(Q);
+    _mm256_add_epi16(value, _mm256_and_si256(_mm256_srai_epi16::<15>(value), q))
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn add(a: __m256i, b: __m256i) -> __m256i {
+    // When a + b < q, the unsigned subtraction wraps around and the minimum is a + b
+    let q = _mm256_set1_epi16(Q);
+    let sum = _mm256_add_epi16(a, b);
+    _mm256_min_epu16(sum, _mm256_sub_epi16(sum, q))
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn sub(a: __m256i, b: __m256i) -> __m256i {
+    // When a >= b, a - b + q is above a - b and the minimum is a - b
+    let q = _mm256_set1_epi16(Q);
+    let difference = _mm256_sub_epi16(a, b);
+    _mm256_min_epu16(difference, _mm256_add_epi16(difference, q))
+}
+
+/// This function corresponds to the Montgomery multiplication of every lane of a by the matching
+/// lane of factors, the high and low halves of the products being computed separately.
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn mul_montgomery(a: __m256i, factors: __m256i) -> __m256i {
+    let q = _mm256_set1_epi16(Q);
+    let low = _mm256_mullo_epi16(a, factors);
+    let high = _mm256_mulhi_epi16(a, factors);
+    let t = _mm256_mullo_epi16(low, _mm256_set1_epi16(Q_INVERSE));
+    normalize(_mm256_sub_epi16(high, _mm256_mulhi_epi16(t, q)))
+}
+
+/// This function swaps the two 16-bits lanes of every 32-bits lane.
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn swap_pairs(value: __m256i) -> __m256i {
+    _mm256_or_si256(_mm256_slli_epi32::<16>(value), _mm256_srli_epi32::<16>(value))
+}
+
+/// This function splits 32 coefficients made of blocks of 2 * layer coefficients (layer being 8,
+/// 4 or 2) into the first halves and the second halves of the blocks, in the order of the blocks.
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn split_blocks(x: __m256i, y: __m256i, layer: usize) -> (__m256i, __m256i) {
+    let permutation = match layer {
+        8 => _mm256_setr_epi32(0, 1, 2, 3, 4, 5, 6, 7),
+        4 => _mm256_setr_epi32(0, 1, 4, 5, 2, 3, 6, 7),
+        _ => _mm256_setr_epi32(0, 2, 4, 6, 1, 3, 5, 7)
+    };
+    let x = _mm256_permutevar8x32_epi32(x, permutation);
+    let y = _mm256_permutevar8x32_epi32(y, permutation);
+    (_mm256_permute2x128_si256::<0x20>(x, y), _mm256_permute2x128_si256::<0x31>(x, y))
+}
+
+/// This function is the inverse of split_blocks.
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn merge_blocks(u: __m256i, v: __m256i, layer: usize) -> (__m256i, __m256i) {
+    let permutation = match layer {
+        8 => _mm256_setr_epi32(0, 1, 2, 3, 4, 5, 6, 7),
+        4 => _mm256_setr_epi32(0, 1, 4, 5, 2, 3, 6, 7),
+        _ => _mm256_setr_epi32(0, 4, 1, 5, 2, 6, 3, 7)
+    };
+    let x = _mm256_permute2x128_si256::<0x20>(u, v);
+    let y = _mm256_permute2x128_si256::<0x31>(u, v);
+    (_mm256_permutevar8x32_epi32(x, permutation), _mm256_permutevar8x32_epi32(y, permutation))
+}
+
+/// This function is the AVX2 version of ntt_scalar.
+///
+/// # Safety
+/// The CPU must support the AVX2 instructions.
+#[target_feature(enable = "avx2")]
+pub unsafe fn ntt(poly: &mut [GF3329; KYBER_N_VALUE]) {
+    let mut k = 1;
+    let mut layer = KYBER_N_VALUE / 2;
+
+    // The halves of the blocks of the largest layers fill whole registers
+    while layer >= 16 {
+        for block in poly.chunks_exact_mut(2 * layer) {
+            let zeta = _mm256_set1_epi16(ZETAS_MONTGOMERY[k]);
+            k += 1;
+
+            let (low, high) = block.split_at_mut(layer);
+            for (u, v) in low.chunks_exact_mut(16).zip(high.chunks_exact_mut(16)) {
+                let (a, b) = (load(u), load(v));
+                let t = mul_montgomery(b, zeta);
+
+                store(v, sub(a, t));
+                store(u, add(a, t));
+            }
+        }
+        layer /= 2;
+    }
+
+    for (layer, zetas) in [(8, &ZETAS_LAYER_8), (4, &ZETAS_LAYER_4), (2, &ZETAS_LAYER_2)] {
+        for (i, chunk) in poly.chunks_exact_mut(32).enumerate() {
+            let (x, y) = chunk.split_at_mut(16);
+            let (u, v) = split_blocks(load(x), load(y), layer);
+            let t = mul_montgomery(v, load_factors(&zetas[16 * i..]));
+
+            let (a, b) = merge_blocks(add(u, t), sub(u, t), layer);
+            store(x, a);
+            store(y, b);
+        }
+    }
+}
+
+/// This function is the AVX2 version of inverse_ntt_scalar.
+///
+/// # Safety
+/// The CPU must support the AVX2 instructions.
+#[target_feature(enable = "avx2")]
+pub unsafe fn inverse_ntt(poly: &mut [GF3329; KYBER_N_VALUE]) {
+    let half = _mm256_set1_epi16(INVERSE_OF_2_MONTGOMERY);
+
+    for (layer, zetas) in [(2, &HALF_INVERSE_ZETAS_LAYER_2), (4, &HALF_INVERSE_ZETAS_LAYER_4), (8, &HALF_INVERSE_ZETAS_LAYER_8)] {
+        for (i, chunk) in poly.chunks_exact_mut(32).enumerate() {
+            let (x, y) = chunk.split_at_mut(16);
+            let (u, v) = split_blocks(load(x), load(y), layer);
+
+            let u_plus_v = mul_montgomery(add(u, v), half);
+            let u_minus_v = mul_montgomery(sub(u, v), load_factors(&zetas[16 * i..]));
+
+            let (a, b) = merge_blocks(u_plus_v, u_minus_v, layer);
+            store(x, a);
+            store(y, b);
+        }
+    }
+
+    let mut first_k = KYBER_N_VALUE / 32;
+    let mut layer = 16;
+
+    while layer <= KYBER_N_VALUE / 2 {
+        for (k, block) in (first_k..).zip(poly.chunks_exact_mut(2 * layer)) {
+            let half_zeta_inverse = _mm256_set1_epi16(HALF_INVERSE_ZETAS_MONTGOMERY[k]);
+
+            let (low, high) = block.split_at_mut(layer);
+            for (u, v) in low.chunks_exact_mut(16).zip(high.chunks_exact_mut(16)) {
+                let (a, b) = (load(u), load(v));
+
+                store(u, mul_montgomery(add(a, b), half));
+                store(v, mul_montgomery(sub(a, b), half_zeta_inverse));
+            }
+        }
+        first_k /= 2;
+        layer *= 2;
+    }
+}
+
+/// This function is the AVX2 version of multiply_ntt_scalar. The lanes 2i and 2i + 1 of a
+/// register contain a pair of coefficients, so the products a_0.b_0 and a_1.b_1 are computed in
+/// one multiplication and the crossed products after swapping the coefficients of b.
+///
+/// # Safety
+/// The CPU must support the AVX2 instructions.
+#[target_feature(enable = "avx2")]
+pub unsafe fn multiply_ntt(a: &[GF3329; KYBER_N_VALUE], b: &[GF3329; KYBER_N_VALUE]) -> [GF3329; KYBER_N_VALUE] {
+    let mut coefficients = [GF3329::default(); KYBER_N_VALUE];
+    let r_square = _mm256_set1_epi16(MONTGOMERY_R_SQUARE as i16);
+
+    for (i, output) in coefficients.chunks_exact_mut(16).enumerate() {
+        let x = load(&a[16 * i..]);
+        let y = load(&b[16 * i..]);
+
+        // Every product is multiplied by 2^(-16), which is cancelled at the end
+        let products = mul_montgomery(mul_montgomery(x, y), load_factors(&BASEMUL_FACTORS[16 * i..]));
+        let crossed_products = mul_montgomery(x, swap_pairs(y));
+
+        let f_0_hat = add(products, swap_pairs(products));
+        let f_1_hat = add(crossed_products, swap_pairs(crossed_products));
+
+        let result = _mm256_blend_epi16::<0b10101010>(f_0_hat, f_1_hat);
+        store(output, mul_montgomery(result, r_square));
+    }
+
+    coefficients
+}
+
+/// This function computes a - b for every lane, a and b being the number of bits set among the
+/// bits 0 to eta - 1 and eta to 2.eta - 1 of the lane, for eta equal to 2.
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn cbd2_nibbles(nibbles: __m256i) -> __m256i {
+    let one = _mm256_set1_epi16(1);
+    let bit = |shift: i32| _mm256_and_si256(_mm256_srl_epi16(nibbles, _mm_cvtsi32_si128(shift)), one);
+
+    let a = _mm256_add_epi16(bit(0), bit(1));
+    let b = _mm256_add_epi16(bit(2), bit(3));
+    normalize(_mm256_sub_epi16(a, b))
+}
+
+/// This function is the AVX2 version of cbd_eta_scalar for eta equal to 2 or 3, other values
+/// being delegated to the scalar version.
+///
+/// # Safety
+/// The CPU must support the AVX2 instructions.
+#[target_feature(enable = "avx2")]
+pub unsafe fn cbd_eta(bytes_arr: &[u8], eta: u8) -> [GF3329; KYBER_N_VALUE] {
+    let mut coefficients = [GF3329::default(); KYBER_N_VALUE];
+
+    match eta {
+        2 if bytes_arr.len() == 128 => {
+            // Every byte contains two coefficients, one in each nibble
+            for (chunk, output) in bytes_arr.chunks_exact(16).zip(coefficients.chunks_exact_mut(32)) {
+                let bytes = _mm256_cvtepu8_epi16(_mm_loadu_si128(chunk.as_ptr() as *const __m128i));
+                let low = cbd2_nibbles(_mm256_and_si256(bytes, _mm256_set1_epi16(0xF)));
+                let high = cbd2_nibbles(_mm256_srli_epi16::<4>(bytes));
+
+                let first = _mm256_unpacklo_epi16(low, high);
+                let second = _mm256_unpackhi_epi16(low, high);
+                let (x, y) = output.split_at_mut(16);
+                store(x, _mm256_permute2x128_si256::<0x20>(first, second));
+                store(y, _mm256_permute2x128_si256::<0x31>(first, second));
+            }
+        }
+        3 if bytes_arr.len() == 192 => {
+            // Every group of 3 bytes contains 4 coefficients. The bytes are copied in a larger
+            // buffer because each iteration loads 32 bytes and only uses 24 of them.
+            let mut buffer = [0u8; 192 + 8];
+            buffer[..192].copy_from_slice(bytes_arr);
+            let shuffle = _mm256_loadu_si256(CBD3_SHUFFLE.as_ptr() as *const __m256i);
+            let bits = _mm256_set1_epi32(0x249249);
+            let seven = _mm256_set1_epi32(7);
+            let q = _mm256_set1_epi32(Q as i32);
+
+            for (i, output) in coefficients.chunks_exact_mut(32).enumerate() {
+                let bytes = _mm256_loadu_si256(buffer[24 * i..].as_ptr() as *const __m256i);
+                let groups = _mm256_shuffle_epi8(_mm256_permute4x64_epi64::<0b10_01_01_00>(bytes), shuffle);
+
+                // Each 3-bits field of counts contains the number of bits set in the same field
+                // of groups
+                let counts = _mm256_add_epi32(
+                    _mm256_and_si256(groups, bits),
+                    _mm256_add_epi32(
+                        _mm256_and_si256(_mm256_srli_epi32::<1>(groups), bits),
+                        _mm256_and_si256(_mm256_srli_epi32::<2>(groups), bits)
+                    )
+                );
+                let coefficient = |j: i32| {
+                    let a = _mm256_and_si256(_mm256_srl_epi32(counts, _mm_cvtsi32_si128(6 * j)), seven);
+                    let b = _mm256_and_si256(_mm256_srl_epi32(counts, _mm_cvtsi32_si128(6 * j + 3)), seven);
+                    let difference = _mm256_sub_epi32(_mm256_add_epi32(a, q), b);
+                    _mm256_min_epu32(difference, _mm256_sub_epi32(difference, q))
+                };
+
+                let even = _mm256_or_si256(coefficient(0), _mm256_slli_epi32::<16>(coefficient(1)));
+                let odd = _mm256_or_si256(coefficient(2), _mm256_slli_epi32::<16>(coefficient(3)));
+                let first = _mm256_unpacklo_epi32(even, odd);
+                let second = _mm256_unpackhi_epi32(even, odd);
+                let (x, y) = output.split_at_mut(16);
+                store(x, _mm256_permute2x128_si256::<0x20>(first, second));
+                store(y, _mm256_permute2x128_si256::<0x31>(first, second));
+            }
+        }
+        _ => coefficients = cbd_eta_scalar(bytes_arr, eta)
+    }
+
+    coefficients
+}
+
+/// This function is the AVX2 version of rejection_sample. Each iteration extracts 16 candidates
+/// from 24 bytes and keeps those below q, the remaining bytes being handled by the scalar code.
+///
+/// # Safety
+/// The CPU must support the AVX2 instructions.
+#[target_feature(enable = "avx2")]
+pub unsafe fn rejection_sample(bytes_arr: &[u8], coefficients: &mut [GF3329; KYBER_N_VALUE], mut j: usize) -> usize {
+    let shuffle = _mm256_loadu_si256(PARSE_SHUFFLE.as_ptr() as *const __m256i);
+    let q = _mm256_set1_epi16(Q);
+    let mask = _mm256_set1_epi16(0xFFF);
+    let mut i = 0;
+
+    // Each iteration stores 16 lanes, the lanes after the accepted candidates being zeros which
+    // are overwritten by the next coefficients
+    while j + 16 <= KYBER_N_VALUE && i + 32 <= bytes_arr.len() {
+        let bytes = _mm256_loadu_si256(bytes_arr[i..].as_ptr() as *const __m256i);
+        let pairs = _mm256_shuffle_epi8(_mm256_permute4x64_epi64::<0b10_01_01_00>(bytes), shuffle);
+        let candidates = _mm256_blend_epi16::<0b10101010>(
+            _mm256_and_si256(pairs, mask),
+            _mm256_srli_epi16::<4>(pairs)
+        );
+
+        let accepted = _mm256_cmpgt_epi16(q, candidates);
+        let accepted = _mm256_movemask_epi8(_mm256_packs_epi16(accepted, accepted)) as u32;
+
+        for (lanes, half) in [
+            (accepted & 0xFF, _mm256_castsi256_si128(candidates)),
+            ((accepted >> 16) & 0xFF, _mm256_extracti128_si256::<1>(candidates))
+        ] {
+            let compress = _mm_loadu_si128(PARSE_COMPRESS[lanes as usize].as_ptr() as *const __m128i);
+            let output = &mut coefficients[j..j + 8];
+            _mm_storeu_si128(output.as_mut_ptr() as *mut __m128i, _mm_shuffle_epi8(half, compress));
+            j += lanes.count_ones() as usize;
+        }
+        i += 24;
+    }
+
+    scalar_rejection_sample(&bytes_arr[i..], coefficients, j)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, RngCore, thread_rng};
+    use crate::algorithms::kyber::avx2;
+    use crate::algorithms::kyber::constants::{KYBER_N_VALUE, KYBER_Q_VALUE};
+    use crate::algorithms::kyber::cpapke::{cbd_eta_scalar, rejection_sample};
+    use crate::algorithms::kyber::galois_field::GF3329;
+    use crate::algorithms::kyber::ntt::{inverse_ntt_scalar, multiply_ntt_scalar, ntt_scalar};
+    use crate::algorithms::utils::hash::SHAKE_128_RATE;
+
+    const TRIALS: usize = 200;
+
+    fn random_poly() -> [GF3329; KYBER_N_VALUE] {
+        let mut rng = thread_rng();
+        let mut poly = [GF3329::default(); KYBER_N_VALUE];
+        for coefficient in poly.iter_mut() {
+            *coefficient = GF3329::from(rng.gen_range(0..KYBER_Q_VALUE));
+        }
+        poly
+    }
+
+    fn random_bytes(length: usize) -> Vec<u8> {
+        let mut bytes = vec![0u8; length];
+        thread_rng().fill_bytes(&mut bytes);
+        bytes
+    }
+
+    #[test]
+    fn test_ntt() {
+        if !avx2::is_supported() {
+            return;
+        }
+
+        for _ in 0..TRIALS {
+            let mut poly = random_poly();
+            let mut expected = poly;
+
+            ntt_scalar(&mut expected);
+            unsafe { avx2::ntt(&mut poly) };
+            assert_eq!(poly, expected);
+
+            inverse_ntt_scalar(&mut expected);
+            unsafe { avx2::inverse_ntt(&mut poly) };
+            assert_eq!(poly, expected);
+        }
+    }
+
+    #[test]
+    fn test_ntt_extreme_coefficients() {
+        if !avx2::is_supported() {
+            return;
+        }
+
+        for value in [0, 1, KYBER_Q_VALUE - 1] {
+            let mut poly = [GF3329::from(value); KYBER_N_VALUE];
+            let mut expected = poly;
+
+            ntt_scalar(&mut expected);
+            unsafe { avx2::ntt(&mut poly) };
+            assert_eq!(poly, expected);
+
+            inverse_ntt_scalar(&mut expected);
+            unsafe { avx2::inverse_ntt(&mut poly) };
+            assert_eq!(poly, expected);
+        }
+    }
+
+    #[test]
+    fn test_multiply_ntt() {
+        if !avx2::is_supported() {
+            return;
+        }
+
+        for _ in 0..TRIALS {
+            let (a, b) = (random_poly(), random_poly());
+            assert_eq!(unsafe { avx2::multiply_ntt(&a, &b) }, multiply_ntt_scalar(&a, &b));
+        }
+
+        let extreme = [GF3329::from(KYBER_Q_VALUE - 1); KYBER_N_VALUE];
+        assert_eq!(unsafe { avx2::multiply_ntt(&extreme, &extreme) }, multiply_ntt_scalar(&extreme, &extreme));
+    }
+
+    #[test]
+    fn test_cbd_eta() {
+        if !avx2::is_supported() {
+            return;
+        }
+
+        for eta in [2, 3] {
+            for _ in 0..TRIALS {
+                let bytes = random_bytes(64 * eta as usize);
+                assert_eq!(unsafe { avx2::cbd_eta(&bytes, eta) }, cbd_eta_scalar(&bytes, eta), "eta = {}", eta);
+            }
+
+            for byte in [0x00, 0xFF] {
+                let bytes = vec![byte; 64 * eta as usize];
+                assert_eq!(unsafe { avx2::cbd_eta(&bytes, eta) }, cbd_eta_scalar(&bytes, eta), "eta = {}", eta);
+            }
+        }
+    }
+
+    #[test]
+    fn test_rejection_sample() {
+        if !avx2::is_supported() {
+            return;
+        }
+
+        for _ in 0..TRIALS {
+            for j in [0, 100, KYBER_N_VALUE - 20, KYBER_N_VALUE] {
+                let bytes = random_bytes(SHAKE_128_RATE);
+                let mut coefficients = random_poly();
+                let mut expected = coefficients;
+
+                let expected_j = rejection_sample(&bytes, &mut expected, j);
+                assert_eq!(unsafe { avx2::rejection_sample(&bytes, &mut coefficients, j) }, expected_j);
+                assert_eq!(coefficients[..expected_j], expected[..expected_j]);
+            }
+        }
+
+        // Blocks whose candidates are all rejected or all accepted
+        for byte in [0xFF, 0x00] {
+            let bytes = [byte; SHAKE_128_RATE];
+            let mut coefficients = [GF3329::default(); KYBER_N_VALUE];
+            let mut expected = coefficients;
+
+            let expected_j = rejection_sample(&bytes, &mut expected, 0);
+            assert_eq!(unsafe { avx2::rejection_sample(&bytes, &mut coefficients, 0) }, expected_j);
+            assert_eq!(coefficients, expected);
+        }
+    }
+}
@@ -1,6 +1,7 @@
 pub const KYBER_N_VALUE: usize = 256;
 pub const KYBER_N_VALUE_IN_BYTES: usize = KYBER_N_VALUE / 8;
 pub const KYBER_Q_VALUE: usize = 3329;
-pub const KYBER_XOF_DEFAULT_BYTES_STREAM_SIZE: usize = 1024;
 pub const KYBER_MESSAGE_LENGTH: usize = 32;
-pub const KYBER_RANDOM_COIN_LENGTH: usize = 32;
\ No newline at end of file
+pub const KYBER_RANDOM_COIN_LENGTH: usize = 32;
+pub const ML_KEM_SHARED_SECRET_LENGTH: usize = 32;/// The length of the seed (d, z) from which a KEM key pair is generated
+pub const KYBER_KEM_SEED_LENGTH: usize = 64;
@@ -2,20 +2,27 @@ use crate::algorithms::algebraic::galois_field::GaloisField;
 use crate::algorithms::algebraic::polynomial::RingElement;
 use crate::algorithms::byte_array::ByteArray;
 use crate::algorithms::kyber::compress::{Compress, Decompress};
-use crate::algorithms::kyber::constants::{KYBER_MESSAGE_LENGTH, KYBER_N_VALUE, KYBER_N_VALUE_IN_BYTES, KYBER_Q_VALUE, KYBER_RANDOM_COIN_LENGTH, KYBER_XOF_DEFAULT_BYTES_STREAM_SIZE};
+use crate::algorithms::kyber::constants::{KYBER_MESSAGE_LENGTH, KYBER_N_VALUE, KYBER_N_VALUE_IN_BYTES, KYBER_Q_VALUE, KYBER_RANDOM_COIN_LENGTH};
 use crate::algorithms::kyber::encoder::{Decoder, Encoder};
 use crate::algorithms::kyber::galois_field::GF3329;
+#[cfg(target_arch = "x86_64")]
+use crate::algorithms::kyber::avx2;
 use crate::algorithms::kyber::matrix::MatrixRQ;
 use crate::algorithms::kyber::ntt::NTT;
 
 use crate::algorithms::kyber::polynomial::PolyRQ;
+use crate::algorithms::kyber::types::{ciphertext_length, cpapke_private_key_length, cpapke_public_key_length, ParameterSet};
 use crate::algorithms::kyber::vector::VectorRQ;
-use crate::algorithms::utils::hash::{sha3_512, shake_128, shake_256};
+use crate::algorithms::utils::hash::{sha3_512, shake_128_stream, shake_128_x4, shake_256, shake_256_x4, Shake128Stream, Shake128StreamX4};
+use crate::algorithms::utils::keccak::PARALLEL_SPONGES;
+use crate::algorithms::utils::secret::Secret;
+use crate::CryptumResult;
+use crate::errors::CryptumError;
 
 pub trait KyberPKE {
-    fn keygen(&self, seed: ByteArray) -> (ByteArray, ByteArray);
-    fn encrypt(&self, public_key: ByteArray, message: ByteArray, random_coin: ByteArray) -> ByteArray;
-    fn decrypt(&self, private_key: ByteArray, ciphertext: ByteArray) -> ByteArray;
+    fn keygen(&self, seed: ByteArray) -> CryptumResult<(ByteArray, ByteArray)>;
+    fn encrypt(&self, public_key: ByteArray, message: ByteArray, random_coin: ByteArray) -> CryptumResult<ByteArray>;
+    fn decrypt(&self, private_key: ByteArray, ciphertext: ByteArray) -> CryptumResult<ByteArray>;
     fn get_ciphertext_length(&self) -> usize;
 }
 
@@ -75,8 +82,8 @@ impl <const N: usize> KyberCPAPKECore<N> {
         (12 * self.k as usize * KYBER_N_VALUE / 8) + 32
     }
 
-    fn g(&self, seed: ByteArray) -> (ByteArray, ByteArray) {
-        let hash = sha3_512(seed.get_bytes());
+    fn g(&self, seed: &ByteArray) -> (ByteArray, ByteArray) {
+        let hash = Secret::new(sha3_512(seed.get_bytes()));
         let data = hash.split_at(KYBER_N_VALUE_IN_BYTES);
         (ByteArray::from(data.0), ByteArray::from(data.1))
     }
@@ -88,72 +95,83 @@ impl <const N: usize> KyberCPAPKECore<N> {
     ///     first_byte: A byte value
     ///     second_byte: A byte value
     /// Output:
-    ///     A stream of bytes
-    fn xof(&self, bytes_arr: &ByteArray, first_byte: u8, second_byte: u8) -> ByteArray {
+    ///     A stream of bytes squeezed on demand
+    fn xof(&self, bytes_arr: &ByteArray, first_byte: u8, second_byte: u8) -> Shake128Stream {
         let concat = ByteArray::concat(&[
-            &bytes_arr,
+            bytes_arr,
             &first_byte.into(),
             &second_byte.into()
         ]);
-        shake_128(concat.get_bytes(), KYBER_XOF_DEFAULT_BYTES_STREAM_SIZE).into()
+        shake_128_stream(concat.get_bytes())
     }
 
-    /// This function corresponds to the Parse function mentioned in p.6 of the article.
+    /// This function corresponds to the Parse function mentioned in p.6 of the article. The blocks
+    /// of the stream are squeezed one at a time until every coefficient has been sampled.
     ///
     /// Input:
-    ///     bytes_stream: An array of bytes
+    ///     bytes_stream: A stream of bytes
     /// Output:
     ///     A Polynomial belonging to the Polynomial Ring R_q
-    fn parse(bytes_stream: ByteArray) -> PolyRQ {
+    fn parse(bytes_stream: &mut Shake128Stream) -> PolyRQ {
         let mut coefficients = [GF3329::zero(); KYBER_N_VALUE];
-
-        let mut i = 0;
         let mut j = 0;
 
-        let bytes_arr = bytes_stream.get_bytes();
-
-        while j  < KYBER_N_VALUE {
-            let b_i = bytes_arr.get(i).unwrap().clone() as usize;
-            let b_i_plus_one = bytes_arr.get(i+1).unwrap().clone() as usize;
-            let b_i_plus_two = bytes_arr.get(i+2).unwrap().clone() as usize;
-
-            let d_1 = b_i + KYBER_N_VALUE * (b_i_plus_one % 16 as usize);
-            let d_2 = (b_i_plus_one / 16 as usize) + 16 as usize * b_i_plus_two;
+        while j < KYBER_N_VALUE {
+            j = sample_block(&bytes_stream.squeeze_block(), &mut coefficients, j);
+        }
 
-            if d_1 < KYBER_Q_VALUE {
-                coefficients[j] = d_1.into();
-                j += 1;
-            }
+        coefficients.into()
+    }
 
-            if d_2 < KYBER_Q_VALUE && j < KYBER_N_VALUE {
-                coefficients[j] = d_2.into();
-                j += 1;
+    /// This function applies the Parse function to up to four streams squeezed together.
+    ///
+    /// Input:
+    ///     bytes_streams: The streams of bytes
+    ///     count: The number of streams to parse
+    /// Output:
+    ///     The polynomials sampled from the first count streams
+    fn parse_x4(bytes_streams: &mut Shake128StreamX4, count: usize) -> Vec<PolyRQ> {
+        let mut coefficients = vec![[GF3329::zero(); KYBER_N_VALUE]; count];
+        let mut sampled = vec![0; count];
+
+        while sampled.iter().any(|&j| j < KYBER_N_VALUE) {
+            let blocks = bytes_streams.squeeze_blocks();
+
+            for ((poly, j), block) in coefficients.iter_mut().zip(sampled.iter_mut()).zip(blocks.iter()) {
+                if *j < KYBER_N_VALUE {
+                    *j = sample_block(block, poly, *j);
+                }
             }
-            i += 3;
         }
 
-        coefficients.into()
+        coefficients.into_iter().map(PolyRQ::from).collect()
     }
 
-
+    /// This function generates the matrix A_hat. The XOF streams of four coefficients of the
+    /// matrix are squeezed together, which gives the same matrix as one XOF call per coefficient.
     pub fn generate_matrix_from_seed(&self, seed: &ByteArray) -> MatrixRQ {
-        let mut matrix_data = vec![];
-
-        for i in 0..self.k{
-            let mut row_data = vec![];
-
-            for j in 0..self.k {
-                let bytes_stream = self.xof(
-                    seed,
-                    j,
-                    i
-                );
-                let poly = Self::parse(bytes_stream);
-                row_data.push(poly);
+        let positions: Vec<(u8, u8)> = (0..self.k)
+            .flat_map(|i| (0..self.k).map(move |j| (i, j)))
+            .collect();
+        let mut polynomials = Vec::with_capacity(positions.len());
+
+        for group in positions.chunks(PARALLEL_SPONGES) {
+            if let [(i, j)] = group {
+                polynomials.push(Self::parse(&mut self.xof(seed, *j, *i)));
+                continue;
             }
-            matrix_data.push(row_data);
+
+            let inputs: Vec<Vec<u8>> = group.iter()
+                .map(|&(i, j)| [seed.get_bytes(), &[j, i]].concat())
+                .collect();
+            let input_slices: Vec<&[u8]> = inputs.iter().map(|input| input.as_slice()).collect();
+            polynomials.extend(Self::parse_x4(&mut shake_128_x4(&input_slices), group.len()));
         }
 
+        let matrix_data: Vec<Vec<PolyRQ>> = polynomials
+            .chunks(self.k as usize)
+            .map(|row| row.to_vec())
+            .collect();
         matrix_data.into()
     }
 
@@ -167,7 +185,7 @@ impl <const N: usize> KyberCPAPKECore<N> {
     /// Output:
     ///     A stream of bytes
     fn prf(&self, s: &ByteArray, b: u8, length: usize) -> ByteArray {
-        let data = ByteArray::concat(&[&s, &b.into()]);
+        let data = ByteArray::concat(&[s, &b.into()]);
 
         // Checking length of data
         if data.get_bytes().len() != 33 {
@@ -183,26 +201,13 @@ impl <const N: usize> KyberCPAPKECore<N> {
             panic!("bytes_array does not have the correct size");
         }
 
-        let mut coefficients = [GF3329::zero(); KYBER_N_VALUE];
-        let bits = bytes_array.to_bits();
-
-        for i in 0..KYBER_N_VALUE {
-            let mut a = GF3329::zero();
-            let mut b = GF3329::zero();
-
-            for j in 0..eta {
-                let a_index = 2 * i  * eta as usize + j as usize;
-                a = a.add(&GF3329::from(bits[a_index] as usize));
-
-                let b_index = 2 * i * eta as usize + eta as usize + j as usize;
-                b = b.add(&GF3329::from(bits[b_index] as usize));
-            }
-
-            coefficients[i] = a.sub(&b);
-
+        #[cfg(target_arch = "x86_64")]
+        if avx2::is_supported() {
+            // SAFETY: the CPU supports the AVX2 instructions
+            return unsafe { avx2::cbd_eta(bytes_array.get_bytes(), eta) }.into();
         }
 
-        coefficients.into()
+        cbd_eta_scalar(bytes_array.get_bytes(), eta).into()
     }
 
 
@@ -211,126 +216,129 @@ impl <const N: usize> KyberCPAPKECore<N> {
         self.cbd_eta(&bytes_arr, eta)
     }
 
+    /// This function generates a vector of k polynomials whose coefficients follow the centered
+    /// binomial distribution. The PRF outputs of four polynomials are computed together.
+    ///
+    /// Input:
+    ///     sigma: A 32-bytes array
+    ///     upper_n: The counter given to the PRF, incremented for every polynomial
+    ///     eta: The parameter of the distribution
+    /// Output:
+    ///     A vector of polynomials belonging to the Polynomial Ring R_q
     fn generate_random_vec(&self, sigma: &ByteArray, upper_n: &mut u8, eta: u8) -> VectorRQ {
+        let counters: Vec<u8> = (*upper_n..*upper_n + self.k).collect();
         let mut vector_data = Vec::with_capacity(self.k as usize);
+        *upper_n += self.k;
+
+        for group in counters.chunks(PARALLEL_SPONGES) {
+            if let [counter] = group {
+                vector_data.push(self.generate_random_poly(sigma, *counter, eta));
+                continue;
+            }
+
+            let inputs = Secret::new(group.iter()
+                .map(|&counter| [sigma.get_bytes(), &[counter]].concat())
+                .collect::<Vec<Vec<u8>>>());
+            let input_slices: Vec<&[u8]> = inputs.iter().map(|input| input.as_slice()).collect();
+            let outputs = Secret::new(shake_256_x4(&input_slices, 64 * eta as usize));
 
-        for _ in 0..self.k {
-            let poly = self.generate_random_poly(sigma, *upper_n, eta);
-            vector_data.push(poly);
-            *upper_n += 1;
+            for output in outputs.iter() {
+                vector_data.push(self.cbd_eta(&ByteArray::from(output.as_slice()), eta));
+            }
         }
 
         vector_data.into()
     }
 
-    fn decode_vec(&self, public_key: &ByteArray, l_value: u8) -> VectorRQ {
+    fn decode_vec(&self, public_key: &ByteArray, l_value: u8) -> CryptumResult<VectorRQ> {
         let mut polynomials = vec![];
         let bytes = public_key.get_bytes();
 
         for chunk in bytes.chunks_exact(32 * l_value as usize) {
             let sub_bytes_array = ByteArray::from(chunk);
-            let polynomial = PolyRQ::decode(sub_bytes_array, l_value);
+            let polynomial = PolyRQ::decode(sub_bytes_array, l_value)?;
             polynomials.push(polynomial);
         }
 
-        polynomials.into()
+        Ok(polynomials.into())
     }
 
     pub fn get_private_key_length(&self) -> usize {
         (12 * self.k as usize * KYBER_N_VALUE) / 8
     }
-}
 
-impl <const V: usize>KyberPKE for KyberCPAPKECore<V> {
-    /// This function corresponds to the KeyGen function (Algorithm 4). We only modify it to take
-    /// a seed as an input to perform some unit test. In the paper, the seed is computed inside
-    /// the function.
+    /// This function corresponds to the part of the Enc function (Algorithm 5) that only depends
+    /// on the public key: t_hat is decoded and the matrix A_hat is expanded from rho and
+    /// transposed. Both can be kept to encrypt several messages with the same public key.
     ///
     /// Input:
-    ///     seed: A 32-bytes array
+    ///     public_key: An array of bytes that corresponds to the public key
     /// Output:
-    ///     - An array of bytes corresponding to the public key
-    ///     - An array of bytes corresponding to the private key
-    fn keygen(&self, seed: ByteArray) -> (ByteArray, ByteArray) {
-        // Checking length of seed
-        if seed.length() != 32 {
-            panic!("Invalid for seed ! Should be 32 found {}", seed.length());
-        }
-
-        let (rho, sigma) = self.g(seed);
-
-        // Generating the A hat matrix
-        let a_hat = self.generate_matrix_from_seed(&rho);
-
-        // // Generating s and e
-        let mut upper_n = 0;
-        let s = self.generate_random_vec(&sigma, &mut upper_n, self.eta_1);
-        let e = self.generate_random_vec(&sigma, &mut upper_n, self.eta_1);
-
-        // Applying NTT transformation to s and e
-        let s_hat = s.to_ntt();
-        let e_hat = e.to_ntt();
+    ///     - The vector t_hat
+    ///     - The transposed matrix A_hat
+    pub(crate) fn decode_public_key(&self, public_key: &ByteArray) -> CryptumResult<(VectorRQ, MatrixRQ)> {
+        // Checking the length of the public key
+        let public_key_length = public_key.length();
 
-        let t_hat = a_hat.multiply_vec(&s_hat) + e_hat;
+        let expected_public_key_length = self.get_public_key_length();
+        if public_key_length !=  expected_public_key_length {
+            return Err(CryptumError::InvalidKeyLength {
+                expected: expected_public_key_length,
+                found: public_key_length
+            });
+        }
 
-        let public_key = ByteArray::concat(&[&t_hat.encode(12), &rho]);
-        let private_key = s_hat.encode(12);
+        let t_hat = self.decode_vec(public_key, 12)?;
+        let rho = public_key.slice(public_key_length - 32);
 
+        let a_hat_transposed = self.generate_matrix_from_seed(&rho).transpose();
 
-        (public_key, private_key)
+        Ok((t_hat, a_hat_transposed))
     }
 
-    /// This function corresponds to the Enc function (Algorithm 5).
+    /// This function corresponds to the Enc function (Algorithm 5) once the public key has been
+    /// decoded by the decode_public_key function.
     ///
     /// Input:
-    ///     public_key: An array of bytes that corresponds to the public key
+    ///     t_hat: The vector t_hat of the public key
+    ///     a_hat_transposed: The transposed matrix A_hat of the public key
     ///     message: A 32-bytes array to cipher
     ///     random_coin: An array of bytes filled with random values
     /// Output:
     ///     An array of bytes corresponding to a ciphertext
-    fn encrypt(&self, public_key: ByteArray, message: ByteArray, random_coin: ByteArray) -> ByteArray {
-        // Checking the length of the public key
-        let public_key_length = public_key.length();
-
-        let expected_public_key_length = self.get_public_key_length();
-        if public_key_length !=  expected_public_key_length {
-            panic!(
-                "Invalid length for public key ! Expected {} found {}",
-                expected_public_key_length,
-                public_key.length()
-            )
-        }
-
+    pub(crate) fn encrypt_prepared(&self, t_hat: &VectorRQ, a_hat_transposed: &MatrixRQ, message: ByteArray, random_coin: ByteArray) -> CryptumResult<ByteArray> {
         // Checking length for message
         let message_length = message.length();
 
         if message_length != KYBER_MESSAGE_LENGTH {
-            panic!("Invalid length for message ! Expected 32 found {}.", message_length);
+            return Err(CryptumError::InvalidMessageLength {
+                expected: KYBER_MESSAGE_LENGTH,
+                found: message_length
+            });
         }
 
         // Checking length for random coin
         let random_coin_length = random_coin.length();
 
         if random_coin_length != KYBER_RANDOM_COIN_LENGTH {
-            panic!("Invalid length for random coin ! Expected 32 found {}.", random_coin_length);
+            return Err(CryptumError::InvalidSeedLength {
+                expected: KYBER_RANDOM_COIN_LENGTH,
+                found: random_coin_length
+            });
         }
 
         let mut upper_n = 0;
 
-        let t_hat = self.decode_vec(&public_key, 12);
-        let rho = public_key.slice(public_key_length - 32);
-
-        let a_hat = self.generate_matrix_from_seed(&rho);
-
-        let r = self.generate_random_vec(&random_coin, &mut upper_n, self.eta_1);
-        let e_1 = self.generate_random_vec(&random_coin, &mut upper_n, self.eta_2);
-        let e_2 = self.generate_random_poly(&random_coin, upper_n, self.eta_2);
+        let random_coin = Secret::new(random_coin);
+        let r = Secret::new(self.generate_random_vec(&random_coin, &mut upper_n, self.eta_1));
+        let e_1 = Secret::new(self.generate_random_vec(&random_coin, &mut upper_n, self.eta_2));
+        let e_2 = Secret::new(self.generate_random_poly(&random_coin, upper_n, self.eta_2));
 
-        let r_hat = r.to_ntt();
+        let r_hat = Secret::new(r.clone().to_ntt());
 
-        let u: VectorRQ = (a_hat.transpose().multiply_vec(&r_hat)).inverse_ntt() + e_1;
+        let u: VectorRQ = (a_hat_transposed.multiply_vec(&r_hat)).inverse_ntt() + e_1.clone();
 
-        let m = PolyRQ::decode(message, 1).decompress(1);
+        let m = Secret::new(PolyRQ::decode(message, 1)?.decompress(1));
 
         let v = (t_hat.dot_ntt(&r_hat))
             .inverse_ntt()
@@ -341,52 +349,230 @@ impl <const V: usize>KyberPKE for KyberCPAPKECore<V> {
         let c_1 = u.compress(self.d_u as u32).encode(self.d_u);
         let c_2 = v.compress(self.d_v as u32).encode(self.d_v);
 
-        ByteArray::concat(&[&c_1, &c_2])
+        Ok(ByteArray::concat(&[&c_1, &c_2]))
     }
 
-    /// This function corresponds to the Dec function (Algorithm 6).
+    /// This function decodes the vector s_hat from a private key generated by the KeyGen function.
     ///
     /// Input:
-    ///     private_key: An array of bytes that corresponds to a private key generate by the KeyGen function.
-    ///     ciphertext: An array of bytes to decipher.
+    ///     private_key: An array of bytes that corresponds to a private key
     /// Output:
-    ///     An array of bytes that represents the original message that has been ciphered previously.
-    fn decrypt(&self, private_key: ByteArray, ciphertext: ByteArray) -> ByteArray {
+    ///     The vector s_hat
+    pub(crate) fn decode_private_key(&self, private_key: &ByteArray) -> CryptumResult<Secret<VectorRQ>> {
         // Checking length of private
         let private_key_length = private_key.length();
         let expected_private_key_length = self.get_private_key_length();
 
         if private_key_length !=  expected_private_key_length {
-            panic!(
-                "Invalid length for secret_key ! Expected {} found {}",
-                expected_private_key_length,
-                private_key_length
-            )
+            return Err(CryptumError::InvalidKeyLength {
+                expected: expected_private_key_length,
+                found: private_key_length
+            });
         }
 
+        Ok(Secret::new(self.decode_vec(private_key, 12)?))
+    }
+
+    /// This function corresponds to the Dec function (Algorithm 6) once the private key has been
+    /// decoded by the decode_private_key function.
+    ///
+    /// Input:
+    ///     s_hat: The vector s_hat of the private key
+    ///     ciphertext: An array of bytes to decipher.
+    /// Output:
+    ///     An array of bytes that represents the original message that has been ciphered previously.
+    pub(crate) fn decrypt_prepared(&self, s_hat: &VectorRQ, ciphertext: ByteArray) -> CryptumResult<ByteArray> {
         // Checking length of ciphertext
         let ciphertext_length = ciphertext.length();
 
         if ciphertext_length != self.get_ciphertext_length() {
-            panic!(
-                "Invalid length for ciphertext ! Expected {} found {}",
-                self.get_ciphertext_length(),
-                ciphertext_length
-            );
+            return Err(CryptumError::InvalidCiphertextLength {
+                expected: self.get_ciphertext_length(),
+                found: ciphertext_length
+            });
         }
 
         let split_pos = (self.d_u * self.k as usize * KYBER_N_VALUE) / 8;
         let (c_1, c_2) = ciphertext.split_at(split_pos);
 
 
-        let u = VectorRQ::decode(c_1, self.d_u as u8).decompress(self.d_u as u32);
-        let v = PolyRQ::decode(c_2, self.d_v as u8).decompress(self.d_v as u32);
-        let s_hat = VectorRQ::decode(private_key, 12);
+        let u = VectorRQ::decode(c_1, self.d_u as u8)?.decompress(self.d_u as u32);
+        let v = PolyRQ::decode(c_2, self.d_v as u8)?.decompress(self.d_v as u32);
+
+        let product = Secret::new((s_hat.dot_ntt(&u.to_ntt())).inverse_ntt());
+        let poly = Secret::new(v.sub(&product));
+
+        Ok(poly.clone().compress(1).encode(1))
+    }
+
+    /// This function corresponds to the K-PKE.KeyGen function (Algorithm 13) of FIPS 203. The
+    /// only difference with the round 3 KeyGen function lies in the input of G which also contains
+    /// the module rank k for domain separation.
+    ///
+    /// Input:
+    ///     seed: A 32-bytes array
+    /// Output:
+    ///     - An array of bytes corresponding to the public key
+    ///     - An array of bytes corresponding to the private key
+    pub fn keygen_ml_kem(&self, seed: ByteArray) -> CryptumResult<(ByteArray, ByteArray)> {
+        // Checking length of seed
+        if seed.length() != 32 {
+            return Err(CryptumError::InvalidSeedLength { expected: 32, found: seed.length() });
+        }
+
+        Ok(self.generate_keys(Secret::new(ByteArray::concat(&[&seed, &self.k.into()]))))
+    }
+
+    fn generate_keys(&self, g_input: Secret<ByteArray>) -> (ByteArray, ByteArray) {
+        let (rho, sigma) = self.g(&g_input);
+        let sigma = Secret::new(sigma);
+
+        // Generating the A hat matrix
+        let a_hat = self.generate_matrix_from_seed(&rho);
+
+        // // Generating s and e
+        let mut upper_n = 0;
+        let s = Secret::new(self.generate_random_vec(&sigma, &mut upper_n, self.eta_1));
+        let e = Secret::new(self.generate_random_vec(&sigma, &mut upper_n, self.eta_1));
+
+        // Applying NTT transformation to s and e
+        let s_hat = Secret::new(s.clone().to_ntt());
+        let e_hat = Secret::new(e.clone().to_ntt());
+
+        let t_hat = a_hat.multiply_vec(&s_hat) + e_hat.clone();
+
+        let public_key = ByteArray::concat(&[&t_hat.encode(12), &rho]);
+        let private_key = s_hat.encode(12);
+
+
+        (public_key, private_key)
+    }
+}
+
+/// This function samples the coefficients of a polynomial from one block of a XOF stream, with
+/// the AVX2 backend when the CPU supports it.
+fn sample_block(block: &[u8], coefficients: &mut [GF3329; KYBER_N_VALUE], j: usize) -> usize {
+    #[cfg(target_arch = "x86_64")]
+    if avx2::is_supported() {
+        // SAFETY: the CPU supports the AVX2 instructions
+        return unsafe { avx2::rejection_sample(block, coefficients, j) };
+    }
+
+    rejection_sample(block, coefficients, j)
+}
+
+/// This function corresponds to the rejection sampling of the Parse function: the candidates
+/// read from the bytes fill the coefficients from the position j.
+/// Input:
+///     bytes_arr: An array of bytes whose length is a multiple of 3
+///     coefficients: The coefficients of the polynomial, the first j are already sampled
+///     j: The number of coefficients already sampled
+/// Output:
+///     The number of coefficients sampled once the bytes are consumed or the polynomial is full
+pub(crate) fn rejection_sample(bytes_arr: &[u8], coefficients: &mut [GF3329; KYBER_N_VALUE], mut j: usize) -> usize {
+    for bytes in bytes_arr.chunks_exact(3) {
+        if j >= KYBER_N_VALUE {
+            break;
+        }
+
+        let b_i = bytes[0] as usize;
+        let b_i_plus_one = bytes[1] as usize;
+        let b_i_plus_two = bytes[2] as usize;
+
+        let d_1 = b_i + KYBER_N_VALUE * (b_i_plus_one % 16);
+        let d_2 = (b_i_plus_one / 16) + 16 * b_i_plus_two;
+
+        if d_1 < KYBER_Q_VALUE {
+            coefficients[j] = d_1.into();
+            j += 1;
+        }
+
+        if d_2 < KYBER_Q_VALUE && j < KYBER_N_VALUE {
+            coefficients[j] = d_2.into();
+            j += 1;
+        }
+    }
+
+    j
+}
+
+/// This function corresponds to the CBD_eta function (p.7 of the article).
+/// Input:
+///     bytes_arr: An array of 64 * eta bytes
+///     eta: The eta value
+/// Output:
+///     The coefficients of a polynomial sampled from the centered binomial distribution
+pub(crate) fn cbd_eta_scalar(bytes_arr: &[u8], eta: u8) -> [GF3329; KYBER_N_VALUE] {
+    let mut coefficients = [GF3329::zero(); KYBER_N_VALUE];
+    // Every coefficient consumes 2 * eta bits: a and b are the number of bits set among the
+    // first and the last eta bits
+    let eta = eta as usize;
+    let mask = (1 << eta) - 1;
+    let mut bytes_iter = bytes_arr.iter();
+    let mut buffer: u32 = 0;
+    let mut buffer_length = 0;
+
+    for coefficient in coefficients.iter_mut() {
+        while buffer_length < 2 * eta {
+            buffer |= (*bytes_iter.next().unwrap_or(&0) as u32) << buffer_length;
+            buffer_length += 8;
+        }
+
+        let a = (buffer & mask).count_ones() as u16;
+        let b = ((buffer >> eta) & mask).count_ones() as u16;
+        buffer >>= 2 * eta;
+        buffer_length -= 2 * eta;
+
+        *coefficient = GF3329::new(a).sub(&GF3329::new(b));
+    }
 
-        let mut poly = (s_hat.dot_ntt(&u.to_ntt())).inverse_ntt();
-        poly = v.sub(&poly);
+    coefficients
+}
+
+impl <const V: usize>KyberPKE for KyberCPAPKECore<V> {
+    /// This function corresponds to the KeyGen function (Algorithm 4). We only modify it to take
+    /// a seed as an input to perform some unit test. In the paper, the seed is computed inside
+    /// the function.
+    ///
+    /// Input:
+    ///     seed: A 32-bytes array
+    /// Output:
+    ///     - An array of bytes corresponding to the public key
+    ///     - An array of bytes corresponding to the private key
+    fn keygen(&self, seed: ByteArray) -> CryptumResult<(ByteArray, ByteArray)> {
+        // Checking length of seed
+        if seed.length() != 32 {
+            return Err(CryptumError::InvalidSeedLength { expected: 32, found: seed.length() });
+        }
 
-        poly.compress(1).encode(1)
+        Ok(self.generate_keys(Secret::new(seed)))
+    }
+
+    /// This function corresponds to the Enc function (Algorithm 5).
+    ///
+    /// Input:
+    ///     public_key: An array of bytes that corresponds to the public key
+    ///     message: A 32-bytes array to cipher
+    ///     random_coin: An array of bytes filled with random values
+    /// Output:
+    ///     An array of bytes corresponding to a ciphertext
+    fn encrypt(&self, public_key: ByteArray, message: ByteArray, random_coin: ByteArray) -> CryptumResult<ByteArray> {
+        let (t_hat, a_hat_transposed) = self.decode_public_key(&public_key)?;
+
+        self.encrypt_prepared(&t_hat, &a_hat_transposed, message, random_coin)
+    }
+
+    /// This function corresponds to the Dec function (Algorithm 6).
+    ///
+    /// Input:
+    ///     private_key: An array of bytes that corresponds to a private key generate by the KeyGen function.
+    ///     ciphertext: An array of bytes to decipher.
+    /// Output:
+    ///     An array of bytes that represents the original message that has been ciphered previously.
+    fn decrypt(&self, private_key: ByteArray, ciphertext: ByteArray) -> CryptumResult<ByteArray> {
+        let s_hat = self.decode_private_key(&private_key)?;
+
+        self.decrypt_prepared(&s_hat, ciphertext)
     }
 
     fn get_ciphertext_length(&self) -> usize {
@@ -395,6 +581,13 @@ impl <const V: usize>KyberPKE for KyberCPAPKECore<V> {
 }
 
 
+impl <const V: usize> ParameterSet for KyberCPAPKECore<V> {
+    const PUBLIC_KEY_LENGTH: usize = cpapke_public_key_length(V);
+    const SECRET_KEY_LENGTH: usize = cpapke_private_key_length(V);
+    const CIPHERTEXT_LENGTH: usize = ciphertext_length(V);
+}
+
+
 pub type KyberCPAPKE512 = KyberCPAPKECore<512>;
 pub type KyberCPAPKE768 = KyberCPAPKECore<768>;
 pub type KyberCPAPKE1024 = KyberCPAPKECore<1024>;
@@ -405,14 +598,18 @@ mod tests {
     use hex_literal::hex;
     use crate::algorithms::byte_array::ByteArray;
     use crate::algorithms::kyber::constants::{KYBER_MESSAGE_LENGTH, KYBER_N_VALUE_IN_BYTES, KYBER_RANDOM_COIN_LENGTH};
-    use crate::algorithms::kyber::cpapke::{KyberCPAPKE512};
+    use crate::algorithms::kyber::constants::KYBER_N_VALUE;
+    use crate::algorithms::kyber::cpapke::{rejection_sample, KyberCPAPKE512, KyberCPAPKE768};
+    use crate::algorithms::kyber::galois_field::GF3329;
     use crate::algorithms::kyber::KyberPKE;
+    use crate::algorithms::utils::hash::{shake_128, SHAKE_128_RATE};
+    use crate::errors::CryptumError;
 
     #[test]
     fn test_g() {
         let seed = ByteArray::from([0u8; KYBER_N_VALUE_IN_BYTES].as_slice());
         let kyber = KyberCPAPKE512::init();
-        let (rho, sigma) = kyber.g(seed);
+        let (rho, sigma) = kyber.g(&seed);
 
         let expected_rho = [173, 86, 195, 92, 171, 80, 99, 185, 231, 234, 86, 131, 20, 236, 129, 196, 11, 165, 119, 170, 230, 48, 222, 144, 32, 4, 0, 158, 136, 241, 141, 165];
         let expected_rho = ByteArray::from(expected_rho.as_slice());
@@ -431,6 +628,53 @@ mod tests {
         let _ = kyber.generate_matrix_from_seed(&seed);
     }
 
+    #[test]
+    fn test_generate_matrix_matches_one_xof_per_coefficient() {
+        // The 9 coefficients of Kyber768 are squeezed in two groups of 4 and one single stream
+        let seed = ByteArray::random(32);
+        let kyber = KyberCPAPKE768::init();
+
+        let matrix = kyber.generate_matrix_from_seed(&seed);
+
+        for i in 0..3u8 {
+            for j in 0..3u8 {
+                let expected_poly = KyberCPAPKE768::parse(&mut kyber.xof(&seed, j, i));
+                assert_eq!(matrix.get_row(i as usize)[j as usize], expected_poly);
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_matches_fixed_length_stream() {
+        // Squeezing the blocks on demand gives the same polynomial as sampling from a long enough
+        // prefix of the stream
+        let seed = ByteArray::random(32);
+        let kyber = KyberCPAPKE512::init();
+
+        let mut bytes_stream = kyber.xof(&seed, 1, 0);
+        let poly = KyberCPAPKE512::parse(&mut bytes_stream);
+
+        let input = ByteArray::concat(&[&seed, &1.into(), &0.into()]);
+        let mut expected = [GF3329::default(); KYBER_N_VALUE];
+        let j = rejection_sample(&shake_128(input.get_bytes(), 1024), &mut expected, 0);
+
+        assert_eq!(j, KYBER_N_VALUE);
+        assert_eq!(poly.as_array(), &expected);
+    }
+
+    #[test]
+    fn test_rejection_sample_consumes_the_whole_block() {
+        let mut coefficients = [GF3329::default(); KYBER_N_VALUE];
+
+        // Every candidate of the block is rejected, so more bytes are needed
+        assert_eq!(rejection_sample(&[0xFF; SHAKE_128_RATE], &mut coefficients, 10), 10);
+
+        // Every candidate is accepted until the polynomial is full
+        assert_eq!(rejection_sample(&[0x00; SHAKE_128_RATE], &mut coefficients, 0), 112);
+        assert_eq!(rejection_sample(&[0x00; SHAKE_128_RATE], &mut coefficients, 200), KYBER_N_VALUE);
+        assert_eq!(rejection_sample(&[], &mut coefficients, 0), 0);
+    }
+
     #[test]
     fn test_prf_func() {
         let bytes_array = ByteArray::from([0x41; 32].as_slice());
@@ -455,7 +699,7 @@ mod tests {
     fn test_keygen_length() {
         let kyber=  KyberCPAPKE512::init();
         let seed = ByteArray::random(32);
-        let (public_key, private_key) = kyber.keygen(seed);
+        let (public_key, private_key) = kyber.keygen(seed).unwrap();
 
         let expected_length_public_key = 800;
         let expected_length_private_key = 768;
@@ -469,7 +713,7 @@ mod tests {
     fn test_keygen() {
         let kyber = KyberCPAPKE512::init();
         let seed = ByteArray::from([0u8; KYBER_N_VALUE_IN_BYTES].as_slice());
-        let (public_key, private_key) = kyber.keygen(seed);
+        let (public_key, private_key) = kyber.keygen(seed).unwrap();
 
         let expected_public_key = [156, 229, 198, 126, 252, 156, 51, 103, 168, 60, 124, 66, 176, 161, 41, 55, 201, 64, 207, 113, 26, 162, 167, 127, 148, 56, 146, 141, 105, 80, 234, 96, 7, 192, 185, 2, 255, 25, 119, 123, 32, 147, 18, 102, 6, 119, 0, 185, 105, 160, 36, 146, 236, 192, 102, 115, 206, 246, 225, 206, 54, 119, 58, 75, 219, 149, 56, 213, 52, 88, 38, 24, 222, 210, 121, 226, 153, 161, 32, 35, 24, 19, 140, 168, 213, 73, 182, 198, 8, 129, 238, 210, 183, 74, 184, 132, 185, 48, 116, 104, 83, 89, 96, 53, 39, 236, 99, 139, 171, 177, 61, 152, 242, 167, 62, 42, 54, 42, 86, 98, 163, 81, 83, 141, 83, 87, 236, 124, 32, 182, 103, 64, 128, 199, 106, 83, 101, 171, 159, 72, 63, 120, 108, 78, 44, 219, 202, 35, 240, 170, 10, 100, 88, 158, 184, 110, 170, 7, 55, 113, 240, 77, 57, 128, 142, 144, 68, 55, 104, 170, 13, 9, 34, 181, 223, 161, 84, 231, 231, 9, 61, 52, 34, 56, 20, 186, 161, 182, 109, 174, 54, 83, 206, 162, 21, 179, 172, 125, 108, 101, 184, 44, 49, 174, 223, 192, 62, 62, 246, 29, 210, 147, 21, 181, 193, 28, 16, 165, 185, 223, 19, 146, 23, 160, 194, 62, 244, 191, 129, 65, 14, 191, 22, 15, 91, 186, 77, 60, 103, 24, 179, 86, 177, 133, 101, 90, 190, 209, 66, 163, 196, 153, 5, 226, 40, 157, 212, 202, 5, 160, 58, 5, 11, 160, 8, 211, 82, 189, 34, 186, 235, 137, 120, 78, 252, 41, 1, 12, 162, 120, 74, 108, 33, 203, 70, 147, 121, 0, 153, 219, 158, 202, 147, 37, 81, 105, 149, 49, 100, 21, 136, 140, 53, 168, 52, 97, 187, 90, 134, 180, 37, 23, 178, 177, 86, 210, 133, 152, 201, 91, 117, 78, 27, 55, 79, 64, 160, 174, 83, 33, 105, 114, 118, 136, 103, 205, 69, 167, 202, 214, 213, 167, 56, 43, 42, 93, 124, 22, 148, 73, 110, 5, 72, 173, 244, 87, 27, 75, 92, 95, 93, 33, 147, 159, 188, 155, 242, 105, 205, 175, 83, 190, 95, 9, 190, 97, 147, 174, 186, 89, 31, 79, 193, 185, 67, 151, 121, 141, 185, 66, 231, 58, 117, 89, 0, 23, 190, 75, 70, 193, 233, 77, 208, 251, 27, 138, 148, 118, 242, 138, 207, 11, 33, 26, 193, 202, 128, 44, 168, 114, 62, 122, 111, 97, 241, 52, 60, 23, 69, 158, 85, 178, 115, 44, 206, 246, 60, 128, 190, 67, 89, 247, 246, 21, 249, 12, 46, 140, 108, 8, 35, 133, 174, 183, 21, 24, 137, 42, 28, 135, 203, 63, 54, 102, 129, 71, 217, 25, 193, 6, 107, 170, 105, 182, 227, 249, 153, 249, 38, 120, 13, 102, 23, 112, 21, 35, 64, 115, 29, 2, 118, 196, 84, 168, 108, 130, 224, 183, 76, 75, 76, 27, 119, 55, 242, 249, 174, 50, 34, 183, 208, 242, 203, 192, 1, 186, 84, 33, 204, 106, 26, 34, 208, 133, 135, 162, 88, 206, 66, 188, 105, 161, 114, 68, 118, 165, 167, 150, 185, 127, 161, 92, 133, 156, 124, 96, 106, 227, 44, 129, 188, 153, 57, 105, 153, 211, 69, 123, 180, 35, 149, 99, 42, 182, 153, 83, 64, 181, 20, 128, 15, 88, 46, 86, 149, 52, 208, 26, 39, 66, 220, 47, 10, 53, 191, 173, 97, 134, 56, 181, 155, 207, 195, 59, 180, 194, 77, 53, 231, 101, 98, 252, 75, 220, 68, 180, 22, 65, 49, 59, 12, 95, 202, 146, 108, 84, 211, 198, 66, 229, 42, 98, 6, 56, 103, 200, 186, 58, 10, 121, 118, 19, 94, 254, 59, 108, 73, 234, 38, 93, 85, 162, 209, 213, 103, 127, 73, 176, 158, 186, 56, 35, 74, 190, 56, 179, 149, 39, 168, 23, 66, 20, 78, 147, 67, 130, 178, 117, 100, 41, 42, 36, 214, 165, 56, 145, 107, 33, 72, 210, 63, 120, 231, 10, 89, 233, 128, 150, 201, 18, 3, 247, 121, 101, 33, 31, 46, 234, 47, 198, 252, 31, 89, 99, 78, 36, 107, 12, 158, 36, 118, 156, 229, 169, 79, 194, 124, 51, 213, 86, 80, 2, 143, 149, 27, 42, 48, 117, 201, 139, 135, 135, 70, 224, 61, 201, 28, 191, 221, 151, 37, 236, 9, 180, 77, 185, 15, 12, 102, 43, 165, 9, 110, 157, 171, 7, 106, 123, 123, 26, 202, 180, 235, 43, 121, 91, 101, 49, 173, 86, 195, 92, 171, 80, 99, 185, 231, 234, 86, 131, 20, 236, 129, 196, 11, 165, 119, 170, 230, 48, 222, 144, 32, 4, 0, 158, 136, 241, 141, 165];
         let expected_public_key = ByteArray::from(expected_public_key.as_slice());
@@ -482,18 +726,18 @@ mod tests {
     }
 
     #[test]
-    #[should_panic]
     fn test_failed_encryption_invalid_length_for_public_key() {
         let public_key = ByteArray::random(2);
         let message = ByteArray::random(2);
         let random_coin = ByteArray::random(2);
 
         let kyber = KyberCPAPKE512::init();
-        let _ = kyber.encrypt(public_key, message, random_coin);
+        let result = kyber.encrypt(public_key, message, random_coin);
+
+        assert!(matches!(result, Err(CryptumError::InvalidKeyLength { expected: 800, found: 2 })));
     }
 
     #[test]
-    #[should_panic]
     fn test_failed_encrypt_invalid_length_for_message() {
         let kyber = KyberCPAPKE512::init();
         let public_key_length = kyber.get_public_key_length();
@@ -501,11 +745,12 @@ mod tests {
         let message = ByteArray::random(2);
         let random_coin = ByteArray::random(2);
 
-        let _ = kyber.encrypt(public_key, message, random_coin);
+        let result = kyber.encrypt(public_key, message, random_coin);
+
+        assert!(matches!(result, Err(CryptumError::InvalidMessageLength { expected: 32, found: 2 })));
     }
 
     #[test]
-    #[should_panic]
     fn test_failed_encrypt_invalid_length_for_random_coin() {
         let kyber = KyberCPAPKE512::init();
         let public_key_length = kyber.get_public_key_length();
@@ -513,7 +758,9 @@ mod tests {
         let message = ByteArray::random(KYBER_MESSAGE_LENGTH);
         let random_coin = ByteArray::random(2);
 
-        let _ = kyber.encrypt(public_key, message, random_coin);
+        let result = kyber.encrypt(public_key, message, random_coin);
+
+        assert!(matches!(result, Err(CryptumError::InvalidSeedLength { expected: 32, found: 2 })));
     }
 
     #[test]
@@ -526,31 +773,32 @@ mod tests {
 
         let expected_length = kyber.get_ciphertext_length();
 
-        let encryption = kyber.encrypt(public_key, message, random_coin);
+        let encryption = kyber.encrypt(public_key, message, random_coin).unwrap();
 
         assert_eq!(encryption.length(), expected_length);
     }
 
     #[test]
-    #[should_panic]
-    fn test_decrypt_should_panic_invalid_length_secret_key() {
+    fn test_decrypt_should_fail_invalid_length_secret_key() {
         let secret_key = ByteArray::random(2);
         let ciphertext = ByteArray::random(2);
 
         let kyber = KyberCPAPKE512::init();
-        let _ = kyber.decrypt(secret_key, ciphertext);
+        let result = kyber.decrypt(secret_key, ciphertext);
 
+        assert!(matches!(result, Err(CryptumError::InvalidKeyLength { expected: 768, found: 2 })));
     }
 
     #[test]
-    #[should_panic]
-    fn test_decrypt_should_panic_invalid_length_ciphertext() {
+    fn test_decrypt_should_fail_invalid_length_ciphertext() {
         let kyber = KyberCPAPKE512::init();
         let secret_key_length = kyber.get_private_key_length();
         let secret_key = ByteArray::random(secret_key_length);
         let ciphertext = ByteArray::random(2);
 
-        let _ = kyber.decrypt(secret_key, ciphertext);
+        let result = kyber.decrypt(secret_key, ciphertext);
+
+        assert!(matches!(result, Err(CryptumError::InvalidCiphertextLength { expected: 768, found: 2 })));
     }
 
     #[test]
@@ -560,7 +808,7 @@ mod tests {
 
         let ciphertext = ByteArray::random(kyber.get_ciphertext_length());
 
-        let output = kyber.decrypt(secret_key, ciphertext);
+        let output = kyber.decrypt(secret_key, ciphertext).unwrap();
 
         assert_eq!(output.length(), KYBER_MESSAGE_LENGTH)
     }
@@ -570,7 +818,7 @@ mod tests {
         let kyber = KyberCPAPKE512::init();
         let seed = [0_u8; KYBER_N_VALUE_IN_BYTES];
 
-        let (public_key, private_key) = kyber.keygen(seed.as_slice().into());
+        let (public_key, private_key) = kyber.keygen(seed.as_slice().into()).unwrap();
 
         let random_coin = [0_u8; KYBER_RANDOM_COIN_LENGTH];
 
@@ -579,9 +827,9 @@ mod tests {
         message_data[0..original_message.len()].copy_from_slice(original_message);
         let message_bytes: ByteArray = message_data.as_slice().into();
 
-        let ciphertext = kyber.encrypt(public_key, message_bytes.clone(), random_coin.as_slice().into());
-        let plaintext = kyber.decrypt(private_key, ciphertext);
+        let ciphertext = kyber.encrypt(public_key, message_bytes.clone(), random_coin.as_slice().into()).unwrap();
+        let plaintext = kyber.decrypt(private_key, ciphertext).unwrap();
 
         assert_eq!(plaintext, message_bytes)
     }
-}
\ No newline at end of file
+}
@@ -1,9 +1,10 @@
 use crate::algorithms::byte_array::ByteArray;
+use crate::CryptumResult;
 
 pub trait Encoder {
     fn encode(&self, l_value: usize) -> ByteArray;
 }
 
-pub trait Decoder {
-    fn decode(bytes: ByteArray, l_value: u8) -> Self;
+pub trait Decoder: Sized {
+    fn decode(bytes: ByteArray, l_value: u8) -> CryptumResult<Self>;
 }
\ No newline at end of file
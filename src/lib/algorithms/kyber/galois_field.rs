@@ -1,4 +1,188 @@
-use crate::algorithms::algebraic::galois_field::GaloisFieldCore;
+use zeroize::Zeroize;
+use crate::algorithms::algebraic::galois_field::GaloisField;
 use crate::algorithms::kyber::constants::KYBER_Q_VALUE;
 
-pub type GF3329 = GaloisFieldCore<KYBER_Q_VALUE>;
\ No newline at end of file
+pub(crate) const Q: i16 = KYBER_Q_VALUE as i16;
+
+/// q^(-1) mod 2^16 as a signed value, used by the Montgomery reduction
+pub(crate) const Q_INVERSE: i16 = -3327;
+
+/// 2^32 mod q, multiplying a Montgomery reduced value by this constant cancels the factor 2^(-16)
+pub(crate) const MONTGOMERY_R_SQUARE: i32 = 1353;
+
+/// floor(2^32 / q), used by the Barrett reduction
+const BARRETT_MULTIPLIER: u64 = (1 << 32) / KYBER_Q_VALUE as u64;
+
+/// This structure corresponds to an element of Z_q with q = 3329. The value is always stored as
+/// its canonical representative in [0, q) so that two equal elements have the same representation.
+///
+/// Unlike `GaloisFieldCore`, the arithmetic never relies on a division: additions and
+/// subtractions use a branchless conditional correction, multiplications use the Montgomery
+/// reduction and conversions from integers use the Barrett reduction.
+///
+/// The layout is the one of a `u16` so that the vectorized backends can load and store arrays of
+/// elements directly.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct GF3329(u16);
+
+impl GF3329 {
+    pub const fn new(value: u16) -> Self {
+        Self(value % KYBER_Q_VALUE as u16)
+    }
+
+    pub fn into_inner(self) -> usize {
+        self.0 as usize
+    }
+
+    /// This function returns the Montgomery form of the element, i.e. value * 2^16 mod q.
+    pub const fn to_montgomery(self) -> i16 {
+        barrett_reduce((self.0 as u32) << 16) as i16
+    }
+
+    /// This function multiplies the element by a factor given in Montgomery form with a single
+    /// Montgomery reduction.
+    /// Input:
+    ///     factor: The Montgomery form of the factor, as returned by to_montgomery
+    /// Output:
+    ///     The product of the element by the factor
+    pub fn mul_montgomery(&self, factor: i16) -> Self {
+        Self(normalize(montgomery_reduce(self.0 as i32 * factor as i32)))
+    }
+}
+
+/// This function maps a value in (-q, q) to its canonical representative in [0, q).
+const fn normalize(value: i16) -> u16 {
+    (value + ((value >> 15) & Q)) as u16
+}
+
+/// This function maps a value in [0, 2q) to its canonical representative in [0, q).
+const fn reduce_once(value: i16) -> u16 {
+    normalize(value - Q)
+}
+
+/// This function corresponds to the Montgomery reduction with R = 2^16.
+/// Input:
+///     value: An integer in (-q * 2^15, q * 2^15)
+/// Output:
+///     An integer in (-q, q) congruent to value * 2^(-16) mod q
+pub const fn montgomery_reduce(value: i32) -> i16 {
+    let t = (value as i16).wrapping_mul(Q_INVERSE);
+    ((value - t as i32 * Q as i32) >> 16) as i16
+}
+
+/// This function corresponds to the Barrett reduction.
+/// Input:
+///     value: Any 32-bits unsigned integer
+/// Output:
+///     The canonical representative of value mod q
+pub const fn barrett_reduce(value: u32) -> u16 {
+    let quotient = (value as u64 * BARRETT_MULTIPLIER) >> 32;
+    // The approximated quotient is either exact or one less than the exact quotient, so the
+    // remainder lies in [0, 2q).
+    let remainder = value - quotient as u32 * KYBER_Q_VALUE as u32;
+    reduce_once(remainder as i16)
+}
+
+impl From<usize> for GF3329 {
+    fn from(value: usize) -> Self {
+        match u32::try_from(value) {
+            Ok(value) => Self(barrett_reduce(value)),
+            Err(_) => Self((value % KYBER_Q_VALUE) as u16)
+        }
+    }
+}
+
+impl From<i32> for GF3329 {
+    fn from(value: i32) -> Self {
+        let reduced = barrett_reduce(value.unsigned_abs());
+        if value < 0 {
+            Self(normalize(-(reduced as i16)))
+        } else {
+            Self(reduced)
+        }
+    }
+}
+
+impl Zeroize for GF3329 {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl GaloisField for GF3329 {
+    fn is_zero(&self) -> bool {
+        self.0 == 0
+    }
+
+    fn zero() -> Self {
+        Self(0)
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        Self(reduce_once((self.0 + other.0) as i16))
+    }
+
+    fn sub(&self, other: &Self) -> Self {
+        Self(normalize(self.0 as i16 - other.0 as i16))
+    }
+
+    fn mul(&self, other: &Self) -> Self {
+        // The first reduction gives a.b.2^(-16), multiplying by 2^32 in Montgomery form gives a.b
+        let product = montgomery_reduce(self.0 as i32 * other.0 as i32);
+        Self(normalize(montgomery_reduce(product as i32 * MONTGOMERY_R_SQUARE)))
+    }
+
+    fn value(&self) -> usize {
+        self.0 as usize
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::algorithms::algebraic::galois_field::GaloisField;
+    use crate::algorithms::kyber::constants::KYBER_Q_VALUE;
+    use crate::algorithms::kyber::galois_field::{barrett_reduce, montgomery_reduce, GF3329};
+
+    #[test]
+    fn test_barrett_reduce() {
+        for value in (0..u32::MAX).step_by(65537).chain([u32::MAX - 1, u32::MAX]) {
+            assert_eq!(barrett_reduce(value) as u32, value % KYBER_Q_VALUE as u32, "value = {}", value);
+        }
+    }
+
+    #[test]
+    fn test_montgomery_reduce() {
+        let q = KYBER_Q_VALUE as i64;
+        for value in (-(q << 15) + 1..(q << 15)).step_by(997) {
+            let reduced = montgomery_reduce(value as i32) as i64;
+            assert!(reduced.abs() < q, "value = {}", value);
+            assert_eq!((reduced << 16).rem_euclid(q), value.rem_euclid(q), "value = {}", value);
+        }
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let q = KYBER_Q_VALUE;
+        for a in (0..q).step_by(7) {
+            for b in (0..q).step_by(11) {
+                let (x, y) = (GF3329::from(a), GF3329::from(b));
+                assert_eq!(x.add(&y).value(), (a + b) % q);
+                assert_eq!(x.sub(&y).value(), (a + q - b) % q);
+                assert_eq!(x.mul(&y).value(), (a * b) % q);
+                assert_eq!(x.mul_montgomery(y.to_montgomery()).value(), (a * b) % q);
+            }
+        }
+    }
+
+    #[test]
+    fn test_from_integers() {
+        assert_eq!(GF3329::from(3329_usize), GF3329::zero());
+        assert_eq!(GF3329::from(usize::MAX).value(), usize::MAX % KYBER_Q_VALUE);
+        assert_eq!(GF3329::from(-1_i32).value(), 3328);
+        assert_eq!(GF3329::from(-3329_i32), GF3329::zero());
+        assert_eq!(GF3329::from(i32::MIN).value(), (i32::MIN as i64).rem_euclid(3329) as usize);
+        assert_eq!(GF3329::new(3330).value(), 1);
+    }
+}
@@ -0,0 +1,186 @@
+use std::fs;
+use std::path::PathBuf;
+use crate::algorithms::byte_array::ByteArray;
+use crate::algorithms::kyber::kem::KyberKEM;
+use crate::algorithms::utils::drbg::{AesCtrDrbg, DRBG_SEED_LENGTH};
+
+const KAT_SHARED_SECRET_LENGTH: u8 = 32;
+
+/// This structure corresponds to one entry of a `PQCkemKAT_*.rsp` file.
+#[derive(Debug)]
+struct KatEntry {
+    count: usize,
+    seed: ByteArray,
+    public_key: ByteArray,
+    private_key: ByteArray,
+    ciphertext: ByteArray,
+    shared_secret: ByteArray
+}
+
+/// This function parses the content of a `PQCkemKAT_*.rsp` file. Every entry starts with a
+/// `count` line followed by the `seed`, `pk`, `sk`, `ct` and `ss` values encoded in hexadecimal.
+///
+/// Input:
+///     content: The content of the rsp file
+/// Output:
+///     The list of the entries of the file
+fn parse_rsp(content: &str) -> Vec<KatEntry> {
+    let mut entries: Vec<KatEntry> = Vec::new();
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (name, value) = line
+            .split_once('=')
+            .unwrap_or_else(|| panic!("Invalid line in rsp file: {}", line));
+        let (name, value) = (name.trim(), value.trim());
+
+        if name == "count" {
+            entries.push(KatEntry {
+                count: value.parse().expect("Invalid count in rsp file"),
+                seed: ByteArray::empty(),
+                public_key: ByteArray::empty(),
+                private_key: ByteArray::empty(),
+                ciphertext: ByteArray::empty(),
+                shared_secret: ByteArray::empty()
+            });
+            continue;
+        }
+
+        let entry = entries.last_mut().expect("The rsp file should start with a count");
+        let value = ByteArray::from_hex(value.to_string()).expect("Invalid hex value in rsp file");
+
+        match name {
+            "seed" => entry.seed = value,
+            "pk" => entry.public_key = value,
+            "sk" => entry.private_key = value,
+            "ct" => entry.ciphertext = value,
+            "ss" => entry.shared_secret = value,
+            _ => panic!("Unknown field {} in rsp file", name)
+        }
+    }
+
+    entries
+}
+
+/// This function replays a KAT entry the same way as the `PQCgenKAT_kem` program: the DRBG is
+/// seeded with the seed of the entry, then d and z are drawn for the key generation and a last
+/// 32-bytes value is drawn for the encapsulation.
+///
+/// Input:
+///     kem: The KEM under test
+///     entry: The entry to replay
+fn replay(kem: &dyn KyberKEM, entry: &KatEntry) {
+    let seed: [u8; DRBG_SEED_LENGTH] = entry.seed
+        .get_bytes()
+        .try_into()
+        .unwrap_or_else(|_| panic!("Invalid seed length for count {}", entry.count));
+    let mut drbg = AesCtrDrbg::init(&seed);
+
+    let d = drbg.random_bytes(32);
+    let z = drbg.random_bytes(32);
+    let (public_key, private_key) = kem.keygen([d, z].concat().into()).unwrap();
+
+    assert_eq!(public_key, entry.public_key, "pk mismatch for count {}", entry.count);
+    assert_eq!(private_key, entry.private_key, "sk mismatch for count {}", entry.count);
+
+    let m = drbg.random_bytes(32);
+    let (ciphertext, shared_secret) = kem.encrypt(public_key, m.into(), KAT_SHARED_SECRET_LENGTH).unwrap();
+
+    assert_eq!(ciphertext, entry.ciphertext, "ct mismatch for count {}", entry.count);
+    assert_eq!(shared_secret, entry.shared_secret, "ss mismatch for count {}", entry.count);
+
+    let shared_secret_prime = kem.decrypt(ciphertext, private_key, KAT_SHARED_SECRET_LENGTH).unwrap();
+
+    assert_eq!(shared_secret_prime, entry.shared_secret, "decapsulation mismatch for count {}", entry.count);
+}
+
+/// This function replays every entry of a rsp file located in the `tests/kat` directory. The files
+/// have the format of the NIST files, whose counts start from 0, but were generated with this
+/// implementation. The official files can replace them.
+fn replay_file(kem: &dyn KyberKEM, file_name: &str) {
+    let path: PathBuf = [env!("CARGO_MANIFEST_DIR"), "tests", "kat", file_name].iter().collect();
+    let content = fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("Unable to read {}: {}", path.display(), e));
+
+    let entries = parse_rsp(&content);
+    assert!(!entries.is_empty(), "No entry found in {}", path.display());
+
+    for (count, entry) in entries.iter().enumerate() {
+        assert_eq!(entry.count, count, "Missing entry in {}", path.display());
+        replay(kem, entry);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hex_literal::hex;
+    use crate::algorithms::byte_array::ByteArray;
+    use crate::algorithms::kyber::kat::{parse_rsp, replay_file};
+    use crate::algorithms::kyber::kem::{KyberKEM, KyberKEM1024, KyberKEM512, KyberKEM768};
+    use crate::algorithms::utils::drbg::AesCtrDrbg;
+
+    #[test]
+    fn test_parse_rsp() {
+        let content = "# Kyber512\n\n\
+            count = 0\n\
+            seed = 0102\n\
+            pk = 03\n\
+            sk = 04\n\
+            ct = 05\n\
+            ss = 06\n\n\
+            count = 1\n\
+            seed = 07\n";
+
+        let entries = parse_rsp(content);
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].count, 0);
+        assert_eq!(entries[0].seed, ByteArray::from([1u8, 2].as_slice()));
+        assert_eq!(entries[0].public_key, ByteArray::from(3u8));
+        assert_eq!(entries[0].private_key, ByteArray::from(4u8));
+        assert_eq!(entries[0].ciphertext, ByteArray::from(5u8));
+        assert_eq!(entries[0].shared_secret, ByteArray::from(6u8));
+        assert_eq!(entries[1].count, 1);
+        assert_eq!(entries[1].seed, ByteArray::from(7u8));
+    }
+
+    #[test]
+    fn test_kyber512_first_shared_secret() {
+        // Shared secret of the first entry of PQCkemKAT_1632.rsp, the randomness is derived
+        // from the seed of this entry.
+        let seed = hex!("061550234D158C5EC95595FE04EF7A25767F2E24CC2BC479D09D86DC9ABCFDE7056A8C266F9EF97ED08541DBD2E1FFA1");
+        let mut drbg = AesCtrDrbg::init(&seed);
+
+        let kyber = KyberKEM512::init();
+        let d = drbg.random_bytes(32);
+        let z = drbg.random_bytes(32);
+        let (public_key, private_key) = kyber.keygen([d, z].concat().into()).unwrap();
+        let (ciphertext, shared_secret) = kyber.encrypt(public_key, drbg.random_bytes(32).into(), 32).unwrap();
+
+        let expected_shared_secret: ByteArray = hex!("0A6925676F24B22C286F4C81A4224CEC506C9B257D480E02E3B49F44CAA3237F")
+            .as_slice()
+            .into();
+
+        assert_eq!(shared_secret, expected_shared_secret);
+        assert_eq!(kyber.decrypt(ciphertext, private_key, 32).unwrap(), expected_shared_secret);
+    }
+
+    #[test]
+    fn test_kat_kyber512() {
+        replay_file(&KyberKEM512::init(), "PQCkemKAT_1632.rsp");
+    }
+
+    #[test]
+    fn test_kat_kyber768() {
+        replay_file(&KyberKEM768::init(), "PQCkemKAT_2400.rsp");
+    }
+
+    #[test]
+    fn test_kat_kyber1024() {
+        replay_file(&KyberKEM1024::init(), "PQCkemKAT_3168.rsp");
+    }
+}
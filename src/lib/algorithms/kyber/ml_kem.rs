@@ -0,0 +1,443 @@
+use crate::algorithms::byte_array::ByteArray;
+use crate::algorithms::kyber::constants::{KYBER_KEM_SEED_LENGTH, ML_KEM_SHARED_SECRET_LENGTH};
+use crate::algorithms::kyber::cpapke::{KyberPKE, KyberCPAPKE1024, KyberCPAPKE512, KyberCPAPKE768, KyberCPAPKECore};
+use crate::algorithms::kyber::kem::KyberKEM;
+use crate::algorithms::kyber::prepared::{PreparedPublicKey, PreparedSecretKey};
+use crate::algorithms::kyber::types::{ciphertext_length, cpapke_public_key_length, kem_private_key_length, ParameterSet};
+use crate::algorithms::utils::constant_time::{ct_eq, ct_select};
+use crate::algorithms::kyber::utils::{g, h};
+use crate::algorithms::utils::hash::shake_256;
+use crate::CryptumResult;
+use crate::errors::CryptumError;
+
+/// This structure implements the ML-KEM key-encapsulation mechanism as standardized in FIPS 203.
+/// Contrary to the round 3 version of Kyber (see `KyberKEMCore`), the shared secret is directly
+/// taken from the output of G and always has a length of 32 bytes.
+pub struct MLKEMCore<const V: usize>(KyberCPAPKECore<V>);
+
+
+impl <const V: usize> KyberKEM for MLKEMCore<V> {
+    /// This function corresponds to the ML-KEM.KeyGen_internal function (Algorithm 16)
+    ///
+    /// Input:
+    ///     seed: A 64-bytes array corresponding to the concatenation of d and z
+    /// Output:
+    ///     A bytes array of length (12·k·n)/8 + 32
+    ///     A bytes array of length (24·k·n)/8 + 96
+    fn keygen(&self, seed: ByteArray) -> CryptumResult<(ByteArray, ByteArray)> {
+        // Checking length of seed
+        if seed.length() != KYBER_KEM_SEED_LENGTH {
+            return Err(CryptumError::InvalidSeedLength { expected: KYBER_KEM_SEED_LENGTH, found: seed.length() });
+        }
+        let (d, z) = seed.split_at(32);
+
+        let (public_key, private_key_prime) = self.0.keygen_ml_kem(d)?;
+        let hash = h(&public_key);
+
+        let secret_key = ByteArray::concat(
+            &[&private_key_prime, &public_key, &hash, &z]
+        );
+
+        Ok((public_key, secret_key))
+    }
+
+    /// This function corresponds to the ML-KEM.Encaps_internal function (Algorithm 17).
+    ///
+    /// Input:
+    ///     public_key: A bytes array representing a public_key generating by the keygen function.
+    ///     seed: A bytes array filled with random values. It should be of length 32
+    ///     shared_secret_key_size: The size of the shared secret key, it should be equal to 32
+    /// Output:
+    ///     A tuple containing the ciphertext and the shared secret
+    fn encrypt(&self, public_key: ByteArray, seed: ByteArray, shared_secret_key_size: u8) -> CryptumResult<(ByteArray, ByteArray)> {
+        let public_key = self.prepare_public_key(public_key)?;
+
+        self.encapsulate_prepared(&public_key, seed, shared_secret_key_size)
+    }
+
+    /// This function corresponds to the ML-KEM.Decaps_internal function (Algorithm 18).
+    ///
+    /// Input:
+    ///     ciphertext: A ciphertext produced by the encrypt function
+    ///     private_key: A private_key generated by the keygen function
+    ///     shared_secret_key_size: The size of the shared secret key, it should be equal to 32
+    /// Output:
+    ///     A shared secret that can be used to encrypt data
+    fn decrypt(&self, ciphertext: ByteArray, private_key: ByteArray, shared_secret_key_size: u8) -> CryptumResult<ByteArray> {
+        let private_key = self.prepare_private_key(private_key)?;
+
+        self.decapsulate_prepared(&private_key, ciphertext, shared_secret_key_size)
+    }
+
+    /// This function decodes a public key once so that it can be used by several calls to the
+    /// encapsulate_prepared function. The key must pass the modulus check of FIPS 203.
+    ///
+    /// Input:
+    ///     public_key: A bytes array representing a public_key generating by the keygen function.
+    /// Output:
+    ///     The public key with its decoded t_hat, its transposed A_hat and its hash H(ek)
+    fn prepare_public_key(&self, public_key: ByteArray) -> CryptumResult<PreparedPublicKey> {
+        PreparedPublicKey::prepare(&self.0, public_key)
+    }
+
+    /// This function corresponds to the ML-KEM.Encaps_internal function (Algorithm 17) with a
+    /// public key prepared by the prepare_public_key function.
+    ///
+    /// Input:
+    ///     public_key: A public key prepared for this parameter set
+    ///     seed: A bytes array filled with random values. It should be of length 32
+    ///     shared_secret_key_size: The size of the shared secret key, it should be equal to 32
+    /// Output:
+    ///     A tuple containing the ciphertext and the shared secret
+    fn encapsulate_prepared(&self, public_key: &PreparedPublicKey, seed: ByteArray, shared_secret_key_size: u8) -> CryptumResult<(ByteArray, ByteArray)> {
+        // Checking the length of the seed
+        if seed.length() != 32 {
+            return Err(CryptumError::InvalidSeedLength { expected: 32, found: seed.length() });
+        }
+
+        // Checking the size of the shared secret
+        if shared_secret_key_size as usize != ML_KEM_SHARED_SECRET_LENGTH {
+            return Err(CryptumError::InvalidSharedSecretLength {
+                expected: ML_KEM_SHARED_SECRET_LENGTH,
+                found: shared_secret_key_size as usize
+            });
+        }
+
+        // Checking that the public key has been prepared for the same parameter set
+        public_key.check_length(Self::PUBLIC_KEY_LENGTH)?;
+
+        // Contrary to round 3, the random message is not hashed
+        let (shared_secret, r) = g(
+            &ByteArray::concat(
+                &[&seed, public_key.hash()]
+            )
+        );
+
+        let ciphertext = self.0.encrypt_prepared(
+            public_key.t_hat(),
+            public_key.a_hat_transposed(),
+            seed,
+            r
+        )?;
+
+        Ok((ciphertext, shared_secret))
+    }
+
+    /// This function decodes a private key once so that it can be used by several calls to the
+    /// decapsulate_prepared function. The key must pass the hash check of FIPS 203.
+    ///
+    /// Input:
+    ///     private_key: A private_key generated by the keygen function
+    /// Output:
+    ///     The private key with its decoded s_hat and its prepared public key
+    fn prepare_private_key(&self, private_key: ByteArray) -> CryptumResult<PreparedSecretKey> {
+        PreparedSecretKey::prepare(&self.0, private_key)
+    }
+
+    /// This function corresponds to the ML-KEM.Decaps_internal function (Algorithm 18) with a
+    /// private key prepared by the prepare_private_key function.
+    ///
+    /// Input:
+    ///     private_key: A private key prepared for this parameter set
+    ///     ciphertext: A ciphertext produced by the encrypt function
+    ///     shared_secret_key_size: The size of the shared secret key, it should be equal to 32
+    /// Output:
+    ///     A shared secret that can be used to encrypt data
+    fn decapsulate_prepared(&self, private_key: &PreparedSecretKey, ciphertext: ByteArray, shared_secret_key_size: u8) -> CryptumResult<ByteArray> {
+        // Checking the size of the shared secret
+        if shared_secret_key_size as usize != ML_KEM_SHARED_SECRET_LENGTH {
+            return Err(CryptumError::InvalidSharedSecretLength {
+                expected: ML_KEM_SHARED_SECRET_LENGTH,
+                found: shared_secret_key_size as usize
+            });
+        }
+
+        // Checking the length of ciphertext
+        let expected_ciphertext_length = self.0.get_ciphertext_length();
+
+        if ciphertext.length() != expected_ciphertext_length {
+            return Err(CryptumError::InvalidCiphertextLength {
+                expected: expected_ciphertext_length,
+                found: ciphertext.length()
+            });
+        }
+
+        // Checking that the private key has been prepared for the same parameter set
+        let public_key = private_key.public_key();
+        public_key.check_length(Self::PUBLIC_KEY_LENGTH)?;
+
+        let m_prime = self.0.decrypt_prepared(private_key.s_hat(), ciphertext.clone())?;
+
+        let (k_prime, r_prime) = g(
+            &ByteArray::concat(
+                &[&m_prime, public_key.hash()]
+            )
+        );
+
+        let k_bar = self.j(&ByteArray::concat(&[private_key.z(), &ciphertext]));
+
+        let c_prime = self.0.encrypt_prepared(public_key.t_hat(), public_key.a_hat_transposed(), m_prime, r_prime)?;
+
+        // The implicit rejection key is always computed and selected in constant time
+        let is_valid = ct_eq(&ciphertext, &c_prime);
+
+        Ok(ct_select(&k_bar, &k_prime, is_valid))
+    }
+}
+
+impl MLKEMCore<512> {
+    pub fn init() -> Self {
+        Self (KyberCPAPKE512::init())
+    }
+}
+
+impl MLKEMCore<768> {
+    pub fn init() -> Self {
+        Self(KyberCPAPKE768::init())
+    }
+}
+
+impl MLKEMCore<1024> {
+    pub fn init() -> Self {
+        Self (KyberCPAPKE1024::init())
+    }
+}
+
+impl <const V: usize> MLKEMCore<V> {
+    /// This function corresponds to the J function of FIPS 203 (section 4.1) which is used to
+    /// derive the implicit rejection key from z and the ciphertext.
+    ///
+    /// Input:
+    ///     data: A bytes array
+    /// Output:
+    ///     A bytes array of length 32
+    fn j(&self, data: &ByteArray) -> ByteArray {
+        shake_256(data.get_bytes(), ML_KEM_SHARED_SECRET_LENGTH).into()
+    }
+}
+
+impl <const V: usize> ParameterSet for MLKEMCore<V> {
+    const PUBLIC_KEY_LENGTH: usize = cpapke_public_key_length(V);
+    const SECRET_KEY_LENGTH: usize = kem_private_key_length(V);
+    const CIPHERTEXT_LENGTH: usize = ciphertext_length(V);
+}
+
+pub type MLKEM512 = MLKEMCore<512>;
+pub type MLKEM768 = MLKEMCore<768>;
+pub type MLKEM1024 = MLKEMCore<1024>;
+
+#[cfg(test)]
+mod tests {
+    use hex_literal::hex;
+    use sha3::Shake128;
+    use sha3::digest::{ExtendableOutput, Update, XofReader};
+    use crate::algorithms::byte_array::ByteArray;
+    use crate::algorithms::kyber::kem::{KyberKEM, KyberKEM512};
+    use crate::algorithms::kyber::ml_kem::{MLKEM1024, MLKEM512, MLKEM768};
+    use crate::algorithms::utils::hash::{sha3_256, shake_256, take_hash_trace};
+    use crate::errors::CryptumError;
+
+    #[test]
+    fn test_keygen_length() {
+        let kyber = MLKEM768::init();
+        let (public_key, private_key) = kyber.keygen(ByteArray::random(64)).unwrap();
+
+        assert_eq!(public_key.length(), 1184);
+        assert_eq!(private_key.length(), 2400);
+    }
+
+    #[test]
+    fn test_keygen_differs_from_round_3() {
+        let seed = ByteArray::from([0u8; 64].as_slice());
+
+        let (public_key, _) = MLKEM512::init().keygen(seed.clone()).unwrap();
+        let (round_3_public_key, _) = KyberKEM512::init().keygen(seed).unwrap();
+
+        assert_ne!(public_key, round_3_public_key);
+    }
+
+    #[test]
+    fn test_expand_private_key() {
+        let kyber = MLKEM512::init();
+        let seed = ByteArray::random(64);
+        let (public_key, private_key) = kyber.keygen(seed.clone()).unwrap();
+
+        let expanded_private_key = kyber.expand_private_key(seed).unwrap();
+        assert_eq!(expanded_private_key, private_key);
+
+        let (ciphertext, shared_secret) = kyber.encrypt(public_key, ByteArray::random(32), 32).unwrap();
+        assert_eq!(kyber.decrypt(ciphertext, expanded_private_key, 32).unwrap(), shared_secret);
+
+        let result = kyber.expand_private_key(ByteArray::random(32));
+        assert!(matches!(result, Err(CryptumError::InvalidSeedLength { expected: 64, found: 32 })));
+    }
+
+    #[test]
+    fn test_extract_public_key() {
+        let kyber = MLKEM768::init();
+        let (public_key, private_key) = kyber.keygen(ByteArray::random(64)).unwrap();
+
+        assert_eq!(kyber.extract_public_key(private_key.clone()).unwrap(), public_key);
+
+        // The embedded public key no longer matches the stored hash
+        let mut private_key_bytes = private_key.get_bytes().to_vec();
+        private_key_bytes[1152] ^= 1;
+        let result = kyber.extract_public_key(private_key_bytes.into());
+        assert!(matches!(result, Err(CryptumError::InvalidDecapsulationKey)));
+
+        let result = kyber.extract_public_key(public_key);
+        assert!(matches!(result, Err(CryptumError::InvalidKeyLength { expected: 2400, found: 1184 })));
+    }
+
+    #[test]
+    fn test_encrypt_decrypt() {
+        let kyber = MLKEM1024::init();
+        let (public_key, private_key) = kyber.keygen(ByteArray::random(64)).unwrap();
+        let (ciphertext, shared_secret) = kyber.encrypt(public_key, ByteArray::random(32), 32).unwrap();
+        let shared_secret_prime = kyber.decrypt(ciphertext, private_key, 32).unwrap();
+
+        assert_eq!(shared_secret.length(), 32);
+        assert_eq!(shared_secret, shared_secret_prime);
+    }
+
+    #[test]
+    fn test_decrypt_implicit_rejection() {
+        let kyber = MLKEM512::init();
+        let (public_key, private_key) = kyber.keygen(ByteArray::random(64)).unwrap();
+        let (ciphertext, shared_secret) = kyber.encrypt(public_key, ByteArray::random(32), 32).unwrap();
+
+        let mut altered_ciphertext = ciphertext.get_bytes().to_vec();
+        altered_ciphertext[0] ^= 1;
+        let altered_ciphertext = ByteArray::from(altered_ciphertext);
+
+        let z = private_key.slice(private_key.length() - 32);
+        let expected_shared_secret: ByteArray = shake_256(
+            ByteArray::concat(&[&z, &altered_ciphertext]).get_bytes(),
+            32
+        ).into();
+
+        let shared_secret_prime = kyber.decrypt(altered_ciphertext, private_key, 32).unwrap();
+
+        assert_ne!(shared_secret, shared_secret_prime);
+        assert_eq!(shared_secret_prime, expected_shared_secret);
+    }
+
+    #[test]
+    fn test_decrypt_same_operations_on_both_paths() {
+        let kyber = MLKEM768::init();
+        let (public_key, private_key) = kyber.keygen(ByteArray::random(64)).unwrap();
+        let (ciphertext, _) = kyber.encrypt(public_key, ByteArray::random(32), 32).unwrap();
+
+        let mut altered_ciphertext = ciphertext.get_bytes().to_vec();
+        altered_ciphertext[0] ^= 1;
+
+        take_hash_trace();
+        kyber.decrypt(ciphertext, private_key.clone(), 32).unwrap();
+        let valid_trace = take_hash_trace();
+
+        kyber.decrypt(altered_ciphertext.into(), private_key, 32).unwrap();
+        let rejection_trace = take_hash_trace();
+
+        assert!(!valid_trace.is_empty());
+        assert_eq!(valid_trace, rejection_trace);
+    }
+
+    #[test]
+    fn test_encrypt_should_fail_invalid_shared_secret_size() {
+        let kyber = MLKEM512::init();
+        let (public_key, _) = kyber.keygen(ByteArray::random(64)).unwrap();
+        let result = kyber.encrypt(public_key, ByteArray::random(32), 64);
+
+        assert!(matches!(result, Err(CryptumError::InvalidSharedSecretLength { expected: 32, found: 64 })));
+    }
+
+    /// This function checks a FIPS 203 vector generated with OpenSSL 3.5 from the seed
+    /// (d, z) = 00 01 ... 3f: the key pair is compared through the SHA3-256 digests of the keys,
+    /// then the ciphertext is decapsulated, as well as the ciphertext whose first bit is flipped
+    /// which must give the implicit rejection key J(z || c).
+    fn check_vector(kem: &dyn KyberKEM, public_key_digest: [u8; 32], private_key_digest: [u8; 32], ciphertext: &[u8], shared_secret: [u8; 32], rejection_shared_secret: [u8; 32]) {
+        let seed: Vec<u8> = (0..64).collect();
+        let (public_key, private_key) = kem.keygen(seed.into()).unwrap();
+
+        assert_eq!(sha3_256(public_key.get_bytes()), public_key_digest);
+        assert_eq!(sha3_256(private_key.get_bytes()), private_key_digest);
+
+        let decapsulated = kem.decrypt(ciphertext.into(), private_key.clone(), 32).unwrap();
+        assert_eq!(decapsulated.get_bytes(), shared_secret.as_slice());
+
+        let mut invalid_ciphertext = ciphertext.to_vec();
+        invalid_ciphertext[0] ^= 1;
+        let rejected = kem.decrypt(invalid_ciphertext.into(), private_key, 32).unwrap();
+        assert_eq!(rejected.get_bytes(), rejection_shared_secret.as_slice());
+    }
+
+    #[test]
+    fn test_fips_203_vector_512() {
+        check_vector(
+            &MLKEM512::init(),
+            hex!("82F101FF648063B376E2BB6C5B7455F655A50C2FEADADE150EFA0E0E6F365AEA"),
+            hex!("0BD3F5DF01098AC9C29D687C7F1BD0588A5573FEEEF8F1E3B4573FA7F6AB57C8"),
+            &hex!("A17E3D22BA20A3C34068F28D9B1A45B70D6DE008FB4AEFCE8C7E942098E1AE24BA4719B34AA3E4D4C574EF41D664E65F35630FD40B55C8B3FD9389EF76280EFE0BC2B3FB8BF8754770F4202AC1C13E9AC540E5FB9711E7941A7A23AA4E1D75348CAA6D35279C9C08E2D6D1EB5934168090E75385713969ECCFA807A9BA80BEB755F4E7895BCD4664C598FF4E1504B11C6023BE00346B4B1206925E201E941B7AFEABE44352B60E855D7C6394A2F99FBC0F3F4A6EE7D22B064ACF9B2F10D82E159EFB10EE35AA62F306471363F5B61F9A4313073774901EB95A0DEDFF69DA8BA2A10BB55350E45F2527C90AAD19049E9CEFB165A580F55DE60045729B817299EDCA89AEF1627EA88FFF091010BB8201438692228FD94A35F11E6069A388DDA4ECF9D4C5C43334759F6AAEC6D6CAB30DA6681B615E6B51A4A9534D302F50178F073B9F4880E05DDBCADC4185D8D33699C3AC97F9E24909BEDB9167A8C0A18338FB2F83A6F30B539A04A3C11ABD10CA6A3446BEA0B2ED7F3E7EBFFC1A4D5B2273EBD36199C1C16516E1C30068216D86065BAE5FD79C1B635410C4423284E80DEF16388084E40C220C4FCFB59DFF987FE9F88162D56597DE2C53BD7C9D02CC5CDFF2FEB206AEAA62E6224CE8623AC6CB1F826EAFCB04E9B10A688E232CF7435A5973C0C224D1B6E35C95E49E6E84AE5EA8E09599483E6520069EEAAE0031F4C4929476AA0F1FA70669515D6761DFC4D0696B26FC256553F9165105BEAEBB6D428AC26373A60F26497A0B08A8471F2716A27CF44E3B65365EB1EDF31779102A885EFF6E8EA1053F4A7E7DF6584F2916D358AA5E615E10F5260188521CB8D5287035D46D575C327871E4CDCE3C4BACD184513F96880006A56E20B5ECD5EEE8D13407501D1EC0366656B9CEA1FFD11C28E053CD50778B4EFB5E116D9A5C928B55A05362C92F5B64FC850C6F1C45477CF287C76369CD5C546B7F359E704CF101B92BB86C1EF7AE67530DD0A28B553CDAA9AFFE6F039CC08BE6F2BBB429D3A3A0BB241A2F09C07B7376FB5AC54D84E998F16AB32CFF32AE35E80BB2178021235C8BB3B9B3"),
+            hex!("299224221D627901D6D15C512018E474BAB9CD4D67EE8705C425B25AAF3EBA82"),
+            hex!("05EC8E94EB8D780F7EE87258C90EC719D4DA4F9D83D7312C0D549F5ACB79646C")
+        );
+    }
+
+    #[test]
+    fn test_fips_203_vector_768() {
+        check_vector(
+            &MLKEM768::init(),
+            hex!("A24E16D8F8F9383A95B77050F4D9FD2F5733EEC1D63EF3C23EBF9918173669A7"),
+            hex!("1149F17C3C4AC6AB1E3E2D9D8BD0171355AC0FA31BB8855C48CEADE874C0864B"),
+            &hex!("54FA13D1A451628F28A5CD99A1E1A04F795A2F2DBACBB36623A30F296ADF44EE52E0D01314312A57B2C472319BE55929FD62A04F14CB77DA9F370CA0849923150FD3A7C3D5C1C94278B4F313BE072F7F03AF096154DDD89E1D91ACC6F0792CC83A623D2E4D31061027C5FEFBD9A16EA20B756A7D555C25BA34469B096D81C9BE7A1C2A2E4031CAE4F1BE48F7468EB9625ABABF786EA3CB488F60DD804D9E5EB84C40CB9DF2664E74EE66415FB867A7670777D0DF5925B68F3A41BBCCE2761B36C58BD8C2BC174A228FD5AE90AF477992DDAC12AD2FEBE48FB65F542EEADFAA7E96991F41EC8BA270BDAAE6B62730D061A7B756D9AE16A9E6864DAB932C475ECAEE524BC29F27E5C1882AA1A6635C191B78F9B4EFEA796518C40177051E5808E1BD1414062598B7262C51B8EEAD823D2237502E00EEEA452753062D9F4B84B5730F15E1C767EE19CA9FF81C1E758F984A98FE189841115E711805E62E57996066E77748422B6584AD30A85ACDD5CFABE077691027F877209C7580387924566CFB2B63038BC4C28994B348B33974C2195DF27B6C892E3AE84F850580658399BD4040053D71D6CAD1904A33242F20DAEA1FDCA3E006337826E2FADC1A7AC21B566D386C4F9F8EC519A9263A4C1FE4D95948130FBC1E66E95C2DB6355887D11B32066DF40BF7D5D4F1CEEE55B348DD6EA2BDBB4C9FACD4F50E1CA792FEBC793049E7C893BA49843ACBA8183DF32383F736535702C279EA7A0C88E266136D8F3426332E1C2A79BC397880195AA9361952D41865998646CD88356242DDB2F6CEF7344DB160C34E9E45992B3CC2BA840160248D405042AD8F5588F7D1C8D4A90301206CA3F5227FC5C9446416A0D62AFB14E786F4665E652FB6A6A50F2E0A397DAD074ABE3EB6D5E53600C44F2CB9318BEDEB785AFF0CF19D91AF276BD47EBAF9AD75ED2FFD0BD6732CAF07DDF0CB3A120EC32D7E08B62F783158B57AF696F3E0DD13AC740BE2ABE70A8B8FB96D19B6DDDD15BEF3A283D197875E742960AB9C126DEB582115A9C7075A21D4E7A2CF38E29B6B6C8CDCCC72420CB9499A05ABBB854379088D6C9984E37A41519CFC4F927876E5B1E95640C72026569257E21D0C81060C60729A44B3F5A9989435E06F09328BA8092399F55F86DEDACA6FCC086035097223DD813ACFDF541B9D28D3A81E4E08CE08EB85B5616EA27F09159BD87252B00F3103D125C571AEF5B3C0634190056364A30985CC0AB804A11649A42863AA47BAEFB425E2797C1658828131EA4FB9AA1156B52BFFB5E27642019D14B3F26612EF468A30240287BFC5226C6E58B201B8D9F44F8C076ACBDCF31F3DDD39BB46C6E2C866857DFD9C4303270A343C81F376630B78F134BFEF821D239AAF1F7C5C231F81C2550F78C679BBD2C1384570B5AA3BC78F7CD497E3639923B8AF2ADC7BB3A07F3E6FE9495D3CA83D30DFBC6628B93FF0705E33237842F7EA3EFCD7C02492385113B9FBAD0E9D6C27EBA5C649E1E2095691F91CA7EC360C5BB96404459E3945BE"),
+            hex!("A3D0D0323D33C2503011A72182A66221A68DAB83FE8F08F588EBEE9860561E62"),
+            hex!("779680E40450AD34188A60A1615F9EF7C91739F283DFCE4A5AA89D00EDBC4007")
+        );
+    }
+
+    #[test]
+    fn test_fips_203_vector_1024() {
+        check_vector(
+            &MLKEM1024::init(),
+            hex!("61349E5C131A7E116A0463861D7D18663C5627C38C7147DDAADFD48ACD7A4535"),
+            hex!("F0DB5D938027FCD9BAD87847D52C14CF0C4ABCF0703B749793F212111FFB303B"),
+            &hex!("5416567AA4B728E58FD27A6AC1DCA54814F04A59F6EBC21EA1B5CE9FFD0BC0FC2426F27A1456CC2AB1864355D075C57AEB84C17FA0653FE32B299D70BBD36155776FC1C6130F9172F5F22987062BD4F33EB1AB7B9E40CE5D1E97C6177AF66657B29DB93657E967B4E43F2E18E00FB6E6A6451E7F8A803EA99B10F6515676CFEB3B2D197E8A2899C2DAF42A286F4B291AF5410FD32EF018D30F246BAEE899FA48EC13C96AC6C7F2E59BA209FC544782C4D1431C5A42DEDFAAE1C0A29A48E82B96D7A6B26DAF4EB9C41993B4D1A5B2329628F7BEBAEC01F4F7E8DFF3DFF35303FCBAF6BE9DD89F4696CBFD82E68C87AE6C10933C5179E32CB167689B21320C5A544D7FA184692AF90F1AB7BEC11DB86546A400E53AE4C5CAA97C7FDCDBADBDB227C3D690305F1078E21BD719E29B18E0F2C69DC3F47330BB9676F2F25ABA1DF248DE5BBC31CC7AD96BCDFAE4B15ADCA4732CED8C7AE56931FA1FBB43CDBF7CEB02FD80488B306A664E0E8E3D2E7CBDBE2C28FA39BBF542305F006ECEE3BA4446F631104C62AF45A4A7C421419460BE9ED598944FF76A9EE48A0F2DEAE6DA89FB18EA5AD8A538669BE5C5434DB2111D80B8F89BED532E3F597D6546B63949828E15336782AB77E1EA690C64C34AEFE69EC6148E5C2B9A9C806BBBBFF0142F7C3BA62027E45F2F0B6F99F9EE7B6A365789D6AA58757A569A940D9A75B929A63D8792F3EE4E277D1CBF6E2273A8A9FE17B00C2716C3E9FDE2608267658FA17B1ADC7024E87C163496FF8FB905397BD5CF5BD08889A8ABB66E62EDB4372D6F7A730D3C71D057CB0DB852FCE7173C9FE2EE4EE3E7BED70531E6504924392E7D71E6370C19D3B4004C50E4D4C08E6AAE1967BB2D3B3DDC87564EE94CFC6CE18972E925617926DFE5F9B16BD15493B4DE74D909FAFB64607C101B150B82FE8560EC8BCB5AD38813AAE46CC597E50C500B8A7888838354EB1B02EC2BD16D1A32C809933A9DB59BDACAD559B131BF951DAEE94163BAC71C39C813AE1D99E07465887F02E17009318B95EFC73CCE9BD95C0870AA8DF54FA83AA780A39967656E8E290FC70D4B9055495B313E396E32AC95E09AAC75617AAFAF3BDCC2B227F9F467FE9A900E65B241EA4D238D26EC5992D5777B62735D2BF93D805B457FA0BD10E25736A2AFD864A23D9A82210CF5374A04930AF9E28753BDE05A08C0D84C848AF61AA22211219D1513FE14A3BC984C893829C61548E5DB7D3CAFF7DF22CC643A9299A45C5054B2977384B7A615E64E43BB464CD0FAEF516C473A05807A730C08C46DEF8E36507DE69770C1B928BF5E207CE647793C3FD29AFD6098423EFB94A22137D5290D95F654E4B1AC82FFE65EFC48C7726CE9284E2FBCDFD03B1205456BB9370B67A70BD3B9FDAB40291B816BB5D616B85CCF9F2201B9FE9F86EFB628819A0B2DDC9F61914D040AB375B4E734EEEDBCCE75547587EFD81B98A43881D4AAEDC9A586F67CF64D5CA3EAF2FC85390C8BFAF936C85D8B4F1801D6C67EC8A928B4134C8A3445DC9588C4CF55F77CEA280086B4F79E4F23D32C786695283DC555633B44165F9F146FBE57426000643BA1A8249B9E2232F7D122DEB62822D98C2285E81674AA623474EE6F226CF5CAB449E8815F726E47784D27EA2F7D61CDC40D08813A1CABA782BB292E1C29E876C3A9D7EBB8BF966F1888135238BA6AB87A693AF7FB27FF529CF3BC4812F4858FEE9C14AA072AEBCE84C845BDCC8C474FC6FFAE3FEC349B251721445277F9D641F2D99594DC0D80B8A371382119141C8265132FFFD295B13B6B308E76C67B8D2F366CE02C6A5DE51CE6D660B267418391CF57A784997D9659266E9DE41F9D832E1E18A665FF6E8AA8B3AF255093959C791CD2910CCB459F7965F1D4E4EDBC1164FF86B65356EAF4C60F67A5A3BB65D51F4BF6E8DE4AAFF12C5C1AB25A1EF6B6321998F7961B6BEDFE6595FDF16BC87FFF7CC5D52AC54DECF26330E6500D1867386AE13329A5183B88DBEC9D5630690A7E6D858FC1972B54CE564D22C3BD97D076D2BBE97A655D45C597C5513239E226E16208200645F58A12B4DD62BB2C594C9C43391CA066C6A2FD152256A79794C53E0EA958766538C038203CC679F2BAEF227A693AAB9FF07287144F2010D34C4853A952C14A2D88E410C2C7117D1FD31878D0C36880D881B9017DC1547E439B49D6E92AED350E607A00"),
+            hex!("105967C6A8DA2B1D9AE51549B96BF85E1BCD140CE5DD7624C6664D34983C230A"),
+            hex!("426B48C198FAC68D43D8B52C77C50965E7AD5BCE27576C26B65E1C53FA0DBA26")
+        );
+    }
+
+    #[test]
+    fn test_accumulated_vectors_768() {
+        // Accumulated vectors of ML-KEM-768 (100 iterations, as in the C2SP test vectors): the
+        // seeds, the messages and random ciphertexts, which are implicitly rejected, are read
+        // from a SHAKE-128 stream with an empty input and every output is absorbed by another
+        // SHAKE-128 instance
+        let kem = MLKEM768::init();
+        let mut inputs = Shake128::default().finalize_xof();
+        let mut outputs = Shake128::default();
+
+        for _ in 0..100 {
+            let mut seed = [0u8; 64];
+            inputs.read(&mut seed);
+            let (public_key, private_key) = kem.keygen(seed.as_slice().into()).unwrap();
+            outputs.update(public_key.get_bytes());
+
+            let mut message = [0u8; 32];
+            inputs.read(&mut message);
+            let (ciphertext, shared_secret) = kem.encrypt(public_key, message.as_slice().into(), 32).unwrap();
+            outputs.update(ciphertext.get_bytes());
+            outputs.update(shared_secret.get_bytes());
+            assert_eq!(kem.decrypt(ciphertext, private_key.clone(), 32).unwrap(), shared_secret);
+
+            let mut random_ciphertext = [0u8; 1088];
+            inputs.read(&mut random_ciphertext);
+            let rejected = kem.decrypt(random_ciphertext.as_slice().into(), private_key, 32).unwrap();
+            outputs.update(rejected.get_bytes());
+        }
+
+        let mut digest = [0u8; 32];
+        outputs.finalize_xof().read(&mut digest);
+        assert_eq!(digest, hex!("1114b1b6699ed191734fa339376afa7e285c9e6acf6ff0177d346696ce564415"));
+    }
+}
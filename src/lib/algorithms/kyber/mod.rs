@@ -8,10 +8,24 @@ mod vector;
 mod encoder;
 mod compress;
 mod kem;
+mod ml_kem;
+mod types;
+mod prepared;
+mod asn1;
+mod seal;
 mod utils;
+#[cfg(target_arch = "x86_64")]
+mod avx2;
+#[cfg(test)]
+mod kat;
 
 pub use kem::{KyberKEM512, KyberKEM768, KyberKEM1024, KyberKEM};
+pub use ml_kem::{MLKEM512, MLKEM768, MLKEM1024};
 pub use cpapke::{KyberCPAPKE512, KyberCPAPKE768, KyberCPAPKE1024, KyberPKE};
 pub use utils::get_random_coin;
-pub use constants::KYBER_MESSAGE_LENGTH;
+pub use constants::{KYBER_KEM_SEED_LENGTH, KYBER_MESSAGE_LENGTH};
+pub use asn1::{decode_private_key_der, decode_private_key_pem, decode_public_key_der, decode_public_key_pem, encode_private_key_der, encode_private_key_pem, encode_private_key_seed_der, encode_private_key_seed_pem, encode_public_key_der, encode_public_key_pem, PRIVATE_KEY_PEM_LABEL, PUBLIC_KEY_PEM_LABEL};
+pub use prepared::{PreparedPublicKey, PreparedSecretKey};
+pub use seal::{key_fingerprint, open, seal, SealHeader, SEAL_CHUNK_SIZE, SEAL_VERSION};
+pub use types::{Ciphertext, ParameterSet, PublicKey, SecretKey, SharedSecret, TypedKyberKEM, TypedKyberPKE};
 
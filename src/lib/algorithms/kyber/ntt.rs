@@ -1,13 +1,18 @@
 use crate::algorithms::algebraic::galois_field::GaloisField;
+use crate::algorithms::kyber::constants::{KYBER_N_VALUE, KYBER_Q_VALUE};
 use crate::algorithms::kyber::galois_field::GF3329;
+#[cfg(target_arch = "x86_64")]
+use crate::algorithms::kyber::avx2;
 
 
+#[allow(clippy::upper_case_acronyms)]
 pub trait NTT {
     fn inverse_ntt(self) -> Self;
     fn to_ntt(self) -> Self;
 }
 
-const INVERSE_OF_2_MOD_Q: GF3329 =  GF3329::new(1665);
+const INVERSE_OF_2_MOD_Q: usize = 1665;
+pub(crate) const INVERSE_OF_2_MONTGOMERY: i16 = GF3329::new(INVERSE_OF_2_MOD_Q as u16).to_montgomery();
 
 /// This array corresponds to the 256th-roots of unity using the following primitive 17
 pub const ZETAS_256: [usize; 256] = [
@@ -29,77 +34,228 @@ pub const ZETAS_256: [usize; 256] = [
     927, 2443, 1583, 279, 1414, 735, 2508, 2688, 2419, 1175,
 ];
 
-fn br<const K: u8>(i: u8) -> u8 {
-    let mask = (1 << K) - 1; // compute the mask with k bits set to 1
-    let bin_i = format!("{:0width$b}", i & mask, width = K as usize); // convert the masked value to binary string with leading zeros
-    u8::from_str_radix(&bin_i.chars().rev().collect::<String>(), 2).unwrap() // reverse the binary string and convert it back to integer
+/// This function reverses the 7 least significant bits of i.
+pub const fn br7(i: u8) -> u8 {
+    (i & 0x7F).reverse_bits() >> 1
 }
 
-pub fn br7(i: u8) -> u8 {
-    br::<7>(i)
+/// Montgomery form of zeta^br7(k) for k in [0, 128). The forward NTT consumes them in increasing
+/// order, one per block of butterflies.
+pub(crate) const ZETAS_MONTGOMERY: [i16; 128] = precompute_zetas();
+
+/// Montgomery form of zeta^(-br7(k)) / 2 for k in [0, 128), used by the inverse NTT.
+pub(crate) const HALF_INVERSE_ZETAS_MONTGOMERY: [i16; 128] = precompute_half_inverse_zetas();
+
+const fn precompute_zetas() -> [i16; 128] {
+    let mut zetas = [0; 128];
+    let mut k = 0;
+    while k < 128 {
+        zetas[k] = GF3329::new(ZETAS_256[br7(k as u8) as usize] as u16).to_montgomery();
+        k += 1;
+    }
+    zetas
 }
 
-pub fn ntt_inv_rec(poly: &mut [GF3329], zeta_index: u8, layer: usize) {
-    if layer != 2{
-        // Applying divide and conquer strategy
-        let poly_split = poly.split_at_mut(layer);
-        ntt_inv_rec(poly_split.0, zeta_index * 2 , layer / 2);
-        ntt_inv_rec(poly_split.1, zeta_index * 2 + 1, layer / 2);
+const fn precompute_half_inverse_zetas() -> [i16; 128] {
+    let mut zetas = [0; 128];
+    let mut k = 0;
+    while k < 128 {
+        // As zeta is a 256th primitive root of unity, zeta^(256 - u) is the inverse of zeta^u
+        let zeta_inverse = ZETAS_256[(256 - br7(k as u8) as usize) % 256];
+        let value = (zeta_inverse * INVERSE_OF_2_MOD_Q) % KYBER_Q_VALUE;
+        zetas[k] = GF3329::new(value as u16).to_montgomery();
+        k += 1;
     }
+    zetas
+}
 
-    // As zeta is a 256th primitive root of unity if we take u = zeta^u and v = zeta^(256 - u) then
-    // one could affirm that u.v [q] = zeta^u * zeta^(256 - u) [q] = zeta^256 [q] = 1.
-    let zeta_index_inverse = (256 - br7(zeta_index) as usize).rem_euclid(256);
-    let zeta_inverse = GF3329::from(ZETAS_256[zeta_index_inverse]);
+/// This function computes in place the NTT of a polynomial, with the AVX2 backend when the CPU
+/// supports it.
+/// Input:
+///     poly: The coefficients of the polynomial, replaced by its NTT representation
+pub fn ntt(poly: &mut [GF3329; KYBER_N_VALUE]) {
+    #[cfg(target_arch = "x86_64")]
+    if avx2::is_supported() {
+        // SAFETY: the CPU supports the AVX2 instructions
+        unsafe { avx2::ntt(poly) };
+        return;
+    }
 
-    for i in 0..layer {
-        let u_plus_v = poly[i].add(&poly[i + layer]);
-        let u_minus_v = poly[i].sub(&poly[i + layer]);
+    ntt_scalar(poly)
+}
 
-        // WARNING: Based on the paper used https://eprint.iacr.org/2021/563.pdf, we obtain 2 * a_o
-        // (and 2 * a_1 * zeta) using the formula this is why we have to define to define the inverse of 2 [q]
-        poly[i] = INVERSE_OF_2_MOD_Q.mul(&u_plus_v);
-        poly[i + layer] = INVERSE_OF_2_MOD_Q.mul(&zeta_inverse.mul(&u_minus_v));
+/// This function computes in place the inverse NTT of a polynomial, with the AVX2 backend when the
+/// CPU supports it.
+/// Input:
+///     poly: The NTT representation of a polynomial, replaced by its coefficients
+pub fn inverse_ntt(poly: &mut [GF3329; KYBER_N_VALUE]) {
+    #[cfg(target_arch = "x86_64")]
+    if avx2::is_supported() {
+        // SAFETY: the CPU supports the AVX2 instructions
+        unsafe { avx2::inverse_ntt(poly) };
+        return;
     }
+
+    inverse_ntt_scalar(poly)
 }
 
+/// This function computes the product of two polynomials in the NTT domain, with the AVX2 backend
+/// when the CPU supports it.
+/// Input:
+///     a: The NTT representation of the first polynomial
+///     b: The NTT representation of the second polynomial
+/// Output:
+///     The NTT representation of the product
+pub fn multiply_ntt(a: &[GF3329; KYBER_N_VALUE], b: &[GF3329; KYBER_N_VALUE]) -> [GF3329; KYBER_N_VALUE] {
+    #[cfg(target_arch = "x86_64")]
+    if avx2::is_supported() {
+        // SAFETY: the CPU supports the AVX2 instructions
+        return unsafe { avx2::multiply_ntt(a, b) };
+    }
 
-pub fn ntt_rec(poly: &mut [GF3329], zeta_index: u8, layer: usize) {
-    if layer < 2 {
-        return
+    multiply_ntt_scalar(a, b)
+}
+
+/// This function computes in place the NTT of a polynomial with the Cooley-Tukey butterflies
+/// (Algorithm 9 of FIPS 203), starting from the largest layer.
+/// Input:
+///     poly: The coefficients of the polynomial, replaced by its NTT representation
+pub(crate) fn ntt_scalar(poly: &mut [GF3329; KYBER_N_VALUE]) {
+    let mut k = 1;
+    let mut layer = KYBER_N_VALUE / 2;
+
+    while layer >= 2 {
+        for block in poly.chunks_exact_mut(2 * layer) {
+            let zeta = ZETAS_MONTGOMERY[k];
+            k += 1;
+
+            let (low, high) = block.split_at_mut(layer);
+            for (u, v) in low.iter_mut().zip(high.iter_mut()) {
+                let t = v.mul_montgomery(zeta);
+
+                *v = u.sub(&t);
+                *u = u.add(&t);
+            }
+        }
+        layer /= 2;
     }
+}
 
-    let zeta = GF3329::from(ZETAS_256[br7(zeta_index) as usize]);
+/// This function computes in place the inverse NTT of a polynomial with the Gentleman-Sande
+/// butterflies (Algorithm 10 of FIPS 203), starting from the smallest layer.
+/// Input:
+///     poly: The NTT representation of a polynomial, replaced by its coefficients
+pub(crate) fn inverse_ntt_scalar(poly: &mut [GF3329; KYBER_N_VALUE]) {
+    // The blocks of the layer of size 2 use the zetas of index 64 to 127, those of the layer of
+    // size 4 the zetas of index 32 to 63 and so on.
+    let mut first_k = KYBER_N_VALUE / 4;
+    let mut layer = 2;
 
-    for i in 0..layer {
-        let t = zeta.mul(&poly[layer + i]);
+    while layer <= KYBER_N_VALUE / 2 {
+        for (k, block) in (first_k..).zip(poly.chunks_exact_mut(2 * layer)) {
+            let half_zeta_inverse = HALF_INVERSE_ZETAS_MONTGOMERY[k];
 
-        poly[i + layer] = poly[i].sub(&t);
-        poly[i] = poly[i].add(&t);
+            // WARNING: Based on the paper used https://eprint.iacr.org/2021/563.pdf, we obtain 2 * a_o
+            // (and 2 * a_1 * zeta) using the formula this is why every layer multiplies by the inverse of 2 [q].
+            let (low, high) = block.split_at_mut(layer);
+            for (u, v) in low.iter_mut().zip(high.iter_mut()) {
+                let u_plus_v = u.add(v);
+                let u_minus_v = u.sub(v);
 
+                *u = u_plus_v.mul_montgomery(INVERSE_OF_2_MONTGOMERY);
+                *v = u_minus_v.mul_montgomery(half_zeta_inverse);
+            }
+        }
+        first_k /= 2;
+        layer *= 2;
     }
+}
 
-    let poly_split = poly.split_at_mut(layer);
 
+/// This function corresponds to the multiplication of two polynomials in the NTT domain: the
+/// coefficients 2i and 2i + 1 are the remainder of the polynomial modulo X^2 - zeta^(2.br7(i) + 1).
+/// Input:
+///     a: The NTT representation of the first polynomial
+///     b: The NTT representation of the second polynomial
+/// Output:
+///     The NTT representation of the product
+pub(crate) fn multiply_ntt_scalar(a: &[GF3329; KYBER_N_VALUE], b: &[GF3329; KYBER_N_VALUE]) -> [GF3329; KYBER_N_VALUE] {
+    let mut coefficients = [GF3329::default(); KYBER_N_VALUE];
 
-    ntt_rec(poly_split.0, zeta_index * 2, layer / 2);
-    ntt_rec(poly_split.1, zeta_index * 2 + 1, layer / 2);
+    for i in 0..KYBER_N_VALUE/2 {
+        let a_0 = a[2 * i].into_inner();
+        let a_1 = a[2 * i + 1].into_inner();
+
+        let b_0 = b[2 * i].into_inner();
+        let b_1 = b[2 * i + 1].into_inner();
+
+        let zeta_index = 2 * br7(i as u8) as usize + 1;
+        let zeta = ZETAS_256[zeta_index];
+
+        // Products of two coefficients are below q^2, so the sums are accumulated on integers
+        // and reduced only once
+        let a_1_b_1 = GF3329::from(a_1 * b_1).into_inner();
+        let f_0_hat = GF3329::from(a_0 * b_0 + a_1_b_1 * zeta);
+        let f_1_hat = GF3329::from(a_1 * b_0 + a_0 * b_1);
+
+        coefficients[2 * i] = f_0_hat;
+        coefficients[2 * i + 1] = f_1_hat;
+    }
+
+    coefficients
 }
 
 
 #[cfg(test)]
 mod tests {
-    use crate::algorithms::kyber::ntt::{br7};
+    use crate::algorithms::algebraic::galois_field::GaloisField;
+    use crate::algorithms::kyber::constants::KYBER_N_VALUE;
+    use crate::algorithms::kyber::galois_field::GF3329;
+    use crate::algorithms::kyber::ntt::{br7, inverse_ntt_scalar, ntt_scalar, ZETAS_256, ZETAS_MONTGOMERY};
 
     #[test]
     fn test_br_7() {
-        let tested_value = 1 as u8;
+        let tested_value = 1_u8;
         let expected_value = 64;
         let out = br7(tested_value);
         assert_eq!(out, expected_value);
     }
 
+    #[test]
+    fn test_zetas_montgomery() {
+        // Multiplying 1 by a factor in Montgomery form gives back the factor itself
+        for (k, zeta) in ZETAS_MONTGOMERY.iter().enumerate() {
+            let expected = GF3329::from(ZETAS_256[br7(k as u8) as usize]);
+            assert_eq!(GF3329::new(1).mul_montgomery(*zeta), expected);
+        }
+    }
+
+    #[test]
+    fn test_ntt_evaluates_the_polynomial() {
+        // The NTT representation is made of the remainders of the polynomial modulo
+        // X^2 - zeta^(2.br7(i) + 1)
+        let mut poly = [GF3329::zero(); KYBER_N_VALUE];
+        for (i, coefficient) in poly.iter_mut().enumerate() {
+            *coefficient = GF3329::from(i * i + 7);
+        }
+        let mut poly_ntt = poly;
+        ntt_scalar(&mut poly_ntt);
 
-}
+        for i in 0..KYBER_N_VALUE / 2 {
+            let gamma = GF3329::from(ZETAS_256[2 * br7(i as u8) as usize + 1]);
+            let mut remainder = [GF3329::zero(); 2];
+            let mut gamma_power = GF3329::new(1);
 
+            for j in 0..KYBER_N_VALUE / 2 {
+                remainder[0] = remainder[0].add(&poly[2 * j].mul(&gamma_power));
+                remainder[1] = remainder[1].add(&poly[2 * j + 1].mul(&gamma_power));
+                gamma_power = gamma_power.mul(&gamma);
+            }
 
+            assert_eq!([poly_ntt[2 * i], poly_ntt[2 * i + 1]], remainder, "i = {}", i);
+        }
+
+        inverse_ntt_scalar(&mut poly_ntt);
+        assert_eq!(poly_ntt, poly);
+    }
+}
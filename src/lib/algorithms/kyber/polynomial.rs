@@ -1,101 +1,110 @@
-use crate::algorithms::algebraic::galois_field::GaloisField;
-use crate::algorithms::algebraic::polynomial::{Polynomial, RingElement};
+use crate::algorithms::algebraic::polynomial::Polynomial;
 use crate::algorithms::byte_array::ByteArray;
 use crate::algorithms::kyber::compress::{Compress, Decompress};
 use crate::algorithms::kyber::galois_field::GF3329;
 use crate::algorithms::kyber::constants::{KYBER_N_VALUE};
 use crate::algorithms::kyber::encoder::{Decoder, Encoder};
-use crate::algorithms::kyber::ntt::{br7, NTT, ntt_inv_rec, ntt_rec, ZETAS_256};
-use crate::algorithms::kyber::utils::{compress_d, decompress_d, poly_coefficients_to_bits};
+use crate::algorithms::kyber::ntt::{inverse_ntt, multiply_ntt, ntt, NTT};
+use crate::algorithms::kyber::utils::{compress_d, decompress_d};
+use crate::CryptumResult;
+use crate::errors::CryptumError;
 
 pub type PolyRQ = Polynomial<GF3329, KYBER_N_VALUE>;
 
 impl NTT for PolyRQ {
-    fn inverse_ntt(self) -> Self {
-        // Checking if the polynomial is not zero
-        if self.is_zero() {
-            return PolyRQ::zero();
-        }
-
-        let mut coefficients = [GF3329::default(); KYBER_N_VALUE];
-        coefficients.copy_from_slice(self.get_coefficients());
-
-        ntt_inv_rec(&mut coefficients, 1, KYBER_N_VALUE / 2);
-
-        coefficients.into()
+    fn inverse_ntt(mut self) -> Self {
+        self.update_coefficients(inverse_ntt);
+        self
     }
 
-    fn to_ntt(self) -> Self {
-        // Checking if the polynomial is not zero
-        if self.is_zero() {
-            return PolyRQ::zero();
-        }
-
-        let mut coefficients = [GF3329::default(); KYBER_N_VALUE];
-        coefficients.copy_from_slice(self.get_coefficients());
-
-        ntt_rec(&mut coefficients, 1, KYBER_N_VALUE / 2);
-
-        coefficients.into()
+    fn to_ntt(mut self) -> Self {
+        self.update_coefficients(ntt);
+        self
     }
 }
 
 impl PolyRQ {
     pub fn multiply_ntt(&self, other: &Self) -> Self {
-        let mut coefficients = [GF3329::default(); KYBER_N_VALUE];
-
-        for i in 0..KYBER_N_VALUE/2 {
-            let a_0 = self[2 * i];
-            let a_1 = self[2 * i + 1];
+        multiply_ntt(self.as_array(), other.as_array()).into()
+    }
+}
 
-            let b_0 = other[2 * i];
-            let b_1 = other[2 * i + 1];
+impl Encoder for PolyRQ {
+    fn encode(&self, l_value: usize) -> ByteArray {
+        // The l least significant bits of every coefficient are packed in little-endian order
+        let mask = (1 << l_value) - 1;
+        let mut bytes = Vec::with_capacity(32 * l_value);
+
+        if l_value == 12 {
+            // Keys are encoded with 12 bits: every pair of coefficients fills exactly 3 bytes
+            for pair in self.get_coefficients().chunks_exact(2) {
+                let (a, b) = (pair[0].into_inner() & 0xfff, pair[1].into_inner() & 0xfff);
+                bytes.extend_from_slice(&[a as u8, (a >> 8 | b << 4) as u8, (b >> 4) as u8]);
+            }
+            return bytes.into();
+        }
 
-            let zeta_index = 2 * br7(i as u8) as usize + 1;
-            let zeta = GF3329::from(ZETAS_256[zeta_index]);
+        let mut buffer: u32 = 0;
+        let mut buffer_length = 0;
 
-            let f_0_hat = a_0.mul(&b_0).add(&a_1.mul(&b_1).mul(&zeta));
-            let f_1_hat = a_1.mul(&b_0).add(&a_0.mul(&b_1));
+        for coefficient in self.get_coefficients() {
+            buffer |= (coefficient.into_inner() as u32 & mask) << buffer_length;
+            buffer_length += l_value;
 
-            coefficients[2 * i] = f_0_hat;
-            coefficients[2 * i + 1] = f_1_hat;
+            while buffer_length >= 8 {
+                bytes.push(buffer as u8);
+                buffer >>= 8;
+                buffer_length -= 8;
+            }
         }
 
-        coefficients.into()
-    }
-}
-
-impl Encoder for PolyRQ {
-    fn encode(&self, l_value: usize) -> ByteArray {
-        let bits = poly_coefficients_to_bits(self, l_value);
-        ByteArray::from_bits(bits)
+        bytes.into()
     }
 }
 
 impl Decoder for PolyRQ {
-    fn decode(bytes: ByteArray, l_value: u8) -> Self {
+    fn decode(bytes: ByteArray, l_value: u8) -> CryptumResult<Self> {
         // Checking length of bytes
         let bytes_length = bytes.length();
         let expected_length = 32 * l_value as usize;
         if bytes_length != expected_length {
-            panic!("Invalid length for 'bytes'. Expected {} found {}", expected_length, bytes_length)
+            return Err(CryptumError::InvalidEncodingLength {
+                expected: expected_length,
+                found: bytes_length
+            });
         }
 
-        let bits = bytes.to_bits();
+        let l_value = l_value as usize;
+        let mask = (1 << l_value) - 1;
         let mut coefficients = [GF3329::default(); KYBER_N_VALUE];
 
-        for i in 0..KYBER_N_VALUE {
-            let mut coefficient = GF3329::default();
+        if l_value == 12 {
+            // Every group of 3 bytes holds a pair of coefficients
+            for (pair, chunk) in coefficients.chunks_exact_mut(2).zip(bytes.get_bytes().chunks_exact(3)) {
+                let (b_0, b_1, b_2) = (chunk[0] as usize, chunk[1] as usize, chunk[2] as usize);
+                pair[0] = GF3329::from(b_0 | (b_1 & 0xf) << 8);
+                pair[1] = GF3329::from(b_1 >> 4 | b_2 << 4);
+            }
+            return Ok(coefficients.into());
+        }
+
+        let mut bytes_iter = bytes.get_bytes().iter();
+        let mut buffer: u32 = 0;
+        let mut buffer_length = 0;
 
-            for j in 0..l_value {
-                let value = bits[i * l_value as usize + j as usize] as u16 * 2_u16.pow(j as u32);
-                coefficient = coefficient.add(&GF3329::from(value as usize))
+        for coefficient in coefficients.iter_mut() {
+            while buffer_length < l_value {
+                // The length of bytes has been checked so the iterator cannot be exhausted
+                buffer |= (*bytes_iter.next().unwrap_or(&0) as u32) << buffer_length;
+                buffer_length += 8;
             }
 
-            coefficients[i] = coefficient
+            *coefficient = GF3329::from((buffer & mask) as usize);
+            buffer >>= l_value;
+            buffer_length -= l_value;
         }
 
-        coefficients.into()
+        Ok(coefficients.into())
     }
 }
 
@@ -104,9 +113,8 @@ impl Compress for PolyRQ {
     fn compress(self, d_value: u32) -> Self {
         let mut coefficients = [GF3329::default(); KYBER_N_VALUE];
 
-        for i in 0..KYBER_N_VALUE {
-            let coefficient = self[i];
-            coefficients[i] = compress_d(coefficient, d_value);
+        for (i, coefficient) in coefficients.iter_mut().enumerate() {
+            *coefficient = compress_d(self[i], d_value);
         }
 
 
@@ -118,9 +126,8 @@ impl Decompress for PolyRQ {
     fn decompress(self, d_value: u32) -> Self {
         let mut coefficients = [GF3329::default(); KYBER_N_VALUE];
 
-        for i in 0..KYBER_N_VALUE {
-            let coefficient = self[i];
-            coefficients[i] = decompress_d(coefficient, d_value);
+        for (i, coefficient) in coefficients.iter_mut().enumerate() {
+            *coefficient = decompress_d(self[i], d_value);
         }
 
 
@@ -130,9 +137,11 @@ impl Decompress for PolyRQ {
 
 #[cfg(test)]
 mod tests {
+    use zeroize::Zeroize;
+    use crate::algorithms::algebraic::galois_field::GaloisField;
     use crate::algorithms::byte_array::ByteArray;
     use crate::algorithms::kyber::constants::KYBER_N_VALUE;
-    use crate::algorithms::kyber::encoder::Decoder;
+    use crate::algorithms::kyber::encoder::{Decoder, Encoder};
     use crate::algorithms::kyber::galois_field::GF3329;
     use crate::algorithms::kyber::ntt::NTT;
     use crate::algorithms::kyber::polynomial::PolyRQ;
@@ -177,7 +186,7 @@ mod tests {
     #[test]
     fn test_decode_l() {
         let data = ByteArray::from([158, 98, 145, 151, 12, 180, 77, 217, 64, 8, 199, 155, 202, 249, 216, 111, 24, 180, 180, 155, 165, 178, 160, 71, 129, 219, 113, 153, 237, 59, 158, 78].as_slice());
-        let poly = PolyRQ::decode(data, 1);
+        let poly = PolyRQ::decode(data, 1).unwrap();
 
         let expected_data = [0, 1, 1, 1, 1, 0, 0, 1, 0, 1, 0, 0, 0, 1, 1, 0, 1, 0, 0, 0, 1, 0, 0, 1, 1, 1, 1, 0, 1, 0, 0, 1, 0, 0, 1, 1, 0, 0, 0, 0, 0, 0, 1, 0, 1, 1, 0, 1, 1, 0, 1, 1, 0, 0, 1, 0, 1, 0, 0, 1, 1, 0, 1, 1, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 1, 0, 0, 0, 0, 1, 1, 1, 0, 0, 0, 1, 1, 1, 1, 0, 1, 1, 0, 0, 1, 0, 1, 0, 1, 0, 0, 1, 1, 1, 0, 0, 1, 1, 1, 1, 1, 0, 0, 0, 1, 1, 0, 1, 1, 1, 1, 1, 1, 0, 1, 1, 0, 0, 0, 0, 1, 1, 0, 0, 0, 0, 0, 1, 0, 1, 1, 0, 1, 0, 0, 1, 0, 1, 1, 0, 1, 1, 1, 0, 1, 1, 0, 0, 1, 1, 0, 1, 0, 0, 1, 0, 1, 0, 1, 0, 0, 1, 1, 0, 1, 0, 0, 0, 0, 0, 1, 0, 1, 1, 1, 1, 0, 0, 0, 1, 0, 1, 0, 0, 0, 0, 0, 0, 1, 1, 1, 0, 1, 1, 0, 1, 1, 1, 0, 0, 0, 1, 1, 1, 0, 1, 0, 0, 1, 1, 0, 0, 1, 1, 0, 1, 1, 0, 1, 1, 1, 1, 1, 0, 1, 1, 1, 0, 0, 0, 1, 1, 1, 1, 0, 0, 1, 0, 1, 1, 1, 0, 0, 1, 0];
 
@@ -187,4 +196,26 @@ mod tests {
         }
     }
 
-}
\ No newline at end of file
+    #[test]
+    fn test_encode_decode_12() {
+        let poly = PolyRQ::from_degrees(&[0, 1, 255], &[0x123.into(), 0xabc.into(), 3328.into()]);
+        let mut expected_bytes = [0u8; 384];
+        expected_bytes[..3].copy_from_slice(&[0x23, 0xc1, 0xab]);
+        expected_bytes[381..].copy_from_slice(&[0x00, 0x00, 0xd0]);
+
+        let bytes = poly.encode(12);
+
+        assert_eq!(bytes.get_bytes(), expected_bytes.as_slice());
+        assert_eq!(PolyRQ::decode(bytes, 12).unwrap(), poly)
+    }
+
+    #[test]
+    fn test_zeroize() {
+        let mut poly = PolyRQ::from_degrees(&[0, 12, 255], &[1.into(), 2.into(), 3.into()]);
+        poly.zeroize();
+
+        for i in 0..KYBER_N_VALUE {
+            assert!(poly[i].is_zero())
+        }
+    }
+}
@@ -0,0 +1,317 @@
+use std::fmt::{Debug, Formatter};
+use crate::algorithms::byte_array::ByteArray;
+use crate::algorithms::kyber::constants::KYBER_N_VALUE;
+use crate::algorithms::kyber::cpapke::KyberCPAPKECore;
+use crate::algorithms::kyber::encoder::Encoder;
+use crate::algorithms::kyber::matrix::MatrixRQ;
+use crate::algorithms::kyber::types::kem_private_key_length;
+use crate::algorithms::kyber::utils::h;
+use crate::algorithms::kyber::vector::VectorRQ;
+use crate::algorithms::utils::secret::Secret;
+use crate::CryptumResult;
+use crate::errors::CryptumError;
+
+/// A KEM public key whose expensive parts are computed once: t_hat is decoded, the matrix A_hat
+/// is expanded from rho and transposed and the hash H(pk) is kept. It is built by the
+/// `prepare_public_key` function of a KEM and can then encapsulate as many shared secrets as
+/// needed with `encapsulate_prepared`.
+pub struct PreparedPublicKey {
+    bytes: ByteArray,
+    hash: ByteArray,
+    t_hat: VectorRQ,
+    a_hat_transposed: MatrixRQ
+}
+
+impl PreparedPublicKey {
+    /// This function decodes a public key with the CPAPKE of the KEM.
+    ///
+    /// Input:
+    ///     cpapke: The CPAPKE of the KEM
+    ///     public_key: A bytes array representing a public key
+    ///     hash: The hash H(pk) of the public key
+    /// Output:
+    ///     The prepared public key
+    pub(crate) fn new<const V: usize>(cpapke: &KyberCPAPKECore<V>, public_key: ByteArray, hash: ByteArray) -> CryptumResult<Self> {
+        let (t_hat, a_hat_transposed) = cpapke.decode_public_key(&public_key)?;
+
+        Ok(Self {
+            bytes: public_key,
+            hash,
+            t_hat,
+            a_hat_transposed
+        })
+    }
+
+    /// This function corresponds to the prepare_public_key function shared by the round 3 KEM and
+    /// ML-KEM: the public key is decoded, hashed with H and must pass the modulus check of
+    /// FIPS 203.
+    ///
+    /// Input:
+    ///     cpapke: The CPAPKE of the KEM
+    ///     public_key: A bytes array representing a public key
+    /// Output:
+    ///     The prepared public key
+    pub(crate) fn prepare<const V: usize>(cpapke: &KyberCPAPKECore<V>, public_key: ByteArray) -> CryptumResult<Self> {
+        let hash = h(&public_key);
+
+        let public_key = Self::new(cpapke, public_key, hash)?;
+        public_key.check_modulus()?;
+
+        Ok(public_key)
+    }
+
+    /// This function checks that the key has been prepared for a KEM whose public keys have the
+    /// given length, i.e. for the same module rank.
+    pub(crate) fn check_length(&self, expected_length: usize) -> CryptumResult<()> {
+        if self.bytes.length() != expected_length {
+            return Err(CryptumError::InvalidKeyLength {
+                expected: expected_length,
+                found: self.bytes.length()
+            });
+        }
+
+        Ok(())
+    }
+
+    /// This function corresponds to the modulus check of the encapsulation key (section 7.2 of
+    /// FIPS 203): encoding the decoded t_hat again must give the same bytes, which fails when a
+    /// coefficient is not lower than q.
+    pub(crate) fn check_modulus(&self) -> CryptumResult<()> {
+        let t_hat_encoded = self.t_hat.encode(12);
+
+        if t_hat_encoded.get_bytes() != &self.bytes.get_bytes()[..t_hat_encoded.length()] {
+            return Err(CryptumError::InvalidEncapsulationKey);
+        }
+
+        Ok(())
+    }
+
+    pub fn as_bytes(&self) -> &ByteArray {
+        &self.bytes
+    }
+
+    pub(crate) fn hash(&self) -> &ByteArray {
+        &self.hash
+    }
+
+    pub(crate) fn t_hat(&self) -> &VectorRQ {
+        &self.t_hat
+    }
+
+    pub(crate) fn a_hat_transposed(&self) -> &MatrixRQ {
+        &self.a_hat_transposed
+    }
+}
+
+impl Debug for PreparedPublicKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("PreparedPublicKey").field(&self.bytes).finish()
+    }
+}
+
+/// A KEM private key whose vector s_hat is decoded once. The public key embedded in the private
+/// key is prepared as well since the decapsulation encrypts the decrypted message again. It is
+/// built by the `prepare_private_key` function of a KEM and used by `decapsulate_prepared`.
+pub struct PreparedSecretKey {
+    s_hat: Secret<VectorRQ>,
+    public_key: PreparedPublicKey,
+    z: Secret<ByteArray>
+}
+
+impl PreparedSecretKey {
+    /// This function splits a private key generated by the keygen function of a KEM into
+    /// s_hat || pk || H(pk) || z and decodes s_hat and pk. The length of the private key must
+    /// have been checked by the KEM.
+    ///
+    /// Input:
+    ///     cpapke: The CPAPKE of the KEM
+    ///     private_key: A bytes array representing a private key
+    /// Output:
+    ///     The prepared private key
+    pub(crate) fn new<const V: usize>(cpapke: &KyberCPAPKECore<V>, private_key: &ByteArray) -> CryptumResult<Self> {
+        let k = cpapke.get_k() as usize;
+        let private_key_bytes = private_key.get_bytes();
+
+        let mut start_index = (12 * k * KYBER_N_VALUE) / 8;
+        let mut end_index = (24 * k * KYBER_N_VALUE) / 8 + 32;
+
+        let sub_private_key = Secret::new(ByteArray::from(&private_key_bytes[0..start_index]));
+
+        let pk = &private_key_bytes[start_index..end_index];
+
+        start_index = end_index;
+        end_index = (24 * k * KYBER_N_VALUE) / 8 + 64;
+
+        let h = &private_key_bytes[start_index..end_index];
+
+        start_index = end_index;
+        end_index = (24 * k * KYBER_N_VALUE) / 8 + 96;
+
+        let z = &private_key_bytes[start_index..end_index];
+
+        // The hash stored in the private key is used as is, like in the decapsulation algorithm
+        let public_key = PreparedPublicKey::new(cpapke, pk.into(), h.into())?;
+
+        Ok(Self {
+            s_hat: cpapke.decode_private_key(&sub_private_key)?,
+            public_key,
+            z: Secret::new(z.into())
+        })
+    }
+
+    /// This function corresponds to the prepare_private_key function shared by the round 3 KEM
+    /// and ML-KEM: the length of the private key is checked, then the key is decoded and must
+    /// pass the hash check of FIPS 203.
+    ///
+    /// Input:
+    ///     cpapke: The CPAPKE of the KEM
+    ///     private_key: A bytes array representing a private key
+    /// Output:
+    ///     The prepared private key
+    pub(crate) fn prepare<const V: usize>(cpapke: &KyberCPAPKECore<V>, private_key: ByteArray) -> CryptumResult<Self> {
+        // Checking length of private key
+        let expected_private_key_length = kem_private_key_length(V);
+
+        if private_key.length() != expected_private_key_length {
+            return Err(CryptumError::InvalidKeyLength {
+                expected: expected_private_key_length,
+                found: private_key.length()
+            });
+        }
+
+        let private_key = Self::new(cpapke, &private_key)?;
+        private_key.check_hash(&h(private_key.public_key().as_bytes()))?;
+
+        Ok(private_key)
+    }
+
+    /// This function corresponds to the hash check of the decapsulation key (section 7.3 of
+    /// FIPS 203): the hash stored in the private key must be the hash of the embedded public key.
+    ///
+    /// Input:
+    ///     hash: The hash H(pk) computed from the embedded public key
+    pub(crate) fn check_hash(&self, hash: &ByteArray) -> CryptumResult<()> {
+        if self.public_key.hash() != hash {
+            return Err(CryptumError::InvalidDecapsulationKey);
+        }
+
+        Ok(())
+    }
+
+    /// This function returns the public key embedded in the private key.
+    pub fn public_key(&self) -> &PreparedPublicKey {
+        &self.public_key
+    }
+
+    pub(crate) fn s_hat(&self) -> &VectorRQ {
+        &self.s_hat
+    }
+
+    pub(crate) fn z(&self) -> &ByteArray {
+        &self.z
+    }
+}
+
+// s_hat and z are wiped on drop by Secret, they must not be printed either
+impl Debug for PreparedSecretKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("PreparedSecretKey([REDACTED])")
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::algorithms::byte_array::ByteArray;
+    use crate::algorithms::kyber::kem::{KyberKEM, KyberKEM512, KyberKEM768};
+    use crate::algorithms::kyber::ml_kem::MLKEM768;
+    use crate::errors::CryptumError;
+
+    fn check_prepared_matches(kem: &dyn KyberKEM) {
+        let (public_key, private_key) = kem.keygen(ByteArray::random(64)).unwrap();
+        let prepared_public_key = kem.prepare_public_key(public_key.clone()).unwrap();
+        let prepared_private_key = kem.prepare_private_key(private_key.clone()).unwrap();
+        assert_eq!(prepared_private_key.public_key().as_bytes(), &public_key);
+
+        for _ in 0..3 {
+            let seed = ByteArray::random(32);
+            let (ciphertext, shared_secret) = kem.encrypt(public_key.clone(), seed.clone(), 32).unwrap();
+            let prepared = kem.encapsulate_prepared(&prepared_public_key, seed, 32).unwrap();
+            assert_eq!(prepared, (ciphertext.clone(), shared_secret.clone()));
+
+            let shared_secret_prime = kem.decapsulate_prepared(&prepared_private_key, ciphertext, 32).unwrap();
+            assert_eq!(shared_secret_prime, shared_secret);
+        }
+
+        // The implicit rejection gives the same key as the unprepared decapsulation
+        let ciphertext = ByteArray::random(kem.encrypt(public_key, ByteArray::random(32), 32).unwrap().0.length());
+        assert_eq!(
+            kem.decapsulate_prepared(&prepared_private_key, ciphertext.clone(), 32).unwrap(),
+            kem.decrypt(ciphertext, private_key, 32).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_prepared_kyber_kem() {
+        check_prepared_matches(&KyberKEM768::init());
+    }
+
+    #[test]
+    fn test_prepared_ml_kem() {
+        check_prepared_matches(&MLKEM768::init());
+    }
+
+    fn check_key_checks(kem: &dyn KyberKEM) {
+        let (public_key, private_key) = kem.keygen(ByteArray::random(64)).unwrap();
+
+        // The first coefficient of t_hat is set to 4095, which is not reduced modulo q
+        let mut public_key_bytes = public_key.get_bytes().to_vec();
+        public_key_bytes[0] = 0xff;
+        public_key_bytes[1] |= 0x0f;
+        let result = kem.encrypt(public_key_bytes.into(), ByteArray::random(32), 32);
+        assert!(matches!(result, Err(CryptumError::InvalidEncapsulationKey)));
+
+        // The hash H(pk) stored after the public key is modified
+        let mut private_key_bytes = private_key.get_bytes().to_vec();
+        let hash_index = private_key_bytes.len() - 64;
+        private_key_bytes[hash_index] ^= 1;
+        let ciphertext = ByteArray::random(kem.encrypt(public_key, ByteArray::random(32), 32).unwrap().0.length());
+        let result = kem.decrypt(ciphertext, private_key_bytes.into(), 32);
+        assert!(matches!(result, Err(CryptumError::InvalidDecapsulationKey)));
+    }
+
+    #[test]
+    fn test_key_checks_kyber_kem() {
+        check_key_checks(&KyberKEM512::init());
+    }
+
+    #[test]
+    fn test_key_checks_ml_kem() {
+        check_key_checks(&MLKEM768::init());
+    }
+
+    #[test]
+    fn test_prepared_keys_of_another_parameter_set() {
+        let kyber_512 = KyberKEM512::init();
+        let kyber_768 = KyberKEM768::init();
+        let (public_key, private_key) = kyber_512.keygen(ByteArray::random(64)).unwrap();
+
+        let prepared_public_key = kyber_512.prepare_public_key(public_key).unwrap();
+        let result = kyber_768.encapsulate_prepared(&prepared_public_key, ByteArray::random(32), 32);
+        assert!(matches!(result, Err(CryptumError::InvalidKeyLength { expected: 1184, found: 800 })));
+
+        let prepared_private_key = kyber_512.prepare_private_key(private_key).unwrap();
+        let ciphertext = ByteArray::random(1088);
+        let result = kyber_768.decapsulate_prepared(&prepared_private_key, ciphertext, 32);
+        assert!(matches!(result, Err(CryptumError::InvalidKeyLength { expected: 1184, found: 800 })));
+    }
+
+    #[test]
+    fn test_prepared_secret_key_is_redacted() {
+        let kyber = KyberKEM512::init();
+        let (_, private_key) = kyber.keygen(ByteArray::random(64)).unwrap();
+        let prepared_private_key = kyber.prepare_private_key(private_key).unwrap();
+
+        assert_eq!(format!("{:?}", prepared_private_key), "PreparedSecretKey([REDACTED])");
+    }
+}
@@ -0,0 +1,505 @@
+use std::io::{ErrorKind, Read, Write};
+use chacha20poly1305::{ChaCha20Poly1305, Key, KeyInit, Nonce};
+use chacha20poly1305::aead::{Aead, Payload};
+use crate::algorithms::byte_array::ByteArray;
+use crate::algorithms::kyber::kem::KyberKEM;
+use crate::algorithms::utils::hash::{sha3_256, shake_256};
+use crate::algorithms::utils::io::read_chunk;
+use crate::algorithms::utils::secret::Secret;
+use crate::CryptumResult;
+use crate::errors::CryptumError;
+
+/// The bytes every sealed file starts with
+const SEAL_MAGIC: &[u8; 7] = b"CRYPTUM";
+/// The version of the sealed file format
+pub const SEAL_VERSION: u8 = 2;
+/// The length of the plaintext chunks encrypted separately
+pub const SEAL_CHUNK_SIZE: usize = 64 * 1024;
+/// The largest values accepted in a header, so that a forged header cannot make the opening
+/// allocate huge buffers
+const MAX_CHUNK_SIZE: usize = 16 * 1024 * 1024;
+const MAX_ENCAPSULATION_LENGTH: usize = 4096;
+const MAX_RECIPIENTS: usize = 1024;
+/// The length of the Poly1305 tag appended to each chunk and to each wrapped key
+const TAG_LENGTH: usize = 16;
+/// The length of the shared secrets, of the data key and of the derived ChaCha20-Poly1305 keys
+const KEY_LENGTH: usize = 32;
+/// The length of a recipient fingerprint, the SHA3-256 hash of its public key
+const FINGERPRINT_LENGTH: usize = 32;
+const WRAPPED_KEY_LENGTH: usize = KEY_LENGTH + TAG_LENGTH;
+const KEY_WRAPPING_LABEL: &[u8] = b"cryptum seal v2 key wrapping";
+const KEY_DERIVATION_LABEL: &[u8] = b"cryptum seal v2";
+
+/// The data key of a sealed file wrapped for one of its recipients.
+#[derive(Debug, Clone, PartialEq)]
+struct Recipient {
+    fingerprint: ByteArray,
+    encapsulation: ByteArray,
+    wrapped_key: ByteArray
+}
+
+/// The header of a sealed file. It names the KEM parameter set, gives the length of the chunks
+/// and, for each recipient, holds the fingerprint of its public key, the KEM ciphertext (the
+/// encapsulation) of a shared secret and the data key wrapped with this shared secret. The
+/// encoded header is authenticated as the associated data of every chunk.
+///
+/// Encoding: "CRYPTUM" || version (1 byte) || length of the name (1 byte) || name ||
+///           chunk size (4 bytes) || number of recipients (2 bytes) || recipients
+/// where a recipient is encoded as:
+///           fingerprint (32 bytes) || length of the encapsulation (4 bytes) || encapsulation ||
+///           wrapped key (48 bytes)
+/// and the lengths are big-endian.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SealHeader {
+    parameter_set: String,
+    chunk_size: usize,
+    recipients: Vec<Recipient>
+}
+
+impl SealHeader {
+    /// This function returns the name of the KEM parameter set, e.g. "ml-kem-768".
+    pub fn parameter_set(&self) -> &str {
+        &self.parameter_set
+    }
+
+    /// This function returns the fingerprints of the public keys the data is sealed for.
+    pub fn recipient_fingerprints(&self) -> Vec<&ByteArray> {
+        self.recipients.iter()
+            .map(|recipient| &recipient.fingerprint)
+            .collect()
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = SEAL_MAGIC.to_vec();
+        bytes.push(SEAL_VERSION);
+        bytes.push(self.parameter_set.len() as u8);
+        bytes.extend_from_slice(self.parameter_set.as_bytes());
+        bytes.extend_from_slice(&(self.chunk_size as u32).to_be_bytes());
+        bytes.extend_from_slice(&(self.recipients.len() as u16).to_be_bytes());
+
+        for recipient in &self.recipients {
+            bytes.extend_from_slice(recipient.fingerprint.get_bytes());
+            bytes.extend_from_slice(&(recipient.encapsulation.length() as u32).to_be_bytes());
+            bytes.extend_from_slice(recipient.encapsulation.get_bytes());
+            bytes.extend_from_slice(recipient.wrapped_key.get_bytes());
+        }
+
+        bytes
+    }
+
+    /// This function reads the header at the beginning of a sealed file. The reader is left at
+    /// the first chunk.
+    ///
+    /// Input:
+    ///     reader: The sealed data
+    /// Output:
+    ///     The header
+    pub fn read_from(reader: &mut impl Read) -> CryptumResult<Self> {
+        let mut magic = [0u8; 7];
+        read_header_bytes(reader, &mut magic)?;
+        if &magic != SEAL_MAGIC {
+            return Err(CryptumError::InvalidSealedData("the data has not been sealed by cryptum".to_string()));
+        }
+
+        let [version, name_length] = read_header_array(reader)?;
+        if version != SEAL_VERSION {
+            return Err(CryptumError::InvalidSealedData(format!("unsupported version {}", version)));
+        }
+
+        let mut name = vec![0u8; name_length as usize];
+        read_header_bytes(reader, &mut name)?;
+        let parameter_set = String::from_utf8(name)
+            .map_err(|_| CryptumError::InvalidSealedData("invalid parameter set name".to_string()))?;
+
+        let chunk_size = u32::from_be_bytes(read_header_array(reader)?) as usize;
+        if chunk_size == 0 || chunk_size > MAX_CHUNK_SIZE {
+            return Err(CryptumError::InvalidSealedData(format!("invalid chunk size {}", chunk_size)));
+        }
+
+        let recipient_count = u16::from_be_bytes(read_header_array(reader)?) as usize;
+        if recipient_count == 0 || recipient_count > MAX_RECIPIENTS {
+            return Err(CryptumError::InvalidSealedData(format!("invalid number of recipients {}", recipient_count)));
+        }
+
+        let mut recipients = Vec::with_capacity(recipient_count);
+
+        for _ in 0..recipient_count {
+            let fingerprint: [u8; FINGERPRINT_LENGTH] = read_header_array(reader)?;
+
+            let encapsulation_length = u32::from_be_bytes(read_header_array(reader)?) as usize;
+            if encapsulation_length > MAX_ENCAPSULATION_LENGTH {
+                return Err(CryptumError::InvalidSealedData(format!("invalid encapsulation length {}", encapsulation_length)));
+            }
+
+            let mut encapsulation = vec![0u8; encapsulation_length];
+            read_header_bytes(reader, &mut encapsulation)?;
+
+            let wrapped_key: [u8; WRAPPED_KEY_LENGTH] = read_header_array(reader)?;
+
+            recipients.push(Recipient {
+                fingerprint: fingerprint.as_slice().into(),
+                encapsulation: encapsulation.into(),
+                wrapped_key: wrapped_key.as_slice().into()
+            });
+        }
+
+        Ok(Self {
+            parameter_set,
+            chunk_size,
+            recipients
+        })
+    }
+}
+
+/// This function computes the fingerprint identifying a recipient in the header of a sealed
+/// file: the SHA3-256 hash of its public key, which is also the hash H(pk) stored in its private
+/// key.
+pub fn key_fingerprint(public_key: &ByteArray) -> ByteArray {
+    sha3_256(public_key.get_bytes()).into()
+}
+
+/// This function encrypts data for the owners of several KEM public keys: a random data key is
+/// drawn and, for each public key, a shared secret is encapsulated and used to wrap the data key
+/// with ChaCha20-Poly1305. A ChaCha20-Poly1305 key is then derived from the data key and the
+/// header with SHAKE256 and the data is encrypted by chunks of SEAL_CHUNK_SIZE bytes. The nonce
+/// of a chunk is made of its index and of a flag set on the last chunk so that the chunks cannot
+/// be reordered, dropped or truncated without the opening failing.
+///
+/// Input:
+///     kem: The KEM the public keys belong to
+///     parameter_set: The name of the parameter set of the KEM, written in the header
+///     public_keys: The public keys of the recipients
+///     reader: The data to encrypt, read chunk by chunk
+///     writer: Where to write the header followed by the encrypted chunks
+pub fn seal(kem: &dyn KyberKEM, parameter_set: &str, public_keys: &[ByteArray], reader: &mut impl Read, writer: &mut impl Write) -> CryptumResult<()> {
+    seal_with_chunk_size(kem, parameter_set, public_keys, reader, writer, SEAL_CHUNK_SIZE)
+}
+
+fn seal_with_chunk_size(kem: &dyn KyberKEM, parameter_set: &str, public_keys: &[ByteArray], reader: &mut impl Read, writer: &mut impl Write, chunk_size: usize) -> CryptumResult<()> {
+    if parameter_set.len() > u8::MAX as usize {
+        return Err(CryptumError::UnsupportedParameterSet(parameter_set.to_string()));
+    }
+
+    if public_keys.is_empty() || public_keys.len() > MAX_RECIPIENTS {
+        return Err(CryptumError::InvalidSealedData(format!("invalid number of recipients {}", public_keys.len())));
+    }
+
+    let data_key = Secret::new(ByteArray::random(KEY_LENGTH));
+    let mut recipients = Vec::with_capacity(public_keys.len());
+
+    for public_key in public_keys {
+        let fingerprint = key_fingerprint(public_key);
+        let (encapsulation, shared_secret) = kem.encrypt(public_key.clone(), ByteArray::random(32), KEY_LENGTH as u8)?;
+        let shared_secret = Secret::new(shared_secret);
+
+        let wrapped_key = wrapping_cipher(&shared_secret, &fingerprint, &encapsulation)
+            .encrypt(&Nonce::default(), data_key.get_bytes())
+            .map_err(|_| CryptumError::Unknown)?;
+
+        recipients.push(Recipient {
+            fingerprint,
+            encapsulation,
+            wrapped_key: wrapped_key.into()
+        });
+    }
+
+    let header = SealHeader {
+        parameter_set: parameter_set.to_string(),
+        chunk_size,
+        recipients
+    };
+    let header_bytes = header.to_bytes();
+    write_bytes(writer, &header_bytes)?;
+
+    let cipher = derive_cipher(&data_key, &header_bytes);
+    let mut chunk = vec![0u8; chunk_size];
+    let mut index = 0u64;
+
+    loop {
+        let length = read_chunk(reader, &mut chunk)?;
+        // A full chunk is followed by at least one more, possibly empty, chunk
+        let is_last = length < chunk_size;
+
+        let payload = Payload { msg: &chunk[..length], aad: &header_bytes };
+        let sealed_chunk = cipher.encrypt(&chunk_nonce(index, is_last), payload)
+            .map_err(|_| CryptumError::Unknown)?;
+        write_bytes(writer, &sealed_chunk)?;
+
+        if is_last {
+            break;
+        }
+        index += 1;
+    }
+
+    writer.flush().map_err(write_error)
+}
+
+/// This function decrypts data sealed by the seal function. The recipient is found from the
+/// fingerprint of the public key embedded in the private key, then each chunk is authenticated
+/// before being written.
+///
+/// Input:
+///     kem: The KEM of the parameter set named in the header
+///     private_key: The private key of one of the recipients
+///     header: The header read by SealHeader::read_from
+///     reader: The encrypted chunks following the header
+///     writer: Where to write the decrypted data
+pub fn open(kem: &dyn KyberKEM, private_key: ByteArray, header: &SealHeader, reader: &mut impl Read, writer: &mut impl Write) -> CryptumResult<()> {
+    // The private key is validated and expanded once for the lookup and the decapsulation
+    let private_key = kem.prepare_private_key(private_key)?;
+    let fingerprint = key_fingerprint(private_key.public_key().as_bytes());
+
+    let recipient = header.recipients.iter()
+        .find(|recipient| recipient.fingerprint == fingerprint)
+        .ok_or(CryptumError::NotARecipient)?;
+
+    let shared_secret = Secret::new(kem.decapsulate_prepared(&private_key, recipient.encapsulation.clone(), KEY_LENGTH as u8)?);
+
+    let data_key = wrapping_cipher(&shared_secret, &recipient.fingerprint, &recipient.encapsulation)
+        .decrypt(&Nonce::default(), recipient.wrapped_key.get_bytes())
+        .map_err(|_| CryptumError::DecryptionFailed)?;
+    let data_key = Secret::new(ByteArray::from(data_key));
+
+    let header_bytes = header.to_bytes();
+    let cipher = derive_cipher(&data_key, &header_bytes);
+
+    let mut sealed_chunk = vec![0u8; header.chunk_size + TAG_LENGTH];
+    let mut index = 0u64;
+
+    loop {
+        let length = read_chunk(reader, &mut sealed_chunk)?;
+        let is_last = length < sealed_chunk.len();
+
+        if length < TAG_LENGTH {
+            return Err(CryptumError::InvalidSealedData("the data is truncated".to_string()));
+        }
+
+        let payload = Payload { msg: &sealed_chunk[..length], aad: &header_bytes };
+        let chunk = cipher.decrypt(&chunk_nonce(index, is_last), payload)
+            .map_err(|_| CryptumError::DecryptionFailed)?;
+        write_bytes(writer, &chunk)?;
+
+        if is_last {
+            break;
+        }
+        index += 1;
+    }
+
+    writer.flush().map_err(write_error)
+}
+
+/// This function derives the ChaCha20-Poly1305 key wrapping the data key for a recipient from the
+/// shared secret encapsulated for it. As this key is only used once, the wrapping uses a zero
+/// nonce.
+fn wrapping_cipher(shared_secret: &ByteArray, fingerprint: &ByteArray, encapsulation: &ByteArray) -> ChaCha20Poly1305 {
+    let key = Secret::new(shake_256(
+        &[KEY_WRAPPING_LABEL, shared_secret.get_bytes(), fingerprint.get_bytes(), encapsulation.get_bytes()].concat(),
+        KEY_LENGTH
+    ));
+
+    ChaCha20Poly1305::new(Key::from_slice(&key))
+}
+
+/// This function derives the ChaCha20-Poly1305 key of the chunks from the data key and the
+/// header.
+fn derive_cipher(data_key: &ByteArray, header_bytes: &[u8]) -> ChaCha20Poly1305 {
+    let key = Secret::new(shake_256(
+        &[KEY_DERIVATION_LABEL, data_key.get_bytes(), header_bytes].concat(),
+        KEY_LENGTH
+    ));
+
+    ChaCha20Poly1305::new(Key::from_slice(&key))
+}
+
+/// This function builds the 12-bytes nonce of a chunk: its index in big-endian followed by
+/// three zero bytes and the last chunk flag.
+fn chunk_nonce(index: u64, is_last: bool) -> Nonce {
+    let mut nonce = [0u8; 12];
+    nonce[..8].copy_from_slice(&index.to_be_bytes());
+    nonce[11] = is_last as u8;
+    nonce.into()
+}
+
+fn read_header_bytes(reader: &mut impl Read, buffer: &mut [u8]) -> CryptumResult<()> {
+    reader.read_exact(buffer).map_err(|err| match err.kind() {
+        ErrorKind::UnexpectedEof => CryptumError::InvalidSealedData("the header is truncated".to_string()),
+        _ => CryptumError::IO(format!("Error while reading the data: {}", err))
+    })
+}
+
+fn read_header_array<const N: usize>(reader: &mut impl Read) -> CryptumResult<[u8; N]> {
+    let mut bytes = [0u8; N];
+    read_header_bytes(reader, &mut bytes)?;
+    Ok(bytes)
+}
+
+fn write_bytes(writer: &mut impl Write, bytes: &[u8]) -> CryptumResult<()> {
+    writer.write_all(bytes).map_err(write_error)
+}
+
+fn write_error(err: std::io::Error) -> CryptumError {
+    CryptumError::IO(format!("Error while writing the data: {}", err))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::algorithms::byte_array::ByteArray;
+    use crate::algorithms::kyber::kem::{KyberKEM, KyberKEM512};
+    use crate::algorithms::kyber::ml_kem::MLKEM768;
+    use crate::algorithms::kyber::seal::{key_fingerprint, open, seal, seal_with_chunk_size, SealHeader, TAG_LENGTH};
+    use crate::algorithms::utils::hash::take_hash_trace;
+    use crate::errors::CryptumError;
+
+    const CHUNK_SIZE: usize = 100;
+
+    fn seal_bytes(kem: &dyn KyberKEM, public_keys: &[ByteArray], data: &[u8]) -> Vec<u8> {
+        let mut sealed = vec![];
+        seal_with_chunk_size(kem, "ml-kem-768", public_keys, &mut &data[..], &mut sealed, CHUNK_SIZE).unwrap();
+        sealed
+    }
+
+    fn open_bytes(kem: &dyn KyberKEM, private_key: &ByteArray, sealed: &[u8]) -> Result<Vec<u8>, CryptumError> {
+        let mut reader = sealed;
+        let header = SealHeader::read_from(&mut reader)?;
+        let mut opened = vec![];
+        open(kem, private_key.clone(), &header, &mut reader, &mut opened)?;
+        Ok(opened)
+    }
+
+    #[test]
+    fn test_seal_open() {
+        let kem = MLKEM768::init();
+        let (public_key, private_key) = kem.keygen(ByteArray::random(64)).unwrap();
+
+        for length in [0, 1, CHUNK_SIZE - 1, CHUNK_SIZE, CHUNK_SIZE + 1, 3 * CHUNK_SIZE + 7] {
+            let data = ByteArray::random(length);
+            let sealed = seal_bytes(&kem, std::slice::from_ref(&public_key), data.get_bytes());
+
+            let mut reader = sealed.as_slice();
+            let header = SealHeader::read_from(&mut reader).unwrap();
+            assert_eq!(header.parameter_set(), "ml-kem-768");
+            assert_eq!(header.recipient_fingerprints(), vec![&key_fingerprint(&public_key)]);
+            assert_eq!(reader.len(), length + (length / CHUNK_SIZE + 1) * TAG_LENGTH);
+
+            assert_eq!(open_bytes(&kem, &private_key, &sealed).unwrap(), data.get_bytes());
+        }
+    }
+
+    #[test]
+    fn test_open_expands_the_private_key_once() {
+        let kem = MLKEM768::init();
+        let (public_key, private_key) = kem.keygen(ByteArray::random(64)).unwrap();
+        let sealed = seal_bytes(&kem, std::slice::from_ref(&public_key), b"data");
+        let (ciphertext, _) = kem.encrypt(public_key, ByteArray::random(32), 32).unwrap();
+
+        // The matrix A is expanded with SHAKE-128 as many times as by a single decapsulation
+        let count_shake_128 = |trace: Vec<(&str, usize)>| trace.iter().filter(|(function, _)| *function == "shake_128").count();
+
+        take_hash_trace();
+        kem.decrypt(ciphertext, private_key.clone(), 32).unwrap();
+        let decrypt_count = count_shake_128(take_hash_trace());
+
+        open_bytes(&kem, &private_key, &sealed).unwrap();
+        let open_count = count_shake_128(take_hash_trace());
+
+        assert!(decrypt_count > 0);
+        assert_eq!(open_count, decrypt_count);
+    }
+
+    #[test]
+    fn test_seal_open_default_chunk_size() {
+        let kem = KyberKEM512::init();
+        let (public_key, private_key) = kem.keygen(ByteArray::random(64)).unwrap();
+        let data = ByteArray::random(200_000);
+
+        let mut sealed = vec![];
+        seal(&kem, "512", &[public_key], &mut data.get_bytes(), &mut sealed).unwrap();
+        assert_eq!(open_bytes(&kem, &private_key, &sealed).unwrap(), data.get_bytes());
+    }
+
+    #[test]
+    fn test_open_modified_data() {
+        let kem = MLKEM768::init();
+        let (public_key, private_key) = kem.keygen(ByteArray::random(64)).unwrap();
+        let sealed = seal_bytes(&kem, &[public_key], ByteArray::random(3 * CHUNK_SIZE + 7).get_bytes());
+        let header_length = sealed.len() - 3 * (CHUNK_SIZE + TAG_LENGTH) - 7 - TAG_LENGTH;
+        let chunks_start = header_length;
+        let sealed_chunk_length = CHUNK_SIZE + TAG_LENGTH;
+
+        // A modified byte in a chunk and in the header
+        for index in [chunks_start + 5, sealed.len() - 1, header_length - 1] {
+            let mut modified = sealed.clone();
+            modified[index] ^= 1;
+            assert!(matches!(open_bytes(&kem, &private_key, &modified), Err(CryptumError::DecryptionFailed)));
+        }
+
+        // Two chunks swapped
+        let mut swapped = sealed.clone();
+        let (first, second) = swapped[chunks_start..].split_at_mut(sealed_chunk_length);
+        first.swap_with_slice(&mut second[..sealed_chunk_length]);
+        assert!(matches!(open_bytes(&kem, &private_key, &swapped), Err(CryptumError::DecryptionFailed)));
+
+        // The last chunk dropped, which leaves no chunk flagged as the last one, and cut
+        let truncated = &sealed[..sealed.len() - 7 - TAG_LENGTH];
+        assert!(matches!(open_bytes(&kem, &private_key, truncated), Err(CryptumError::InvalidSealedData(_))));
+        let truncated = &sealed[..sealed.len() - 3];
+        assert!(matches!(open_bytes(&kem, &private_key, truncated), Err(CryptumError::DecryptionFailed)));
+
+        // Another private key
+        let (_, other_private_key) = kem.keygen(ByteArray::random(64)).unwrap();
+        assert!(matches!(open_bytes(&kem, &other_private_key, &sealed), Err(CryptumError::NotARecipient)));
+    }
+
+    #[test]
+    fn test_seal_open_several_recipients() {
+        let kem = MLKEM768::init();
+        let key_pairs: Vec<_> = (0..3).map(|_| kem.keygen(ByteArray::random(64)).unwrap()).collect();
+        let public_keys: Vec<_> = key_pairs.iter().map(|(public_key, _)| public_key.clone()).collect();
+        let data = ByteArray::random(2 * CHUNK_SIZE + 1);
+        let sealed = seal_bytes(&kem, &public_keys, data.get_bytes());
+
+        let header = SealHeader::read_from(&mut sealed.as_slice()).unwrap();
+        let fingerprints: Vec<_> = public_keys.iter().map(key_fingerprint).collect();
+        assert_eq!(header.recipient_fingerprints(), fingerprints.iter().collect::<Vec<_>>());
+
+        for (_, private_key) in &key_pairs {
+            assert_eq!(open_bytes(&kem, private_key, &sealed).unwrap(), data.get_bytes());
+        }
+
+        let (_, other_private_key) = kem.keygen(ByteArray::random(64)).unwrap();
+        assert!(matches!(open_bytes(&kem, &other_private_key, &sealed), Err(CryptumError::NotARecipient)));
+
+        // A recipient cannot be removed from the header without the opening failing
+        let mut removed = sealed.clone();
+        let count_index = 7 + 1 + 1 + "ml-kem-768".len() + 4;
+        removed[count_index + 1] = 2;
+        let recipient_length = 32 + 4 + 1088 + 48;
+        removed.drain(count_index + 2 + 2 * recipient_length..count_index + 2 + 3 * recipient_length);
+        assert!(matches!(open_bytes(&kem, &key_pairs[0].1, &removed), Err(CryptumError::DecryptionFailed)));
+    }
+
+    #[test]
+    fn test_seal_without_recipients() {
+        let kem = MLKEM768::init();
+        let mut sealed = vec![];
+        assert!(seal(&kem, "ml-kem-768", &[], &mut &b"data"[..], &mut sealed).is_err());
+    }
+
+    #[test]
+    fn test_read_invalid_header() {
+        let kem = MLKEM768::init();
+        let (public_key, _) = kem.keygen(ByteArray::random(64)).unwrap();
+        let sealed = seal_bytes(&kem, &[public_key], b"data");
+
+        let mut modified = sealed.clone();
+        modified[0] = b'X';
+        assert!(matches!(SealHeader::read_from(&mut modified.as_slice()), Err(CryptumError::InvalidSealedData(_))));
+
+        let mut modified = sealed.clone();
+        modified[7] = 1;
+        assert!(matches!(SealHeader::read_from(&mut modified.as_slice()), Err(CryptumError::InvalidSealedData(_))));
+
+        assert!(matches!(SealHeader::read_from(&mut &sealed[..20]), Err(CryptumError::InvalidSealedData(_))));
+    }
+}
@@ -0,0 +1,306 @@
+use std::fmt::{Debug, Formatter};
+use std::marker::PhantomData;
+use subtle::{Choice, ConstantTimeEq};
+use crate::algorithms::byte_array::ByteArray;
+use crate::algorithms::kyber::constants::KYBER_N_VALUE;
+use crate::algorithms::kyber::cpapke::KyberPKE;
+use crate::algorithms::kyber::kem::KyberKEM;
+use crate::algorithms::utils::constant_time::ct_eq;
+use crate::CryptumResult;
+use crate::errors::CryptumError;
+
+/// This trait gives the lengths of the keys and ciphertexts of a Kyber parameter set. It is
+/// implemented by the PKE and KEM structures so that the typed keys of two different schemes
+/// (or of two different security levels) can not be mixed up.
+pub trait ParameterSet {
+    const PUBLIC_KEY_LENGTH: usize;
+    const SECRET_KEY_LENGTH: usize;
+    const CIPHERTEXT_LENGTH: usize;
+}
+
+/// This function returns the module rank k associated to a Kyber version (512, 768 or 1024).
+pub(crate) const fn k_value(version: usize) -> usize {
+    version / KYBER_N_VALUE
+}
+
+/// This function returns the length of the CPAPKE public key for a Kyber version.
+pub(crate) const fn cpapke_public_key_length(version: usize) -> usize {
+    (12 * k_value(version) * KYBER_N_VALUE) / 8 + 32
+}
+
+/// This function returns the length of the CPAPKE private key for a Kyber version.
+pub(crate) const fn cpapke_private_key_length(version: usize) -> usize {
+    (12 * k_value(version) * KYBER_N_VALUE) / 8
+}
+
+/// This function returns the length of a ciphertext for a Kyber version. The compression
+/// parameters d_u and d_v are the ones set by the init functions of the CPAPKE.
+pub(crate) const fn ciphertext_length(version: usize) -> usize {
+    let (d_u, d_v) = if version == 1024 { (11, 5) } else { (10, 4) };
+    (d_u * k_value(version) * KYBER_N_VALUE) / 8 + (d_v * KYBER_N_VALUE) / 8
+}
+
+/// This function returns the length of the KEM private key for a Kyber version.
+pub(crate) const fn kem_private_key_length(version: usize) -> usize {
+    (24 * k_value(version) * KYBER_N_VALUE) / 8 + 96
+}
+
+macro_rules! typed_bytes {
+    ($name:ident, $length:ident, $error:ident, $doc:literal) => {
+        #[doc = $doc]
+        pub struct $name<P: ParameterSet> {
+            bytes: ByteArray,
+            parameter_set: PhantomData<P>
+        }
+
+        impl <P: ParameterSet> $name<P> {
+            /// This function builds the value from its bytes representation after checking its
+            /// length against the one of the parameter set.
+            pub fn from_bytes(bytes: ByteArray) -> CryptumResult<Self> {
+                if bytes.length() != P::$length {
+                    return Err(CryptumError::$error {
+                        expected: P::$length,
+                        found: bytes.length()
+                    });
+                }
+
+                Ok(Self {
+                    bytes,
+                    parameter_set: PhantomData
+                })
+            }
+
+            pub fn as_bytes(&self) -> &ByteArray {
+                &self.bytes
+            }
+
+            pub fn into_bytes(self) -> ByteArray {
+                self.bytes
+            }
+        }
+
+        impl <P: ParameterSet> Clone for $name<P> {
+            fn clone(&self) -> Self {
+                Self {
+                    bytes: self.bytes.clone(),
+                    parameter_set: PhantomData
+                }
+            }
+        }
+    };
+}
+
+typed_bytes!(PublicKey, PUBLIC_KEY_LENGTH, InvalidKeyLength, "A public key of the parameter set P.");
+typed_bytes!(SecretKey, SECRET_KEY_LENGTH, InvalidKeyLength, "A secret key of the parameter set P.");
+typed_bytes!(Ciphertext, CIPHERTEXT_LENGTH, InvalidCiphertextLength, "A ciphertext produced with a public key of the parameter set P.");
+
+impl <P: ParameterSet> PartialEq for PublicKey<P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.bytes == other.bytes
+    }
+}
+
+impl <P: ParameterSet> PartialEq for Ciphertext<P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.bytes == other.bytes
+    }
+}
+
+// The secret values are compared without branching on their content
+impl <P: ParameterSet> ConstantTimeEq for SecretKey<P> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        ct_eq(&self.bytes, &other.bytes)
+    }
+}
+
+impl <P: ParameterSet> PartialEq for SecretKey<P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl <P: ParameterSet> Debug for PublicKey<P> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("PublicKey").field(&self.bytes.to_hex()).finish()
+    }
+}
+
+impl <P: ParameterSet> Debug for Ciphertext<P> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Ciphertext").field(&self.bytes.to_hex()).finish()
+    }
+}
+
+// The content of the secret key is wiped on drop by ByteArray, it must not be printed either
+impl <P: ParameterSet> Debug for SecretKey<P> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SecretKey([REDACTED])")
+    }
+}
+
+/// A shared secret produced by a KEM. Its length is chosen when encapsulating so it does not
+/// depend on the parameter set.
+#[derive(Clone)]
+pub struct SharedSecret(ByteArray);
+
+impl ConstantTimeEq for SharedSecret {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        ct_eq(&self.0, &other.0)
+    }
+}
+
+impl PartialEq for SharedSecret {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl Debug for SharedSecret {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SharedSecret([REDACTED])")
+    }
+}
+
+impl SharedSecret {
+    pub fn as_bytes(&self) -> &ByteArray {
+        &self.0
+    }
+
+    pub fn into_bytes(self) -> ByteArray {
+        self.0
+    }
+}
+
+impl From<ByteArray> for SharedSecret {
+    fn from(value: ByteArray) -> Self {
+        Self(value)
+    }
+}
+
+/// This trait is a typed version of `KyberPKE`: keys and ciphertexts are bound to the parameter
+/// set that produced them.
+pub trait TypedKyberPKE: KyberPKE + ParameterSet + Sized {
+    fn generate_keypair(&self, seed: ByteArray) -> CryptumResult<(PublicKey<Self>, SecretKey<Self>)> {
+        let (public_key, secret_key) = self.keygen(seed)?;
+        Ok((PublicKey::from_bytes(public_key)?, SecretKey::from_bytes(secret_key)?))
+    }
+
+    fn encrypt_message(&self, public_key: &PublicKey<Self>, message: ByteArray, random_coin: ByteArray) -> CryptumResult<Ciphertext<Self>> {
+        let ciphertext = self.encrypt(public_key.as_bytes().clone(), message, random_coin)?;
+        Ciphertext::from_bytes(ciphertext)
+    }
+
+    fn decrypt_message(&self, secret_key: &SecretKey<Self>, ciphertext: &Ciphertext<Self>) -> CryptumResult<ByteArray> {
+        self.decrypt(secret_key.as_bytes().clone(), ciphertext.as_bytes().clone())
+    }
+}
+
+impl <T: KyberPKE + ParameterSet> TypedKyberPKE for T {}
+
+/// This trait is a typed version of `KyberKEM`: keys and ciphertexts are bound to the parameter
+/// set that produced them and the secret key always comes before the ciphertext.
+pub trait TypedKyberKEM: KyberKEM + ParameterSet + Sized {
+    fn generate_keypair(&self, seed: ByteArray) -> CryptumResult<(PublicKey<Self>, SecretKey<Self>)> {
+        let (public_key, secret_key) = self.keygen(seed)?;
+        Ok((PublicKey::from_bytes(public_key)?, SecretKey::from_bytes(secret_key)?))
+    }
+
+    fn encapsulate(&self, public_key: &PublicKey<Self>, seed: ByteArray, shared_secret_key_size: u8) -> CryptumResult<(Ciphertext<Self>, SharedSecret)> {
+        let (ciphertext, shared_secret) = self.encrypt(public_key.as_bytes().clone(), seed, shared_secret_key_size)?;
+        Ok((Ciphertext::from_bytes(ciphertext)?, shared_secret.into()))
+    }
+
+    fn decapsulate(&self, secret_key: &SecretKey<Self>, ciphertext: &Ciphertext<Self>, shared_secret_key_size: u8) -> CryptumResult<SharedSecret> {
+        let shared_secret = self.decrypt(ciphertext.as_bytes().clone(), secret_key.as_bytes().clone(), shared_secret_key_size)?;
+        Ok(shared_secret.into())
+    }
+}
+
+impl <T: KyberKEM + ParameterSet> TypedKyberKEM for T {}
+
+
+#[cfg(test)]
+mod tests {
+    use subtle::ConstantTimeEq;
+    use crate::algorithms::byte_array::ByteArray;
+    use crate::algorithms::kyber::cpapke::{KyberCPAPKE1024, KyberCPAPKE512, KyberCPAPKE768};
+    use crate::algorithms::kyber::kem::{KyberKEM1024, KyberKEM512, KyberKEM768};
+    use crate::algorithms::kyber::ml_kem::{MLKEM1024, MLKEM512, MLKEM768};
+    use crate::algorithms::kyber::types::{ParameterSet, PublicKey, TypedKyberKEM, TypedKyberPKE};
+    use crate::algorithms::kyber::KyberPKE;
+    use crate::errors::CryptumError;
+
+    #[test]
+    fn test_parameter_set_lengths() {
+        assert_eq!(KyberCPAPKE512::PUBLIC_KEY_LENGTH, KyberCPAPKE512::init().get_public_key_length());
+        assert_eq!(KyberCPAPKE768::SECRET_KEY_LENGTH, KyberCPAPKE768::init().get_private_key_length());
+        assert_eq!(KyberCPAPKE512::CIPHERTEXT_LENGTH, KyberCPAPKE512::init().get_ciphertext_length());
+        assert_eq!(KyberCPAPKE768::CIPHERTEXT_LENGTH, KyberCPAPKE768::init().get_ciphertext_length());
+        assert_eq!(KyberCPAPKE1024::CIPHERTEXT_LENGTH, KyberCPAPKE1024::init().get_ciphertext_length());
+
+        assert_eq!((KyberKEM512::PUBLIC_KEY_LENGTH, KyberKEM512::SECRET_KEY_LENGTH, KyberKEM512::CIPHERTEXT_LENGTH), (800, 1632, 768));
+        assert_eq!((KyberKEM768::PUBLIC_KEY_LENGTH, KyberKEM768::SECRET_KEY_LENGTH, KyberKEM768::CIPHERTEXT_LENGTH), (1184, 2400, 1088));
+        assert_eq!((KyberKEM1024::PUBLIC_KEY_LENGTH, KyberKEM1024::SECRET_KEY_LENGTH, KyberKEM1024::CIPHERTEXT_LENGTH), (1568, 3168, 1568));
+        assert_eq!((MLKEM512::PUBLIC_KEY_LENGTH, MLKEM512::SECRET_KEY_LENGTH, MLKEM512::CIPHERTEXT_LENGTH), (800, 1632, 768));
+        assert_eq!((MLKEM768::PUBLIC_KEY_LENGTH, MLKEM768::SECRET_KEY_LENGTH, MLKEM768::CIPHERTEXT_LENGTH), (1184, 2400, 1088));
+        assert_eq!((MLKEM1024::PUBLIC_KEY_LENGTH, MLKEM1024::SECRET_KEY_LENGTH, MLKEM1024::CIPHERTEXT_LENGTH), (1568, 3168, 1568));
+    }
+
+    #[test]
+    fn test_from_bytes_invalid_length() {
+        let result = PublicKey::<KyberKEM768>::from_bytes(ByteArray::random(800));
+
+        assert!(matches!(result, Err(CryptumError::InvalidKeyLength { expected: 1184, found: 800 })));
+    }
+
+    #[test]
+    fn test_secrets_are_redacted() {
+        let kyber = KyberKEM512::init();
+        let (public_key, secret_key) = kyber.generate_keypair(ByteArray::random(64)).unwrap();
+        let (_, shared_secret) = kyber.encapsulate(&public_key, ByteArray::random(32), 32).unwrap();
+
+        assert_eq!(format!("{:?}", secret_key), "SecretKey([REDACTED])");
+        assert_eq!(format!("{:?}", shared_secret), "SharedSecret([REDACTED])");
+        assert_eq!(format!("{:?}", public_key), format!("PublicKey({:?})", public_key.as_bytes().to_hex()));
+    }
+
+    #[test]
+    fn test_typed_kem() {
+        let kyber = MLKEM768::init();
+        let (public_key, secret_key) = kyber.generate_keypair(ByteArray::random(64)).unwrap();
+        let (ciphertext, shared_secret) = kyber.encapsulate(&public_key, ByteArray::random(32), 32).unwrap();
+        let shared_secret_prime = kyber.decapsulate(&secret_key, &ciphertext, 32).unwrap();
+
+        assert_eq!(shared_secret, shared_secret_prime);
+    }
+
+    #[test]
+    fn test_secrets_are_compared_in_constant_time() {
+        let kyber = KyberKEM768::init();
+        let seed = ByteArray::random(64);
+        let (public_key, secret_key) = kyber.generate_keypair(seed.clone()).unwrap();
+        let (_, secret_key_prime) = kyber.generate_keypair(seed).unwrap();
+        let (_, other_secret_key) = kyber.generate_keypair(ByteArray::random(64)).unwrap();
+
+        assert!(bool::from(secret_key.ct_eq(&secret_key_prime)));
+        assert!(!bool::from(secret_key.ct_eq(&other_secret_key)));
+        assert!(secret_key == secret_key_prime && secret_key != other_secret_key);
+
+        let (ciphertext, shared_secret) = kyber.encapsulate(&public_key, ByteArray::random(32), 32).unwrap();
+        let shared_secret_prime = kyber.decapsulate(&secret_key, &ciphertext, 32).unwrap();
+        let rejected_shared_secret = kyber.decapsulate(&other_secret_key, &ciphertext, 32).unwrap();
+
+        assert!(bool::from(shared_secret.ct_eq(&shared_secret_prime)));
+        assert!(!bool::from(shared_secret.ct_eq(&rejected_shared_secret)));
+    }
+
+    #[test]
+    fn test_typed_pke() {
+        let kyber = KyberCPAPKE1024::init();
+        let message = ByteArray::random(32);
+        let (public_key, secret_key) = kyber.generate_keypair(ByteArray::random(32)).unwrap();
+        let ciphertext = kyber.encrypt_message(&public_key, message.clone(), ByteArray::random(32)).unwrap();
+
+        assert_eq!(kyber.decrypt_message(&secret_key, &ciphertext).unwrap(), message);
+    }
+}
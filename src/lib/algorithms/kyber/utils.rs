@@ -1,46 +1,30 @@
 use crate::algorithms::byte_array::ByteArray;
-use crate::algorithms::kyber::constants::{KYBER_N_VALUE, KYBER_Q_VALUE, KYBER_RANDOM_COIN_LENGTH};
+use crate::algorithms::kyber::constants::{KYBER_Q_VALUE, KYBER_RANDOM_COIN_LENGTH};
 use crate::algorithms::kyber::galois_field::GF3329;
-use crate::algorithms::kyber::polynomial::PolyRQ;
+use crate::algorithms::utils::hash::{sha3_256, sha3_512};
 
-pub fn poly_coefficients_to_bits(poly: &PolyRQ, max_shift: usize) -> Vec<u8> {
-    let mut bits = Vec::with_capacity(KYBER_N_VALUE * max_shift);
+// Multiplier and shift used to divide by q without a division instruction: for every n below
+// 2^26, (n * DIVIDE_BY_Q_MULTIPLIER) >> DIVIDE_BY_Q_SHIFT is equal to n / q.
+const DIVIDE_BY_Q_SHIFT: u32 = 48;
+const DIVIDE_BY_Q_MULTIPLIER: u64 = (1 << DIVIDE_BY_Q_SHIFT) / KYBER_Q_VALUE as u64 + 1;
 
-    for i in 0..KYBER_N_VALUE {
-        let coefficient = poly[i];
-        bits.extend_from_slice(&gf3329_to_bits(&coefficient, max_shift));
-    }
-
-    bits
-}
-
-fn gf3329_to_bits(coefficient: &GF3329, max_shift: usize) -> Vec<u8> {
-    let mut bits = Vec::with_capacity(max_shift);
-    let coefficient_value = coefficient.into_inner();
-
-    for shift in 0..max_shift {
-        bits.push(((coefficient_value >> shift) & 1) as u8)
-    }
-
-    bits
-}
-
-
-/// This function rounds to the nearest integer with ties going up
-pub fn round(value: f64) -> usize {
-    (value + 0.5).floor() as usize
+fn divide_by_q(value: u64) -> u64 {
+    (value * DIVIDE_BY_Q_MULTIPLIER) >> DIVIDE_BY_Q_SHIFT
 }
 
+/// This function corresponds to the Compress_q function (p.6 of the article). The rounding of
+/// (2^d / q)·x is computed as floor((2^(d+1)·x + q) / 2q) with integer arithmetic only, so that
+/// the timing does not depend on the (possibly secret) coefficient.
 pub fn compress_d(x: GF3329, d_value: u32) ->  GF3329 {
-    let two_pow_d = 2_usize.pow(d_value) ;
-    let mut value = round((two_pow_d as f64 / KYBER_Q_VALUE as f64) * x.into_inner() as f64);
-    value = value.rem_euclid(two_pow_d);
-    GF3329::from(value)
+    let numerator = ((x.into_inner() as u64) << (d_value + 1)) + KYBER_Q_VALUE as u64;
+    let value = (divide_by_q(numerator) >> 1) & ((1 << d_value) - 1);
+    GF3329::from(value as usize)
 }
 
+/// This function corresponds to the Decompress_q function (p.6 of the article). The rounding of
+/// (q / 2^d)·x is computed as floor((q·x + 2^(d-1)) / 2^d).
 pub fn decompress_d(x: GF3329, d_value: u32) ->  GF3329 {
-    let two_pow_d = 2_usize.pow(d_value);
-    let value = round((KYBER_Q_VALUE as f64 / two_pow_d as f64) * x.into_inner() as f64);
+    let value = (x.into_inner() * KYBER_Q_VALUE + (1 << (d_value - 1))) >> d_value;
     GF3329::from(value)
 }
 
@@ -48,37 +32,82 @@ pub fn get_random_coin() -> ByteArray {
     ByteArray::random(KYBER_RANDOM_COIN_LENGTH)
 }
 
+/// This function corresponds to the H function of the round 3 KEM and of FIPS 203 (section 4.1)
+/// which is a wrapper around the Sha3-256 hash function.
+///
+/// Input:
+///     data: A bytes array
+/// Output:
+///     A bytes array of length 32
+pub(crate) fn h(data: &ByteArray) -> ByteArray {
+    sha3_256(data.get_bytes()).into()
+}
+
+/// This function corresponds to the G function of the round 3 KEM and of FIPS 203 (section 4.1)
+/// which is a wrapper around the Sha3-512 hash function.
+///
+/// Input:
+///     data: A bytes array
+/// Output:
+///     A tuple of bytes array each of them is of length 32
+pub(crate) fn g(data: &ByteArray) -> (ByteArray, ByteArray) {
+    let hash: ByteArray = sha3_512(data.get_bytes()).into();
+    hash.split_at(32)
+}
+
 
 #[cfg(test)]
 mod tests {
+    use crate::algorithms::kyber::constants::KYBER_Q_VALUE;
     use crate::algorithms::kyber::galois_field::GF3329;
-    use crate::algorithms::kyber::utils::{decompress_d, round};
+    use crate::algorithms::kyber::utils::{compress_d, decompress_d, divide_by_q};
+
+    const D_VALUES: [u32; 5] = [1, 4, 5, 10, 11];
 
     #[test]
-    fn test_round_should_return_2() {
-        let tested_value = 2.22;
-        let expected_value = 2;
-        let output = round(tested_value);
+    fn test_divide_by_q() {
+        for value in (0..1 << 26).step_by(97).chain((1 << 26) - KYBER_Q_VALUE..1 << 26) {
+            assert_eq!(divide_by_q(value as u64), (value / KYBER_Q_VALUE) as u64);
+        }
+    }
 
-        assert_eq!(output, expected_value)
+    #[test]
+    fn test_compress_all_inputs() {
+        for d_value in D_VALUES {
+            for x in 0..KYBER_Q_VALUE {
+                // Rounding of (2^d / q)·x with ties going up, as in the reference implementation
+                let expected = (((x << (d_value + 1)) + KYBER_Q_VALUE) / (2 * KYBER_Q_VALUE)) % (1 << d_value);
+
+                assert_eq!(compress_d(x.into(), d_value), GF3329::from(expected), "x = {}, d = {}", x, d_value);
+            }
+        }
     }
 
     #[test]
-    fn test_round_should_return_3() {
-        let tested_value = 2.78;
-        let expected_value = 3;
-        let output = round(tested_value);
+    fn test_decompress_all_inputs() {
+        for d_value in D_VALUES {
+            for x in 0..1usize << d_value {
+                let expected = (2 * x * KYBER_Q_VALUE + (1 << d_value)) / (1 << (d_value + 1));
 
-        assert_eq!(output, expected_value);
+                assert_eq!(decompress_d(x.into(), d_value), GF3329::from(expected), "x = {}, d = {}", x, d_value);
+            }
+        }
     }
 
     #[test]
-    fn test_round_with_tie() {
-        let tested_value = 2.5;
-        let expected_value = 3;
-        let output = round(tested_value);
-
-        assert_eq!(output, expected_value);
+    fn test_decompress_compress_error_bound() {
+        // |x - Decompress(Compress(x))| mod± q should be at most round(q / 2^(d+1))
+        for d_value in D_VALUES {
+            let bound = (KYBER_Q_VALUE + (1 << d_value)) >> (d_value + 1);
+
+            for x in 0..KYBER_Q_VALUE {
+                let y = decompress_d(compress_d(x.into(), d_value), d_value).into_inner();
+                let difference = (x + KYBER_Q_VALUE - y) % KYBER_Q_VALUE;
+                let difference = difference.min(KYBER_Q_VALUE - difference);
+
+                assert!(difference <= bound, "x = {}, d = {}", x, d_value);
+            }
+        }
     }
 
     #[test]
@@ -92,4 +121,3 @@ mod tests {
         }
     }
 }
-
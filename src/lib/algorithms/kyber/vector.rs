@@ -5,6 +5,7 @@ use crate::algorithms::kyber::compress::{Compress, Decompress};
 use crate::algorithms::kyber::encoder::{Decoder, Encoder};
 use crate::algorithms::kyber::ntt::NTT;
 use crate::algorithms::kyber::polynomial::PolyRQ;
+use crate::CryptumResult;
 
 pub type VectorRQ = Vector<PolyRQ>;
 
@@ -48,22 +49,17 @@ impl Encoder for VectorRQ {
 }
 
 impl Decoder for VectorRQ {
-    fn decode(bytes: ByteArray, l_value: u8) -> Self {
-        // Checking length of bytes
+    fn decode(bytes: ByteArray, l_value: u8) -> CryptumResult<Self> {
         let bytes_length = bytes.length();
-
-        if bytes_length % (32 * l_value as usize) != 0 {
-            panic!("bytes is not a multiple of {}", 32 * l_value)
-        }
-
         let mut polynomials = Vec::with_capacity(bytes_length / (32 * l_value as usize));
 
-        for chunk in bytes.get_bytes().chunks_exact(32 * l_value as usize) {
-            polynomials.push(PolyRQ::decode(chunk.into(), l_value))
+        // A trailing chunk which is too short is rejected when decoding the polynomial
+        for chunk in bytes.get_bytes().chunks(32 * l_value as usize) {
+            polynomials.push(PolyRQ::decode(chunk.into(), l_value)?)
         }
 
 
-        polynomials.into()
+        Ok(polynomials.into())
     }
 }
 
@@ -1,4 +1,5 @@
 pub mod kyber;
-mod utils;
+pub mod dilithium;
+pub(crate) mod utils;
 mod algebraic;
 pub mod byte_array;
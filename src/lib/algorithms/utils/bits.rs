@@ -1,6 +1,6 @@
 pub fn byte_to_bits(byte_value: u8, bits_arr: &mut [u8; 8]) {
-    for i in 0..8 {
-        bits_arr[i] = (byte_value >> i) & 0x1
+    for (i, bit) in bits_arr.iter_mut().enumerate() {
+        *bit = (byte_value >> i) & 0x1
     }
 }
 
@@ -12,14 +12,12 @@ pub fn bits_to_byte(bits: &[u8]) -> u8 {
 
     let mut byte: u8 = 0;
 
-    for i in 0..8 {
-        let bit = bits[i];
-
+    for (i, &bit) in bits.iter().enumerate() {
         if bit != 0 && bit != 1 {
             panic!("Invalid value for bit !")
         }
 
-        byte += bits[i] * 2_u8.pow(i as u32)
+        byte += bit * 2_u8.pow(i as u32)
     }
 
     byte
@@ -0,0 +1,61 @@
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
+use crate::algorithms::byte_array::ByteArray;
+
+/// This function compares two byte arrays without branching on their content. Only the lengths,
+/// which are public, may leak through the timing of the comparison.
+///
+/// Input:
+///     a: A bytes array
+///     b: A bytes array
+/// Output:
+///     A Choice equal to 1 if both arrays are equal, 0 otherwise
+pub fn ct_eq(a: &ByteArray, b: &ByteArray) -> Choice {
+    a.get_bytes().ct_eq(b.get_bytes())
+}
+
+/// This function selects one of two byte arrays of the same length without branching on the
+/// choice.
+///
+/// Input:
+///     a: The bytes array returned when choice is 0
+///     b: The bytes array returned when choice is 1
+///     choice: The selection bit
+/// Output:
+///     A copy of a or b
+pub fn ct_select(a: &ByteArray, b: &ByteArray, choice: Choice) -> ByteArray {
+    assert_eq!(a.length(), b.length(), "Only arrays of the same length can be selected");
+
+    a.get_bytes()
+        .iter()
+        .zip(b.get_bytes().iter())
+        .map(|(a_byte, b_byte)| u8::conditional_select(a_byte, b_byte, choice))
+        .collect::<Vec<u8>>()
+        .into()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use subtle::Choice;
+    use crate::algorithms::byte_array::ByteArray;
+    use crate::algorithms::utils::constant_time::{ct_eq, ct_select};
+
+    #[test]
+    fn test_ct_eq() {
+        let a = ByteArray::from([1u8, 2, 3].as_slice());
+        let b = ByteArray::from([1u8, 2, 4].as_slice());
+
+        assert!(bool::from(ct_eq(&a, &a.clone())));
+        assert!(!bool::from(ct_eq(&a, &b)));
+        assert!(!bool::from(ct_eq(&a, &ByteArray::from([1u8, 2].as_slice()))));
+    }
+
+    #[test]
+    fn test_ct_select() {
+        let a = ByteArray::from([1u8, 2, 3].as_slice());
+        let b = ByteArray::from([4u8, 5, 6].as_slice());
+
+        assert_eq!(ct_select(&a, &b, Choice::from(0)), a);
+        assert_eq!(ct_select(&a, &b, Choice::from(1)), b);
+    }
+}
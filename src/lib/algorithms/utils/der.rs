@@ -0,0 +1,176 @@
+use crate::algorithms::byte_array::ByteArray;
+use crate::CryptumResult;
+use crate::errors::CryptumError;
+
+pub const INTEGER: u8 = 0x02;
+pub const BIT_STRING: u8 = 0x03;
+pub const OCTET_STRING: u8 = 0x04;
+pub const OBJECT_IDENTIFIER: u8 = 0x06;
+pub const SEQUENCE: u8 = 0x30;
+/// The tag of a primitive value with the context-specific tag [0] and an implicit type
+pub const CONTEXT_SPECIFIC_0: u8 = 0x80;
+
+/// This function encodes a value with the Distinguished Encoding Rules as the concatenation of
+/// its tag, the length of its content and its content (TLV).
+///
+/// Input:
+///     tag: The tag of the value
+///     content: The encoded content of the value
+/// Output:
+///     The DER encoding of the value
+pub fn encode_tlv(tag: u8, content: &ByteArray) -> ByteArray {
+    let length = content.length();
+    let mut header = vec![tag];
+
+    if length < 0x80 {
+        header.push(length as u8);
+    } else {
+        // Long form: the number of bytes of the length followed by the length in big-endian
+        let length_bytes: Vec<u8> = length.to_be_bytes()
+            .into_iter()
+            .skip_while(|&byte| byte == 0)
+            .collect();
+        header.push(0x80 | length_bytes.len() as u8);
+        header.extend(length_bytes);
+    }
+
+    ByteArray::concat(&[&header.into(), content])
+}
+
+/// This structure reads the values of a DER encoding one after the other.
+pub struct DerReader<'a> {
+    bytes: &'a [u8]
+}
+
+impl <'a> DerReader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    /// This function reads the next value after checking its tag.
+    ///
+    /// Input:
+    ///     tag: The expected tag
+    /// Output:
+    ///     The content of the value
+    pub fn read(&mut self, tag: u8) -> CryptumResult<&'a [u8]> {
+        let (&found_tag, rest) = self.bytes.split_first()
+            .ok_or_else(|| CryptumError::InvalidDer("unexpected end of data".to_string()))?;
+
+        if found_tag != tag {
+            return Err(CryptumError::InvalidDer(format!("expected tag {:#04x}, found {:#04x}", tag, found_tag)));
+        }
+
+        let (length, rest) = Self::read_length(rest)?;
+
+        if rest.len() < length {
+            return Err(CryptumError::InvalidDer("the length exceeds the data".to_string()));
+        }
+
+        let (content, rest) = rest.split_at(length);
+        self.bytes = rest;
+
+        Ok(content)
+    }
+
+    fn read_length(bytes: &[u8]) -> CryptumResult<(usize, &[u8])> {
+        let invalid_length = || CryptumError::InvalidDer("invalid length".to_string());
+
+        let (&first_byte, rest) = bytes.split_first().ok_or_else(invalid_length)?;
+
+        if first_byte < 0x80 {
+            return Ok((first_byte as usize, rest));
+        }
+
+        let length_size = (first_byte & 0x7f) as usize;
+
+        if length_size == 0 || length_size > size_of::<u32>() || rest.len() < length_size {
+            return Err(invalid_length());
+        }
+
+        let (length_bytes, rest) = rest.split_at(length_size);
+        let length = length_bytes.iter().fold(0, |length, &byte| (length << 8) | byte as usize);
+
+        // DER requires the shortest encoding of the length
+        if length < 0x80 || length_bytes[0] == 0 {
+            return Err(invalid_length());
+        }
+
+        Ok((length, rest))
+    }
+
+    /// This function returns the tag of the next value without reading it.
+    pub fn peek_tag(&self) -> Option<u8> {
+        self.bytes.first().copied()
+    }
+
+    /// This function checks that every value has been read.
+    pub fn finish(self) -> CryptumResult<()> {
+        if !self.bytes.is_empty() {
+            return Err(CryptumError::InvalidDer(format!("{} trailing bytes", self.bytes.len())));
+        }
+
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::algorithms::byte_array::ByteArray;
+    use crate::algorithms::utils::der::{encode_tlv, DerReader, OCTET_STRING, SEQUENCE};
+    use crate::errors::CryptumError;
+
+    #[test]
+    fn test_encode_tlv_length_forms() {
+        let short = encode_tlv(OCTET_STRING, &vec![7u8; 3].into());
+        assert_eq!(short.get_bytes(), [0x04, 0x03, 7, 7, 7]);
+
+        let long = encode_tlv(OCTET_STRING, &vec![0u8; 200].into());
+        assert_eq!(&long.get_bytes()[..3], [0x04, 0x81, 200]);
+
+        let longer = encode_tlv(OCTET_STRING, &vec![0u8; 3168].into());
+        assert_eq!(&longer.get_bytes()[..4], [0x04, 0x82, 0x0c, 0x60]);
+    }
+
+    #[test]
+    fn test_read() {
+        for length in [0, 1, 127, 128, 255, 256, 3168] {
+            let content = ByteArray::random(length);
+            let der = encode_tlv(SEQUENCE, &encode_tlv(OCTET_STRING, &content));
+
+            let mut reader = DerReader::new(der.get_bytes());
+            let mut inner = DerReader::new(reader.read(SEQUENCE).unwrap());
+            reader.finish().unwrap();
+
+            assert_eq!(inner.peek_tag(), Some(OCTET_STRING));
+            assert_eq!(inner.read(OCTET_STRING).unwrap(), content.get_bytes());
+            assert_eq!(inner.peek_tag(), None);
+            inner.finish().unwrap();
+        }
+    }
+
+    #[test]
+    fn test_read_invalid_encodings() {
+        let invalid_encodings: [&[u8]; 6] = [
+            &[],
+            &[0x30, 0x01],
+            &[0x04, 0x01, 0x00],
+            &[0x30, 0x81, 0x05, 0, 0, 0, 0, 0],
+            &[0x30, 0x82, 0x00, 0x80],
+            &[0x30, 0x80]
+        ];
+
+        for bytes in invalid_encodings {
+            let mut reader = DerReader::new(bytes);
+            assert!(matches!(reader.read(SEQUENCE), Err(CryptumError::InvalidDer(_))), "bytes = {:?}", bytes);
+        }
+    }
+
+    #[test]
+    fn test_finish_with_trailing_bytes() {
+        let mut reader = DerReader::new(&[0x04, 0x00, 0xff]);
+        reader.read(OCTET_STRING).unwrap();
+        assert!(matches!(reader.finish(), Err(CryptumError::InvalidDer(_))));
+    }
+}
@@ -0,0 +1,104 @@
+use aes::Aes256;
+use aes::cipher::{BlockEncrypt, KeyInit};
+use aes::cipher::generic_array::GenericArray;
+
+pub const DRBG_SEED_LENGTH: usize = 48;
+
+/// This structure corresponds to the AES-256 CTR DRBG (without derivation function) used by the
+/// NIST `PQCgenKAT_*` programs to derive the randomness of the Known Answer Tests (see `rng.c` in
+/// the reference implementations).
+pub struct AesCtrDrbg {
+    key: [u8; 32],
+    v: [u8; 16]
+}
+
+impl AesCtrDrbg {
+    /// This function corresponds to the randombytes_init function with no personalization string.
+    pub fn init(entropy_input: &[u8; DRBG_SEED_LENGTH]) -> Self {
+        let mut drbg = Self {
+            key: [0u8; 32],
+            v: [0u8; 16]
+        };
+        drbg.update(Some(entropy_input));
+        drbg
+    }
+
+    fn increment_v(&mut self) {
+        for byte in self.v.iter_mut().rev() {
+            if *byte == 0xFF {
+                *byte = 0x00;
+            } else {
+                *byte += 1;
+                break;
+            }
+        }
+    }
+
+    fn encrypt_v(&self) -> [u8; 16] {
+        let cipher = Aes256::new(GenericArray::from_slice(&self.key));
+        let mut block = GenericArray::clone_from_slice(&self.v);
+        cipher.encrypt_block(&mut block);
+        block.into()
+    }
+
+    /// This function corresponds to the AES256_CTR_DRBG_Update function.
+    fn update(&mut self, provided_data: Option<&[u8; DRBG_SEED_LENGTH]>) {
+        let mut temp = [0u8; DRBG_SEED_LENGTH];
+
+        for chunk in temp.chunks_exact_mut(16) {
+            self.increment_v();
+            chunk.copy_from_slice(&self.encrypt_v());
+        }
+
+        if let Some(data) = provided_data {
+            for (byte, data_byte) in temp.iter_mut().zip(data.iter()) {
+                *byte ^= data_byte;
+            }
+        }
+
+        self.key.copy_from_slice(&temp[0..32]);
+        self.v.copy_from_slice(&temp[32..48]);
+    }
+
+    /// This function corresponds to the randombytes function.
+    pub fn random_bytes(&mut self, length: usize) -> Vec<u8> {
+        let mut output = Vec::with_capacity(length);
+
+        while output.len() < length {
+            self.increment_v();
+            let block = self.encrypt_v();
+            let remaining = length - output.len();
+            output.extend_from_slice(&block[0..remaining.min(16)]);
+        }
+
+        self.update(None);
+        output
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use hex_literal::hex;
+    use crate::algorithms::utils::drbg::{AesCtrDrbg, DRBG_SEED_LENGTH};
+
+    #[test]
+    fn test_kat_seeds() {
+        // The NIST KAT generators initialize the DRBG with the bytes 0, 1, ..., 47 and then draw
+        // the seed of each test vector from it.
+        let mut entropy_input = [0u8; DRBG_SEED_LENGTH];
+        for (i, byte) in entropy_input.iter_mut().enumerate() {
+            *byte = i as u8;
+        }
+        let mut drbg = AesCtrDrbg::init(&entropy_input);
+
+        assert_eq!(
+            drbg.random_bytes(DRBG_SEED_LENGTH),
+            hex!("061550234D158C5EC95595FE04EF7A25767F2E24CC2BC479D09D86DC9ABCFDE7056A8C266F9EF97ED08541DBD2E1FFA1")
+        );
+        assert_eq!(
+            drbg.random_bytes(DRBG_SEED_LENGTH),
+            hex!("D81C4D8D734FCBFBEADE3D3F8A039FAA2A2C9957E835AD55B22E75BF57BB556AC81ADDE6AEEB4A5A875C3BFCADFA958F")
+        );
+    }
+}
@@ -1,43 +1,158 @@
 use std::io::Read;
-use sha3::{Sha3_256, Sha3_512, Shake128, Shake256};
-use sha3::digest::{Update, ExtendableOutput, FixedOutput};
+use sha3::{Sha3_256, Sha3_512, Shake128, Shake128Reader, Shake256};
+use sha3::digest::{Update, ExtendableOutput, FixedOutput, XofReader};
+use crate::algorithms::utils::keccak::{KeccakX4, PARALLEL_SPONGES};
 
+/// Number of bytes produced by one permutation of SHAKE-128
+pub const SHAKE_128_RATE: usize = 168;
+
+/// Number of bytes produced by one permutation of SHAKE-256
+pub const SHAKE_256_RATE: usize = 136;
+
+#[cfg(test)]
+thread_local! {
+    // Trace of the hash calls (function and input length) made by the current thread. It is used
+    // by the tests checking that two code paths perform the same operations.
+    static HASH_TRACE: std::cell::RefCell<Vec<(&'static str, usize)>> = const { std::cell::RefCell::new(vec![]) };
+}
+
+#[cfg(test)]
+fn trace(function: &'static str, data: &[u8]) {
+    HASH_TRACE.with(|calls| calls.borrow_mut().push((function, data.len())));
+}
+
+/// This function returns the hash calls made by the current thread since the last call.
+#[cfg(test)]
+pub fn take_hash_trace() -> Vec<(&'static str, usize)> {
+    HASH_TRACE.with(|calls| calls.take())
+}
 
 pub fn sha3_256(data: &[u8]) -> Vec<u8> {
+    #[cfg(test)]
+    trace("sha3_256", data);
+
     let mut hasher = Sha3_256::default();
     hasher.update(data);
     hasher.finalize_fixed().to_vec()
 }
 
 pub fn sha3_512(data: &[u8]) -> Vec<u8> {
+    #[cfg(test)]
+    trace("sha3_512", data);
+
     let mut hasher = Sha3_512::default();
     hasher.update(data);
     hasher.finalize_fixed().to_vec()
 }
 
 pub fn shake_128(data: &[u8], length: usize) -> Vec<u8> {
+    #[cfg(test)]
+    trace("shake_128", data);
+
     let mut buffer = vec![0u8; length];
     let mut hasher = Shake128::default();
     hasher.update(data);
     let mut xof_reader = hasher.finalize_xof();
-    xof_reader.read(&mut buffer).expect("Xof reader should give some bytes");
+    xof_reader.read_exact(&mut buffer).expect("Xof reader should give some bytes");
     buffer
 }
 
+/// This structure corresponds to the output of SHAKE-128 squeezed incrementally, one block of
+/// SHAKE_128_RATE bytes at a time.
+pub struct Shake128Stream {
+    reader: Shake128Reader
+}
+
+impl Shake128Stream {
+    pub fn squeeze_block(&mut self) -> [u8; SHAKE_128_RATE] {
+        let mut block = [0u8; SHAKE_128_RATE];
+        XofReader::read(&mut self.reader, &mut block);
+        block
+    }
+}
+
+pub fn shake_128_stream(data: &[u8]) -> Shake128Stream {
+    #[cfg(test)]
+    trace("shake_128", data);
+
+    let mut hasher = Shake128::default();
+    hasher.update(data);
+    Shake128Stream {
+        reader: hasher.finalize_xof()
+    }
+}
+
+/// This structure corresponds to the outputs of up to four SHAKE-128 instances squeezed together,
+/// one block of SHAKE_128_RATE bytes per instance at a time.
+pub struct Shake128StreamX4 {
+    sponges: KeccakX4
+}
+
+impl Shake128StreamX4 {
+    pub fn squeeze_blocks(&mut self) -> [[u8; SHAKE_128_RATE]; PARALLEL_SPONGES] {
+        let mut blocks = [[0u8; SHAKE_128_RATE]; PARALLEL_SPONGES];
+        let [first, second, third, fourth] = &mut blocks;
+        self.sponges.squeeze(&mut [first, second, third, fourth]);
+        blocks
+    }
+}
+
+/// This function computes up to four SHAKE-128 streams at once. The blocks squeezed for the
+/// missing inputs must be ignored.
+///
+/// Input:
+///     inputs: Between 1 and 4 arrays of bytes of the same length
+/// Output:
+///     The streams of the inputs
+pub fn shake_128_x4(inputs: &[&[u8]]) -> Shake128StreamX4 {
+    #[cfg(test)]
+    inputs.iter().for_each(|data| trace("shake_128", data));
+
+    Shake128StreamX4 {
+        sponges: KeccakX4::absorb(inputs, SHAKE_128_RATE)
+    }
+}
+
 pub fn shake_256(data: &[u8], length: usize) -> Vec<u8> {
+    #[cfg(test)]
+    trace("shake_256", data);
+
     let mut buffer = vec![0u8; length];
     let mut hasher = Shake256::default();
     hasher.update(data);
     let mut xof_reader = hasher.finalize_xof();
-    xof_reader.read(&mut buffer).expect("Xof reader should give some bytes");
+    xof_reader.read_exact(&mut buffer).expect("Xof reader should give some bytes");
     buffer
 }
 
+/// This function computes the SHAKE-256 outputs of up to four inputs at once.
+///
+/// Input:
+///     inputs: Between 1 and 4 arrays of bytes of the same length
+///     length: The length of every output
+/// Output:
+///     The outputs, in the order of the inputs
+pub fn shake_256_x4(inputs: &[&[u8]], length: usize) -> Vec<Vec<u8>> {
+    #[cfg(test)]
+    inputs.iter().for_each(|data| trace("shake_256", data));
+
+    let mut outputs = vec![vec![0u8; length]; inputs.len()];
+    let mut sponges = KeccakX4::absorb(inputs, SHAKE_256_RATE);
+
+    for offset in (0..length).step_by(SHAKE_256_RATE) {
+        let end = length.min(offset + SHAKE_256_RATE);
+        let mut blocks: Vec<&mut [u8]> = outputs.iter_mut().map(|output| &mut output[offset..end]).collect();
+        sponges.squeeze(&mut blocks);
+    }
+
+    outputs
+}
+
 
 #[cfg(test)]
 mod tests {
     use hex_literal::hex;
-    use crate::algorithms::utils::hash::{sha3_256, sha3_512, shake_128, shake_256};
+    use crate::algorithms::utils::hash::{sha3_256, sha3_512, shake_128, shake_128_stream, shake_128_x4, shake_256, shake_256_x4, SHAKE_128_RATE};
 
     #[test]
     fn test_sha3_256() {
@@ -63,6 +178,17 @@ mod tests {
         assert_eq!(output, hex!("18d71c6c1c2f8edac4e4"))
     }
 
+    #[test]
+    fn test_shake_128_stream() {
+        let data = b"telecom".as_slice();
+        let expected_output = shake_128(data, 3 * SHAKE_128_RATE);
+
+        let mut stream = shake_128_stream(data);
+        let output = [stream.squeeze_block(), stream.squeeze_block(), stream.squeeze_block()].concat();
+
+        assert_eq!(output, expected_output)
+    }
+
     #[test]
     fn test_shake_256() {
         let data = b"telecom".as_slice();
@@ -72,4 +198,33 @@ mod tests {
         assert_eq!(output.len(), expected_size);
         assert_eq!(output, hex!("20a3a9e642efb29ceb5b"))
     }
+
+    #[test]
+    fn test_shake_128_x4() {
+        let inputs: [&[u8]; 3] = [b"telecom", b"paris!!", &[0u8; 7]];
+
+        let mut stream = shake_128_x4(&inputs);
+        let blocks = [stream.squeeze_blocks(), stream.squeeze_blocks()];
+
+        for (i, input) in inputs.iter().enumerate() {
+            let output = [blocks[0][i], blocks[1][i]].concat();
+            assert_eq!(output, shake_128(input, 2 * SHAKE_128_RATE))
+        }
+    }
+
+    #[test]
+    fn test_shake_256_x4() {
+        // Inputs longer than a block, and outputs longer than a block and not a multiple of it
+        for (input_length, output_length) in [(0, 10), (33, 128), (136, 300), (500, 192)] {
+            let inputs: Vec<Vec<u8>> = (0..4u8).map(|i| vec![i; input_length]).collect();
+            let input_slices: Vec<&[u8]> = inputs.iter().map(|input| input.as_slice()).collect();
+
+            let outputs = shake_256_x4(&input_slices, output_length);
+
+            assert_eq!(outputs.len(), 4);
+            for (input, output) in inputs.iter().zip(outputs) {
+                assert_eq!(output, shake_256(input, output_length))
+            }
+        }
+    }
 }
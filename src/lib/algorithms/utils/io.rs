@@ -0,0 +1,56 @@
+use std::io::{ErrorKind, Read};
+use crate::CryptumResult;
+use crate::errors::CryptumError;
+
+/// This function fills the buffer with the data of the reader. Fewer bytes are only read at the
+/// end of the data.
+///
+/// Input:
+///     reader: The data
+///     buffer: Where to store the bytes read
+/// Output:
+///     The number of bytes read
+pub(crate) fn read_chunk(reader: &mut impl Read, buffer: &mut [u8]) -> CryptumResult<usize> {
+    let mut length = 0;
+
+    while length < buffer.len() {
+        match reader.read(&mut buffer[length..]) {
+            Ok(0) => break,
+            Ok(read_length) => length += read_length,
+            Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+            Err(err) => return Err(CryptumError::IO(format!("Error while reading the data: {}", err)))
+        }
+    }
+
+    Ok(length)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+    use crate::algorithms::utils::io::read_chunk;
+
+    /// A reader returning at most 3 bytes per call, as a pipe may do
+    struct SlowReader<'a>(&'a [u8]);
+
+    impl Read for SlowReader<'_> {
+        fn read(&mut self, buffer: &mut [u8]) -> std::io::Result<usize> {
+            let length = buffer.len().min(self.0.len()).min(3);
+            buffer[..length].copy_from_slice(&self.0[..length]);
+            self.0 = &self.0[length..];
+            Ok(length)
+        }
+    }
+
+    #[test]
+    fn test_read_chunk() {
+        let mut reader = SlowReader(b"0123456789");
+        let mut buffer = [0u8; 8];
+
+        assert_eq!(read_chunk(&mut reader, &mut buffer).unwrap(), 8);
+        assert_eq!(&buffer, b"01234567");
+        assert_eq!(read_chunk(&mut reader, &mut buffer).unwrap(), 2);
+        assert_eq!(&buffer[..2], b"89");
+        assert_eq!(read_chunk(&mut reader, &mut buffer).unwrap(), 0);
+    }
+}
@@ -0,0 +1,218 @@
+//! Four Keccak sponges permuted in parallel.
+//!
+//! Kyber expands its matrix and its noise from several independent SHAKE instances whose inputs
+//! have the same length, so their permutations can be computed together: with AVX2 every 64-bits
+//! lane of the state is stored in a 256-bits register holding the lane of the four states.
+
+use zeroize::Zeroize;
+
+/// Number of 64-bits lanes of a Keccak-f[1600] state
+const STATE_LENGTH: usize = 25;
+
+/// Number of sponges permuted together
+pub const PARALLEL_SPONGES: usize = 4;
+
+/// Domain separation bits and first bit of the padding of SHAKE-128 and SHAKE-256
+const SHAKE_PADDING: u8 = 0x1f;
+
+#[cfg(target_arch = "x86_64")]
+const ROUND_CONSTANTS: [u64; 24] = [
+    0x0000000000000001, 0x0000000000008082, 0x800000000000808a, 0x8000000080008000,
+    0x000000000000808b, 0x0000000080000001, 0x8000000080008081, 0x8000000000008009,
+    0x000000000000008a, 0x0000000000000088, 0x0000000080008009, 0x000000008000000a,
+    0x000000008000808b, 0x800000000000008b, 0x8000000000008089, 0x8000000000008003,
+    0x8000000000008002, 0x8000000000000080, 0x000000000000800a, 0x800000008000000a,
+    0x8000000080008081, 0x8000000000008080, 0x0000000080000001, 0x8000000080008008
+];
+
+/// This structure corresponds to four SHAKE sponges absorbing inputs of the same length. The
+/// sponges that are not used stay in their initial state and their output must be ignored.
+pub struct KeccakX4 {
+    states: [[u64; STATE_LENGTH]; PARALLEL_SPONGES],
+    rate: usize
+}
+
+impl KeccakX4 {
+    /// This function absorbs the inputs and their SHAKE padding into the sponges.
+    ///
+    /// Input:
+    ///     inputs: Between 1 and 4 arrays of bytes of the same length
+    ///     rate: The rate in bytes of the sponges
+    /// Output:
+    ///     The sponges ready to be squeezed
+    pub fn absorb(inputs: &[&[u8]], rate: usize) -> Self {
+        assert!(!inputs.is_empty() && inputs.len() <= PARALLEL_SPONGES, "Between 1 and 4 inputs can be absorbed");
+        assert!(inputs.iter().all(|input| input.len() == inputs[0].len()), "The inputs must have the same length");
+
+        let mut sponges = Self {
+            states: [[0; STATE_LENGTH]; PARALLEL_SPONGES],
+            rate
+        };
+        let length = inputs[0].len();
+        let mut block = vec![0u8; rate];
+
+        for offset in (0..=length).step_by(rate) {
+            let block_length = rate.min(length - offset);
+
+            for (state, input) in sponges.states.iter_mut().zip(inputs) {
+                block.fill(0);
+                block[..block_length].copy_from_slice(&input[offset..offset + block_length]);
+
+                // The last block, which may be empty, receives the padding
+                if block_length < rate {
+                    block[block_length] ^= SHAKE_PADDING;
+                    block[rate - 1] ^= 0x80;
+                }
+
+                for (lane, bytes) in state.iter_mut().zip(block.chunks_exact(8)) {
+                    *lane ^= u64::from_le_bytes(bytes.try_into().expect("Chunks have 8 bytes"));
+                }
+            }
+
+            if block_length == rate {
+                sponges.permute();
+            }
+        }
+
+        sponges
+    }
+
+    /// This function permutes the sponges and writes the first bytes of their states, at most
+    /// rate bytes, to the outputs.
+    ///
+    /// Input:
+    ///     outputs: At most 4 arrays of bytes, one for each of the first sponges
+    pub fn squeeze(&mut self, outputs: &mut [&mut [u8]]) {
+        self.permute();
+
+        for (state, output) in self.states.iter().zip(outputs.iter_mut()) {
+            assert!(output.len() <= self.rate, "At most rate bytes can be squeezed at once");
+
+            for (bytes, lane) in output.chunks_mut(8).zip(state.iter()) {
+                bytes.copy_from_slice(&lane.to_le_bytes()[..bytes.len()]);
+            }
+        }
+    }
+
+    fn permute(&mut self) {
+        #[cfg(target_arch = "x86_64")]
+        if is_x86_feature_detected!("avx2") {
+            // SAFETY: the CPU supports the AVX2 instructions
+            unsafe { f1600_x4(&mut self.states) };
+            return;
+        }
+
+        for state in self.states.iter_mut() {
+            keccak::f1600(state);
+        }
+    }
+}
+
+impl Drop for KeccakX4 {
+    fn drop(&mut self) {
+        // The states of the sponges computing the noise of Kyber are secret
+        self.states.zeroize();
+    }
+}
+
+/// This function corresponds to the Keccak-f[1600] permutation of four states, the lanes of the
+/// states being gathered in the 64-bits lanes of 256-bits registers.
+///
+/// # Safety
+/// The CPU must support the AVX2 instructions.
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+unsafe fn f1600_x4(states: &mut [[u64; STATE_LENGTH]; PARALLEL_SPONGES]) {
+    use std::arch::x86_64::*;
+
+    macro_rules! rotate {
+        ($lane:expr, $offset:literal) => {
+            _mm256_or_si256(_mm256_slli_epi64::<$offset>($lane), _mm256_srli_epi64::<{ 64 - $offset }>($lane))
+        };
+    }
+
+    let mut lanes = [_mm256_setzero_si256(); STATE_LENGTH];
+    for (i, lane) in lanes.iter_mut().enumerate() {
+        *lane = _mm256_set_epi64x(
+            states[3][i] as i64,
+            states[2][i] as i64,
+            states[1][i] as i64,
+            states[0][i] as i64
+        );
+    }
+
+    for round_constant in ROUND_CONSTANTS {
+        // Theta
+        let mut columns = [_mm256_setzero_si256(); 5];
+        for (x, column) in columns.iter_mut().enumerate() {
+            *column = _mm256_xor_si256(
+                _mm256_xor_si256(lanes[x], lanes[x + 5]),
+                _mm256_xor_si256(_mm256_xor_si256(lanes[x + 10], lanes[x + 15]), lanes[x + 20])
+            );
+        }
+        for x in 0..5 {
+            let next = columns[(x + 1) % 5];
+            let d = _mm256_xor_si256(columns[(x + 4) % 5], rotate!(next, 1));
+            for y in 0..5 {
+                lanes[x + 5 * y] = _mm256_xor_si256(lanes[x + 5 * y], d);
+            }
+        }
+
+        // Rho and pi, the lane x + 5.y being rotated and moved to the position y + 5.(2x + 3y)
+        let moved = [
+            lanes[0], rotate!(lanes[6], 44), rotate!(lanes[12], 43), rotate!(lanes[18], 21), rotate!(lanes[24], 14),
+            rotate!(lanes[3], 28), rotate!(lanes[9], 20), rotate!(lanes[10], 3), rotate!(lanes[16], 45), rotate!(lanes[22], 61),
+            rotate!(lanes[1], 1), rotate!(lanes[7], 6), rotate!(lanes[13], 25), rotate!(lanes[19], 8), rotate!(lanes[20], 18),
+            rotate!(lanes[4], 27), rotate!(lanes[5], 36), rotate!(lanes[11], 10), rotate!(lanes[17], 15), rotate!(lanes[23], 56),
+            rotate!(lanes[2], 62), rotate!(lanes[8], 55), rotate!(lanes[14], 39), rotate!(lanes[15], 41), rotate!(lanes[21], 2)
+        ];
+
+        // Chi
+        for y in 0..5 {
+            for x in 0..5 {
+                lanes[x + 5 * y] = _mm256_xor_si256(
+                    moved[x + 5 * y],
+                    _mm256_andnot_si256(moved[(x + 1) % 5 + 5 * y], moved[(x + 2) % 5 + 5 * y])
+                );
+            }
+        }
+
+        // Iota
+        lanes[0] = _mm256_xor_si256(lanes[0], _mm256_set1_epi64x(round_constant as i64));
+    }
+
+    let mut values = [0u64; PARALLEL_SPONGES];
+    for (i, lane) in lanes.iter().enumerate() {
+        _mm256_storeu_si256(values.as_mut_ptr() as *mut __m256i, *lane);
+        for (state, value) in states.iter_mut().zip(values) {
+            state[i] = value;
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::algorithms::utils::keccak::{KeccakX4, PARALLEL_SPONGES, STATE_LENGTH};
+
+    #[test]
+    fn test_permute_matches_keccak() {
+        let mut sponges = KeccakX4 {
+            states: [[0; STATE_LENGTH]; PARALLEL_SPONGES],
+            rate: 168
+        };
+        for (i, state) in sponges.states.iter_mut().enumerate() {
+            for (j, lane) in state.iter_mut().enumerate() {
+                *lane = ((i * STATE_LENGTH + j) as u64).wrapping_mul(0x9e3779b97f4a7c15);
+            }
+        }
+
+        let mut expected_states = sponges.states;
+        for state in expected_states.iter_mut() {
+            keccak::f1600(state);
+        }
+        sponges.permute();
+
+        assert_eq!(sponges.states, expected_states)
+    }
+}
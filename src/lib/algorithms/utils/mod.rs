@@ -1,2 +1,11 @@
 pub mod bits;
+pub mod constant_time;
+pub mod der;
 pub mod hash;
+pub mod io;
+pub mod keccak;
+pub mod padding;
+pub mod pem;
+pub mod secret;
+#[cfg(test)]
+pub mod drbg;
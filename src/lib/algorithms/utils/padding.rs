@@ -0,0 +1,80 @@
+use crate::CryptumResult;
+use crate::errors::CryptumError;
+
+/// This function pads data to a multiple of the block length with the ISO/IEC 7816-4 scheme: a
+/// 0x80 byte followed by as many 0x00 bytes as needed. A whole block is added when the length of
+/// the data is already a multiple of the block length so that the padding can always be removed
+/// without ambiguity, whatever the content of the data.
+///
+/// Input:
+///     data: The bytes to pad
+///     block_length: The length of a block in bytes
+/// Output:
+///     The padded bytes, whose length is a non-zero multiple of block_length
+pub fn pad(data: &[u8], block_length: usize) -> Vec<u8> {
+    let padding_length = block_length - data.len() % block_length;
+
+    let mut padded = Vec::with_capacity(data.len() + padding_length);
+    padded.extend_from_slice(data);
+    padded.push(0x80);
+    padded.resize(data.len() + padding_length, 0x00);
+
+    padded
+}
+
+/// This function removes the padding added by the pad function.
+///
+/// Input:
+///     padded: The padded bytes
+///     block_length: The length of a block in bytes
+/// Output:
+///     The original data
+pub fn unpad(padded: &[u8], block_length: usize) -> CryptumResult<Vec<u8>> {
+    if padded.is_empty() || !padded.len().is_multiple_of(block_length) {
+        return Err(CryptumError::InvalidPadding);
+    }
+
+    // The padding is at most one block long and ends with the first 0x80 byte met from the end
+    let last_block = &padded[padded.len() - block_length..];
+    let marker_index = last_block.iter()
+        .rposition(|&byte| byte != 0x00)
+        .filter(|&index| last_block[index] == 0x80)
+        .ok_or(CryptumError::InvalidPadding)?;
+
+    Ok(padded[..padded.len() - block_length + marker_index].to_vec())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::algorithms::byte_array::ByteArray;
+    use crate::algorithms::utils::padding::{pad, unpad};
+    use crate::errors::CryptumError;
+
+    #[test]
+    fn test_pad_unpad() {
+        for length in [0, 1, 31, 32, 33, 64, 100] {
+            let data = ByteArray::random(length).get_bytes().to_vec();
+
+            let padded = pad(&data, 32);
+            assert_eq!(padded.len(), (length / 32 + 1) * 32);
+            assert_eq!(unpad(&padded, 32).unwrap(), data);
+        }
+    }
+
+    #[test]
+    fn test_pad_unpad_trailing_zeros_and_markers() {
+        for data in [vec![0x00; 32], vec![0x80; 31], vec![0x41, 0x80, 0x00]] {
+            assert_eq!(unpad(&pad(&data, 32), 32).unwrap(), data);
+        }
+    }
+
+    #[test]
+    fn test_unpad_invalid() {
+        let invalid_paddings = [vec![], vec![0x80; 31], vec![0x00; 32], vec![0x41; 32]];
+
+        for padded in invalid_paddings {
+            assert!(matches!(unpad(&padded, 32), Err(CryptumError::InvalidPadding)), "padded = {:?}", padded);
+        }
+    }
+}
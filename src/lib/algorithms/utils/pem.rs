@@ -0,0 +1,109 @@
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use crate::algorithms::byte_array::ByteArray;
+use crate::CryptumResult;
+use crate::errors::CryptumError;
+
+/// The number of base64 characters on each line of a PEM block (RFC 7468)
+const PEM_LINE_LENGTH: usize = 64;
+
+/// This function encodes bytes as a PEM block (RFC 7468).
+///
+/// Input:
+///     data: The bytes to encode
+///     label: The label of the block, e.g. "PUBLIC KEY"
+/// Output:
+///     The base64 encoding of the bytes between the BEGIN and END lines
+pub fn encode_pem(data: &[u8], label: &str) -> String {
+    let encoded = STANDARD.encode(data);
+    let mut pem = format!("-----BEGIN {}-----\n", label);
+
+    for line in encoded.as_bytes().chunks(PEM_LINE_LENGTH) {
+        // The base64 alphabet only contains ASCII characters
+        pem.push_str(std::str::from_utf8(line).unwrap_or_default());
+        pem.push('\n');
+    }
+
+    pem.push_str(&format!("-----END {}-----\n", label));
+    pem
+}
+
+/// This function decodes a PEM block (RFC 7468) after checking its label.
+///
+/// Input:
+///     text: The PEM block
+///     label: The expected label
+/// Output:
+///     The bytes encoded in the block
+pub fn decode_pem(text: &str, label: &str) -> CryptumResult<ByteArray> {
+    let begin = format!("-----BEGIN {}-----", label);
+    let end = format!("-----END {}-----", label);
+
+    let mut lines = text.trim().lines().map(str::trim);
+    let first_line = lines.next().unwrap_or_default();
+
+    if first_line != begin {
+        return Err(CryptumError::InvalidPem(format!("expected `{}`, found `{}`", begin, first_line)));
+    }
+
+    let mut encoded = String::new();
+
+    for line in lines {
+        if line == end {
+            return Ok(STANDARD.decode(encoded)?.into());
+        }
+        encoded.push_str(line);
+    }
+
+    Err(CryptumError::InvalidPem(format!("missing `{}`", end)))
+}
+
+/// This function returns true if the text is a PEM block with the given label.
+pub fn has_pem_label(text: &str, label: &str) -> bool {
+    text.trim_start().starts_with(&format!("-----BEGIN {}-----", label))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::algorithms::byte_array::ByteArray;
+    use crate::algorithms::utils::pem::{decode_pem, encode_pem, has_pem_label};
+    use crate::errors::CryptumError;
+
+    const LABEL: &str = "PUBLIC KEY";
+
+    #[test]
+    fn test_encode_decode() {
+        let data = ByteArray::random(100);
+        let pem = encode_pem(data.get_bytes(), LABEL);
+
+        assert!(has_pem_label(&pem, LABEL));
+        assert_eq!(decode_pem(&pem, LABEL).unwrap(), data);
+    }
+
+    #[test]
+    fn test_encode_line_length() {
+        let pem = encode_pem(&[0u8; 60], LABEL);
+        let lines: Vec<&str> = pem.lines().collect();
+
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0], "-----BEGIN PUBLIC KEY-----");
+        assert_eq!(lines[1].len(), 64);
+        assert_eq!(lines[2].len(), 16);
+        assert_eq!(lines[3], "-----END PUBLIC KEY-----");
+    }
+
+    #[test]
+    fn test_decode_another_label() {
+        let pem = encode_pem(&[1, 2, 3], "PRIVATE KEY");
+
+        assert!(!has_pem_label(&pem, LABEL));
+        assert!(matches!(decode_pem(&pem, LABEL), Err(CryptumError::InvalidPem(_))));
+    }
+
+    #[test]
+    fn test_decode_truncated() {
+        let pem = "-----BEGIN PUBLIC KEY-----\nAAAA\n";
+        assert!(matches!(decode_pem(pem, LABEL), Err(CryptumError::InvalidPem(_))));
+    }
+}
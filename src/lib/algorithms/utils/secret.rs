@@ -0,0 +1,59 @@
+use std::fmt::{Debug, Formatter};
+use std::ops::Deref;
+use zeroize::Zeroize;
+
+/// This structure wraps a value holding secret material (seeds, private keys, noise
+/// polynomials...). The value is wiped from memory when the wrapper is dropped and is never
+/// printed by the Debug implementation.
+///
+/// A ByteArray already wipes itself when it is dropped, so a `Secret<ByteArray>` is wiped twice:
+/// once by the wrapper, then once more by the ByteArray. The second pass only writes zeros over
+/// the zeroed buffer and is kept so that the wrapper behaves the same for every type; it marks
+/// the byte arrays holding secret material.
+pub struct Secret<T: Zeroize>(T);
+
+impl <T: Zeroize> Secret<T> {
+    pub fn new(value: T) -> Self {
+        Self(value)
+    }
+}
+
+impl <T: Zeroize> Deref for Secret<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl <T: Zeroize> Drop for Secret<T> {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl <T: Zeroize> Debug for Secret<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Secret([REDACTED])")
+    }
+}
+
+impl <T: Zeroize> From<T> for Secret<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::algorithms::byte_array::ByteArray;
+    use crate::algorithms::utils::secret::Secret;
+
+    #[test]
+    fn test_debug_is_redacted() {
+        let secret = Secret::new(ByteArray::from([0x41u8; 4].as_slice()));
+
+        assert_eq!(format!("{:?}", secret), "Secret([REDACTED])");
+    }
+}
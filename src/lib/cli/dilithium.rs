@@ -0,0 +1,68 @@
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+/// The parameter sets of Dilithium
+pub const DILITHIUM_SPECS: [&str; 3] = ["2", "3", "5"];
+
+#[derive(StructOpt, Debug)]
+pub enum DilithiumArgs {
+    KEYGEN(DilithiumKeyGenArgs),
+    SIGN(DilithiumSignArgs),
+    VERIFY(DilithiumVerifyArgs)
+}
+
+
+#[derive(StructOpt, Debug)]
+pub struct DilithiumKeyGenArgs {
+    #[structopt(short, long, default_value="2", possible_values=&DILITHIUM_SPECS)]
+    /// The version of the algorithm to use (2/3/5)
+    pub spec: u8,
+
+    /// The path where to save the generated private key
+    #[structopt(long, default_value="dilithium_key.priv", parse(from_os_str))]
+    pub out_privkey: PathBuf,
+
+    /// The path where to save the generated public key
+    #[structopt(long, default_value="dilithium_key.pub", parse(from_os_str))]
+    pub out_pubkey: PathBuf
+}
+
+
+#[derive(StructOpt, Debug)]
+pub struct DilithiumSignArgs {
+    #[structopt(short, long, default_value="2", possible_values=&DILITHIUM_SPECS)]
+    /// The version of the algorithm to use (2/3/5)
+    pub spec: u8,
+
+    /// The path where is located the private key to use to sign the message
+    #[structopt(long, default_value="dilithium_key.priv", parse(from_os_str))]
+    pub in_privkey: PathBuf,
+
+    /// The path where the message to be signed is located
+    #[structopt(long, parse(from_os_str))]
+    pub in_message: PathBuf,
+
+    /// Path to save the generated signature
+    #[structopt(long, parse(from_os_str))]
+    pub out_signature: Option<PathBuf>,
+}
+
+
+#[derive(StructOpt, Debug)]
+pub struct DilithiumVerifyArgs {
+    #[structopt(short, long, default_value="2", possible_values=&DILITHIUM_SPECS)]
+    /// The version of the algorithm to use (2/3/5)
+    pub spec: u8,
+
+    /// The path where is located the public key of the signer
+    #[structopt(long, default_value="dilithium_key.pub", parse(from_os_str))]
+    pub in_pubkey: PathBuf,
+
+    /// The path where the signed message is located
+    #[structopt(long, parse(from_os_str))]
+    pub in_message: PathBuf,
+
+    /// The path where the signature to be checked is located
+    #[structopt(long, parse(from_os_str))]
+    pub in_signature: PathBuf,
+}
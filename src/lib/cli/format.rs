@@ -0,0 +1,68 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+
+/// The encodings that can be used to write keys, ciphertexts and shared keys to the disk.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DataFormat {
+    /// The bytes are written as they are
+    RAW,
+    /// The bytes are written as an hexadecimal string
+    HEX,
+    /// The bytes are written as a base64 string
+    BASE64,
+    /// The bytes are written as a base64 string between BEGIN and END lines
+    PEM,
+    /// The KEM keys are written as a DER encoded SubjectPublicKeyInfo or PKCS#8 structure
+    DER
+}
+
+impl DataFormat {
+    pub const VARIANTS: [&'static str; 5] = ["raw", "hex", "base64", "pem", "der"];
+    /// The formats available for the data that are not keys
+    pub const DATA_VARIANTS: [&'static str; 4] = ["raw", "hex", "base64", "pem"];
+}
+
+impl FromStr for DataFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "raw" => Ok(Self::RAW),
+            "hex" => Ok(Self::HEX),
+            "base64" => Ok(Self::BASE64),
+            "pem" => Ok(Self::PEM),
+            "der" => Ok(Self::DER),
+            _ => Err(format!("Unsupported format: {}", s))
+        }
+    }
+}
+
+impl Display for DataFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::RAW => "raw",
+            Self::HEX => "hex",
+            Self::BASE64 => "base64",
+            Self::PEM => "pem",
+            Self::DER => "der"
+        };
+        f.write_str(name)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::format::DataFormat;
+
+    #[test]
+    fn test_from_str() {
+        for name in DataFormat::VARIANTS {
+            let format: DataFormat = name.parse().unwrap();
+            assert_eq!(format.to_string(), name);
+        }
+        assert_eq!("PEM".parse::<DataFormat>(), Ok(DataFormat::PEM));
+        assert!("ber".parse::<DataFormat>().is_err());
+    }
+}
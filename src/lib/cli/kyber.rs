@@ -1,11 +1,18 @@
 use std::path::PathBuf;
 use structopt::StructOpt;
+use crate::cli::format::DataFormat;
 
+/// The parameter sets of the PKE
+pub const PKE_SPECS: [&str; 3] = ["512", "768", "1024"];
+/// The parameter sets of the KEM: the round 3 versions and the ML-KEM ones
+pub const KEM_SPECS: [&str; 6] = ["512", "768", "1024", "ml-kem-512", "ml-kem-768", "ml-kem-1024"];
 
 #[derive(StructOpt, Debug)]
 pub enum KyberArgs {
     PKE(KyberPKEArgs),
     KEM(KyberKEMArgs),
+    SEAL(KyberSealArgs),
+    OPEN(KyberOpenArgs),
 }
 
 #[derive(StructOpt, Debug)]
@@ -18,17 +25,19 @@ pub enum KyberPKEArgs {
 
 #[derive(StructOpt, Debug)]
 pub enum KyberKEMArgs {
-    KEYGEN(KyberKeyGenArgs),
+    KEYGEN(KyberKEMKeyGenArgs),
     ENCRYPT(KyberKEMEncryptArgs),
-    DECRYPT(KyberKEMDecryptArgs)
+    DECRYPT(KyberKEMDecryptArgs),
+    VALIDATE(KyberKEMValidateArgs),
+    PUBKEY(KyberKEMPubKeyArgs)
 }
 
 
 #[derive(StructOpt, Debug)]
 pub struct KyberKeyGenArgs {
-    #[structopt(short, long, default_value="512")]
+    #[structopt(short, long, default_value="512", possible_values=&PKE_SPECS)]
     /// The version of the algorithm to use (512/768/1024)
-    pub spec: u16,
+    pub spec: String,
 
     /// The path where to save the generated private key
     #[structopt(long, default_value="kyber_key.priv", parse(from_os_str))]
@@ -36,55 +45,95 @@ pub struct KyberKeyGenArgs {
 
     /// The path where to save the generated public key
     #[structopt(long, default_value="kyber_key.pub", parse(from_os_str))]
-    pub out_pubkey: PathBuf
+    pub out_pubkey: PathBuf,
+
+    /// The format of the generated keys (raw/hex/base64/pem)
+    #[structopt(long, default_value="hex", possible_values=&DataFormat::DATA_VARIANTS, case_insensitive=true)]
+    pub format: DataFormat
+}
+
+
+#[derive(StructOpt, Debug)]
+pub struct KyberKEMKeyGenArgs {
+    #[structopt(short, long, default_value="512", possible_values=&KEM_SPECS)]
+    /// The version of the algorithm to use (512/768/1024/ml-kem-512/ml-kem-768/ml-kem-1024)
+    pub spec: String,
+
+    /// The path where to save the generated private key
+    #[structopt(long, default_value="kyber_key.priv", parse(from_os_str))]
+    pub out_privkey: PathBuf,
+
+    /// The path where to save the generated public key
+    #[structopt(long, default_value="kyber_key.pub", parse(from_os_str))]
+    pub out_pubkey: PathBuf,
+
+    /// The format of the generated keys (raw/hex/base64/pem/der). pem and der produce a
+    /// SubjectPublicKeyInfo and a PKCS#8 private key
+    #[structopt(long, default_value="hex", possible_values=&DataFormat::VARIANTS, case_insensitive=true)]
+    pub format: DataFormat,
+
+    /// Save only the 64-bytes seed (d, z) as the private key. It is expanded again each time it
+    /// is used and gives back the same key pair
+    #[structopt(long)]
+    pub seed_only: bool
 }
 
 
 #[derive(StructOpt, Debug)]
 pub struct KyberPKEEncryptArgs {
-    #[structopt(short, long, default_value="512")]
+    #[structopt(short, long, default_value="512", possible_values=&PKE_SPECS)]
     /// The version of the algorithm to use (512/768/1024)
-    pub spec: u16,
+    pub spec: String,
 
     /// The path where is located the public key to be used to cipher the message
     #[structopt(long, default_value="kyber_key.pub", parse(from_os_str))]
     pub in_pubkey: PathBuf,
 
-    /// The path where the message to be encrypted is located
+    /// The path where the message to be encrypted is located. It is read from the standard input
+    /// otherwise
     #[structopt(long, parse(from_os_str))]
-    pub in_plaintext: PathBuf,
+    pub in_plaintext: Option<PathBuf>,
 
-    /// Pave to save the generated ciphertext
+    /// Path to save the generated ciphertext. It is written to the standard output otherwise
     #[structopt(long, parse(from_os_str))]
     pub out_ciphertext: Option<PathBuf>,
+
+    /// The format of the generated ciphertext (raw/hex/base64/pem)
+    #[structopt(long, default_value="hex", possible_values=&DataFormat::DATA_VARIANTS, case_insensitive=true)]
+    pub format: DataFormat
 }
 
 
 #[derive(StructOpt, Debug)]
 pub struct KyberPKEDecryptArgs {
-    #[structopt(short, long, default_value="512")]
+    #[structopt(short, long, default_value="512", possible_values=&PKE_SPECS)]
     /// The version of the algorithm to use (512/768/1024)
-    pub spec: u16,
+    pub spec: String,
 
     /// The path where is located the private key to use to decipher the message
     #[structopt(long, default_value="kyber_key.priv", parse(from_os_str))]
     pub in_privkey: PathBuf,
 
-    /// Path to save the retrieved plaintext
+    /// Path to save the retrieved plaintext. It is written to the standard output otherwise
     #[structopt(long, parse(from_os_str))]
     pub out_plaintext: Option<PathBuf>,
 
-    /// The path where the ciphertext to be deciphered is located
+    /// The path where the ciphertext to be deciphered is located. It is read from the standard
+    /// input otherwise
     #[structopt(long, parse(from_os_str))]
-    pub in_ciphertext: PathBuf,
+    pub in_ciphertext: Option<PathBuf>,
+
+    /// The format of the retrieved plaintext (raw/hex/base64/pem)
+    #[structopt(long, default_value="raw", possible_values=&DataFormat::DATA_VARIANTS, case_insensitive=true)]
+    pub format: DataFormat
 }
 
 
 #[derive(StructOpt, Debug)]
 pub struct KyberKEMEncryptArgs {
-    #[structopt(short, long, default_value="512")]
-    /// The version of the algorithm to use (512/768/1024)
-    pub spec: u16,
+    #[structopt(short, long, default_value="512", possible_values=&KEM_SPECS)]
+    /// The version of the algorithm to use (512/768/1024/ml-kem-512/ml-kem-768/ml-kem-1024)
+    pub spec: String,
 
     /// The path where to save the generated ciphertext
     #[structopt(long, default_value="kyber_ciphertext.txt", parse(from_os_str))]
@@ -100,15 +149,19 @@ pub struct KyberKEMEncryptArgs {
 
     /// The path where is situated the public key
     #[structopt(long, default_value="kyber_key.pub", parse(from_os_str))]
-    pub in_pubkey: PathBuf
+    pub in_pubkey: PathBuf,
+
+    /// The format of the generated ciphertext and shared key (raw/hex/base64/pem)
+    #[structopt(long, default_value="hex", possible_values=&DataFormat::DATA_VARIANTS, case_insensitive=true)]
+    pub format: DataFormat
 }
 
 
 #[derive(StructOpt, Debug)]
 pub struct KyberKEMDecryptArgs {
-    #[structopt(short, long, default_value="512")]
-    /// The version of the algorithm to use (512/768/1024)
-    pub spec: u16,
+    #[structopt(short, long, default_value="512", possible_values=&KEM_SPECS)]
+    /// The version of the algorithm to use (512/768/1024/ml-kem-512/ml-kem-768/ml-kem-1024)
+    pub spec: String,
 
     /// The path where to save the generated shared key
     #[structopt(long)]
@@ -124,6 +177,88 @@ pub struct KyberKEMDecryptArgs {
 
     /// The path where is situated the public key
     #[structopt(long, default_value="kyber_key.priv", parse(from_os_str))]
-    pub in_privkey: PathBuf
+    pub in_privkey: PathBuf,
+
+    /// The format of the generated shared key (raw/hex/base64/pem)
+    #[structopt(long, default_value="hex", possible_values=&DataFormat::DATA_VARIANTS, case_insensitive=true)]
+    pub format: DataFormat
 }
 
+
+
+#[derive(StructOpt, Debug)]
+pub struct KyberKEMValidateArgs {
+    #[structopt(short, long, default_value="512", possible_values=&KEM_SPECS)]
+    /// The version of the algorithm to use (512/768/1024/ml-kem-512/ml-kem-768/ml-kem-1024)
+    pub spec: String,
+
+    /// The path where is situated the public key to check
+    #[structopt(long, parse(from_os_str), required_unless="in-privkey")]
+    pub in_pubkey: Option<PathBuf>,
+
+    /// The path where is situated the private key to check. When a public key is given as well,
+    /// both keys must belong to the same key pair
+    #[structopt(long, parse(from_os_str))]
+    pub in_privkey: Option<PathBuf>
+}
+
+
+#[derive(StructOpt, Debug)]
+pub struct KyberKEMPubKeyArgs {
+    #[structopt(short, long, default_value="512", possible_values=&KEM_SPECS)]
+    /// The version of the algorithm to use (512/768/1024/ml-kem-512/ml-kem-768/ml-kem-1024)
+    pub spec: String,
+
+    /// The path where is situated the private key
+    #[structopt(long, default_value="kyber_key.priv", parse(from_os_str))]
+    pub in_privkey: PathBuf,
+
+    /// The path where to save the recovered public key
+    #[structopt(long, parse(from_os_str))]
+    pub out_pubkey: Option<PathBuf>,
+
+    /// The format of the recovered public key (raw/hex/base64/pem/der). pem and der produce a
+    /// SubjectPublicKeyInfo
+    #[structopt(long, default_value="hex", possible_values=&DataFormat::VARIANTS, case_insensitive=true)]
+    pub format: DataFormat
+}
+
+
+#[derive(StructOpt, Debug)]
+pub struct KyberSealArgs {
+    #[structopt(short, long, default_value="512", possible_values=&KEM_SPECS)]
+    /// The version of the KEM to use (512/768/1024/ml-kem-512/ml-kem-768/ml-kem-1024)
+    pub spec: String,
+
+    /// The paths where are situated the public keys of the recipients. Repeat the option to
+    /// encrypt the file for several recipients: any of their private keys can decrypt it
+    #[structopt(long, default_value="kyber_key.pub", number_of_values=1, parse(from_os_str))]
+    pub in_pubkey: Vec<PathBuf>,
+
+    /// The path where the file to be encrypted is located. It is read from the standard input
+    /// otherwise
+    #[structopt(long, parse(from_os_str))]
+    pub in_plaintext: Option<PathBuf>,
+
+    /// The path where to save the encrypted file. It is written to the standard output otherwise
+    #[structopt(long, parse(from_os_str))]
+    pub out_sealed: Option<PathBuf>
+}
+
+
+#[derive(StructOpt, Debug)]
+pub struct KyberOpenArgs {
+    /// The path where is situated the private key of the recipient. The version of the KEM is
+    /// read from the encrypted file
+    #[structopt(long, default_value="kyber_key.priv", parse(from_os_str))]
+    pub in_privkey: PathBuf,
+
+    /// The path where the file to be decrypted is located. It is read from the standard input
+    /// otherwise
+    #[structopt(long, parse(from_os_str))]
+    pub in_sealed: Option<PathBuf>,
+
+    /// The path where to save the decrypted file. It is written to the standard output otherwise
+    #[structopt(long, parse(from_os_str))]
+    pub out_plaintext: Option<PathBuf>
+}
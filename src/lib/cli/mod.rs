@@ -1,6 +1,8 @@
 
 use structopt::{StructOpt};
 pub mod kyber;
+pub mod dilithium;
+pub mod format;
 
 #[derive(StructOpt, Debug)]
 #[structopt(
@@ -9,5 +11,6 @@ pub mod kyber;
     about = "A CLI program that allows either encrypting or signing data using lattice-based cryptography."
 )]
 pub enum CryptumArgs {
-    KYBER(kyber::KyberArgs)
+    KYBER(kyber::KyberArgs),
+    DILITHIUM(dilithium::DilithiumArgs)
 }
\ No newline at end of file
@@ -6,6 +6,74 @@ pub enum CryptumError {
     IO(String),
     #[error("Hex parsing error: {0}")]
     HexParser(#[from] hex::FromHexError),
+    #[error("Base64 parsing error: {0}")]
+    Base64Parser(#[from] base64::DecodeError),
+    #[error("Invalid PEM block: {0}")]
+    InvalidPem(String),
+    #[error("Invalid DER encoding: {0}")]
+    InvalidDer(String),
+    #[error("Invalid padding: the data has not been padded by cryptum or has been corrupted")]
+    InvalidPadding,
+    #[error("Invalid sealed data: {0}")]
+    InvalidSealedData(String),
+    #[error("The data cannot be decrypted: the private key does not match or the data has been modified")]
+    DecryptionFailed,
+    #[error("The private key is not one of the recipients of the data")]
+    NotARecipient,
+    #[error("Unsupported format: {0}")]
+    UnsupportedFormat(String),
+    #[error("The signature is not valid")]
+    InvalidSignature,
+    #[error("The public key is not valid: its coefficients are not reduced modulo q")]
+    InvalidEncapsulationKey,
+    #[error("The private key is not valid: the hash of its public key does not match")]
+    InvalidDecapsulationKey,
+    #[error("The public key does not match the private key")]
+    KeyPairMismatch,
+    #[error("The key belongs to the {found} parameter set, not to {expected}")]
+    ParameterSetMismatch { expected: String, found: String },
+    #[error("Invalid key length: expected {expected} bytes, found {found}")]
+    InvalidKeyLength { expected: usize, found: usize },
+    #[error("Invalid ciphertext length: expected {expected} bytes, found {found}")]
+    InvalidCiphertextLength { expected: usize, found: usize },
+    #[error("Invalid seed length: expected {expected} bytes, found {found}")]
+    InvalidSeedLength { expected: usize, found: usize },
+    #[error("Invalid message length: expected {expected} bytes, found {found}")]
+    InvalidMessageLength { expected: usize, found: usize },
+    #[error("Invalid shared secret length: expected {expected} bytes, found {found}")]
+    InvalidSharedSecretLength { expected: usize, found: usize },
+    #[error("Invalid length for an encoded polynomial: expected {expected} bytes, found {found}")]
+    InvalidEncodingLength { expected: usize, found: usize },
+    #[error("Unsupported parameter set: {0}")]
+    UnsupportedParameterSet(String),
     #[error("An unknown error as occurred")]
     Unknown,
-}
\ No newline at end of file
+}
+
+impl CryptumError {
+    /// This function returns the exit code of the command line program for the error:
+    ///
+    /// Code | Errors
+    /// --- | ---
+    /// 1 | Unknown
+    /// 2 | Invalid arguments: UnsupportedParameterSet, UnsupportedFormat (and the errors detected by the argument parser)
+    /// 3 | Input/output errors: IO
+    /// 4 | Malformed input encoding: HexParser, Base64Parser, InvalidPem, InvalidDer, InvalidSealedData
+    /// 5 | Unusable keys: InvalidEncapsulationKey, InvalidDecapsulationKey, KeyPairMismatch, ParameterSetMismatch, NotARecipient, InvalidKeyLength, InvalidSeedLength
+    /// 6 | Data of an invalid length: InvalidCiphertextLength, InvalidMessageLength, InvalidSharedSecretLength, InvalidEncodingLength
+    /// 7 | Authentication failures: InvalidSignature, DecryptionFailed, InvalidPadding
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::Unknown => 1,
+            Self::UnsupportedParameterSet(_) | Self::UnsupportedFormat(_) => 2,
+            Self::IO(_) => 3,
+            Self::HexParser(_) | Self::Base64Parser(_) | Self::InvalidPem(_) | Self::InvalidDer(_)
+            | Self::InvalidSealedData(_) => 4,
+            Self::InvalidEncapsulationKey | Self::InvalidDecapsulationKey | Self::KeyPairMismatch
+            | Self::ParameterSetMismatch { .. } | Self::NotARecipient | Self::InvalidKeyLength { .. } | Self::InvalidSeedLength { .. } => 5,
+            Self::InvalidCiphertextLength { .. } | Self::InvalidMessageLength { .. }
+            | Self::InvalidSharedSecretLength { .. } | Self::InvalidEncodingLength { .. } => 6,
+            Self::InvalidSignature | Self::DecryptionFailed | Self::InvalidPadding => 7
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use crate::algorithms::byte_array::ByteArray;
+use crate::algorithms::dilithium::{Dilithium, Dilithium2, Dilithium3, Dilithium5, DILITHIUM_SEED_LENGTH};
+use crate::cli::dilithium::{DilithiumArgs, DilithiumKeyGenArgs, DilithiumSignArgs, DilithiumVerifyArgs};
+use crate::CryptumResult;
+use crate::errors::CryptumError;
+use crate::handler::format::{DILITHIUM_PRIVATE_KEY_LABEL, DILITHIUM_PUBLIC_KEY_LABEL, DILITHIUM_SIGNATURE_LABEL};
+use crate::handler::utils::{read_bytes_from_file, read_encoded_data_from_file, write_data_to_disk};
+
+pub fn get_dilithium(spec: u8) -> CryptumResult<Box<dyn Dilithium>> {
+    match spec {
+        2 => {
+            Ok(Box::new(Dilithium2::init()))
+        },
+        3 => {
+            Ok(Box::new(Dilithium3::init()))
+        },
+        5 => {
+            Ok(Box::new(Dilithium5::init()))
+        },
+        _ => {
+            Err(CryptumError::UnsupportedParameterSet(format!("Dilithium {}", spec)))
+        }
+    }
+}
+
+pub fn dilithium_keygen(args: DilithiumKeyGenArgs) -> CryptumResult<()> {
+    let dilithium = get_dilithium(args.spec)?;
+    let seed = ByteArray::random(DILITHIUM_SEED_LENGTH);
+    let (public_key, private_key) = dilithium.keygen(seed)?;
+
+    write_data_to_disk(public_key.to_hex(), args.out_pubkey)?;
+    write_data_to_disk(private_key.to_hex(), args.out_privkey)?;
+
+    Ok(())
+}
+
+pub fn dilithium_sign(args: DilithiumSignArgs) -> CryptumResult<()> {
+    let dilithium = get_dilithium(args.spec)?;
+
+    // As for the Kyber commands, the format of the inputs is detected and `-` reads them from
+    // the standard input
+    let private_key = read_encoded_data_from_file(args.in_privkey, DILITHIUM_PRIVATE_KEY_LABEL)?;
+
+    let message = ByteArray::from(read_bytes_from_file(args.in_message)?);
+
+    let signature = dilithium.sign(private_key, message)?;
+
+    match args.out_signature {
+        Some(path) => write_data_to_disk(signature.to_hex(), path)?,
+        None => print!("{}", signature.to_hex())
+    }
+
+    Ok(())
+}
+
+pub fn dilithium_verify(args: DilithiumVerifyArgs) -> CryptumResult<()> {
+    let dilithium = get_dilithium(args.spec)?;
+
+    let public_key = read_encoded_data_from_file(args.in_pubkey, DILITHIUM_PUBLIC_KEY_LABEL)?;
+    let signature = read_encoded_data_from_file(args.in_signature, DILITHIUM_SIGNATURE_LABEL)?;
+
+    let message = ByteArray::from(read_bytes_from_file(args.in_message)?);
+
+    if !dilithium.verify(public_key, message, signature)? {
+        return Err(CryptumError::InvalidSignature);
+    }
+
+    Ok(())
+}
+
+
+pub fn dilithium_handler(args: DilithiumArgs) -> CryptumResult<()> {
+    match args {
+        DilithiumArgs::KEYGEN(args) => {
+            dilithium_keygen(args)
+        },
+        DilithiumArgs::SIGN(args) => {
+            dilithium_sign(args)
+        },
+        DilithiumArgs::VERIFY(args) => {
+            dilithium_verify(args)
+        }
+    }
+}
@@ -0,0 +1,566 @@
+use std::io::{Read, Write};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use crate::algorithms::byte_array::ByteArray;
+use crate::algorithms::kyber::{decode_private_key_der, decode_private_key_pem, decode_public_key_der, decode_public_key_pem, encode_private_key_der, encode_private_key_pem, encode_private_key_seed_der, encode_private_key_seed_pem, encode_public_key_der, encode_public_key_pem, PRIVATE_KEY_PEM_LABEL, PUBLIC_KEY_PEM_LABEL};
+use crate::algorithms::utils::pem::{decode_pem, encode_pem, has_pem_label};
+use crate::cli::format::DataFormat;
+use crate::CryptumResult;
+use crate::errors::CryptumError;
+use crate::algorithms::utils::io::read_chunk;
+
+pub const PUBLIC_KEY_LABEL: &str = "KYBER PUBLIC KEY";
+pub const PRIVATE_KEY_LABEL: &str = "KYBER PRIVATE KEY";
+pub const CIPHERTEXT_LABEL: &str = "KYBER CIPHERTEXT";
+pub const SHARED_KEY_LABEL: &str = "KYBER SHARED KEY";
+pub const PLAINTEXT_LABEL: &str = "KYBER PLAINTEXT";
+pub const DILITHIUM_PUBLIC_KEY_LABEL: &str = "DILITHIUM PUBLIC KEY";
+pub const DILITHIUM_PRIVATE_KEY_LABEL: &str = "DILITHIUM PRIVATE KEY";
+pub const DILITHIUM_SIGNATURE_LABEL: &str = "DILITHIUM SIGNATURE";
+
+/// This function encodes bytes in the given format.
+///
+/// Input:
+///     data: The bytes to encode
+///     format: The format to use
+///     label: The label of the PEM block, e.g. "KYBER PUBLIC KEY"
+/// Output:
+///     The encoded bytes
+pub fn encode_data(data: &ByteArray, format: DataFormat, label: &str) -> CryptumResult<Vec<u8>> {
+    match format {
+        DataFormat::RAW => Ok(data.get_bytes().to_vec()),
+        DataFormat::HEX => Ok(data.to_hex().into_bytes()),
+        DataFormat::BASE64 => Ok(STANDARD.encode(data.get_bytes()).into_bytes()),
+        DataFormat::PEM => Ok(encode_pem(data.get_bytes(), label).into_bytes()),
+        DataFormat::DER => Err(CryptumError::UnsupportedFormat("der is only available for the KEM keys".to_string()))
+    }
+}
+
+/// This function checks that the keys of a KEM parameter set can be stored in a
+/// SubjectPublicKeyInfo or PKCS#8 structure. The OIDs of these structures only identify the
+/// ML-KEM parameter sets: the round 3 Kyber keys have the same layout, so other tools would
+/// misread them as ML-KEM keys. The pem format still falls back to a labelled PEM block for them,
+/// but the der format has no such fallback.
+fn check_standard_encoding(spec: &str) -> CryptumResult<()> {
+    if is_standard_encoding(spec) {
+        return Ok(());
+    }
+
+    Err(CryptumError::UnsupportedParameterSet(
+        format!("Kyber KEM {} keys cannot be stored as SubjectPublicKeyInfo or PKCS#8, which are only defined for ML-KEM", spec)
+    ))
+}
+
+/// This function tells if the keys of a KEM parameter set have a SubjectPublicKeyInfo and PKCS#8
+/// encoding, which is only the case of the ML-KEM parameter sets.
+fn is_standard_encoding(spec: &str) -> bool {
+    spec.starts_with("ml-kem-")
+}
+
+/// This function checks that the parameter set given by the OID of a SubjectPublicKeyInfo or
+/// PKCS#8 structure is the one selected with the spec option. Otherwise a seed, which does not
+/// tell its parameter set, would silently be expanded into a key of another parameter set.
+fn check_parameter_set(version: usize, spec: &str) -> CryptumResult<()> {
+    let found = format!("ml-kem-{}", version);
+
+    if found != spec {
+        return Err(CryptumError::ParameterSetMismatch { expected: spec.to_string(), found });
+    }
+
+    Ok(())
+}
+
+/// This function encodes a KEM public key in the given format. For the ML-KEM parameter sets, the
+/// pem and der formats produce a SubjectPublicKeyInfo structure. The round 3 Kyber keys are written
+/// in a labelled PEM block instead and cannot be encoded in der.
+pub fn encode_kem_public_key(public_key: &ByteArray, format: DataFormat, spec: &str) -> CryptumResult<Vec<u8>> {
+    match format {
+        DataFormat::PEM if is_standard_encoding(spec) => Ok(encode_public_key_pem(public_key)?.into_bytes()),
+        DataFormat::DER => {
+            check_standard_encoding(spec)?;
+            Ok(encode_public_key_der(public_key)?.get_bytes().to_vec())
+        }
+        _ => encode_data(public_key, format, PUBLIC_KEY_LABEL)
+    }
+}
+
+/// This function encodes a KEM private key in the given format. For the ML-KEM parameter sets, the
+/// pem and der formats produce a PKCS#8 structure. The round 3 Kyber keys are written in a labelled
+/// PEM block instead and cannot be encoded in der.
+pub fn encode_kem_private_key(private_key: &ByteArray, format: DataFormat, spec: &str) -> CryptumResult<Vec<u8>> {
+    match format {
+        DataFormat::PEM if is_standard_encoding(spec) => Ok(encode_private_key_pem(private_key)?.into_bytes()),
+        DataFormat::DER => {
+            check_standard_encoding(spec)?;
+            Ok(encode_private_key_der(private_key)?.get_bytes().to_vec())
+        }
+        _ => encode_data(private_key, format, PRIVATE_KEY_LABEL)
+    }
+}
+
+/// This function encodes the seed of a KEM key pair in the given format. For the ML-KEM parameter
+/// sets, the pem and der formats produce a PKCS#8 structure with the seed choice, whose parameter
+/// set is deduced from the public key. The round 3 Kyber keys are expanded from the seed in another
+/// way, so their seed is written in a labelled PEM block instead and cannot be encoded in der.
+pub fn encode_kem_private_key_seed(seed: &ByteArray, public_key: &ByteArray, format: DataFormat, spec: &str) -> CryptumResult<Vec<u8>> {
+    match format {
+        DataFormat::PEM if is_standard_encoding(spec) => Ok(encode_private_key_seed_pem(seed, public_key)?.into_bytes()),
+        DataFormat::DER => {
+            check_standard_encoding(spec)?;
+            Ok(encode_private_key_seed_der(seed, public_key)?.get_bytes().to_vec())
+        }
+        _ => encode_data(seed, format, PRIVATE_KEY_LABEL)
+    }
+}
+
+/// This function decodes bytes whose format is detected from their content: a PEM block starts
+/// with a BEGIN line, an hexadecimal or base64 string only contains characters of its alphabet
+/// and anything else is considered as raw bytes.
+///
+/// Input:
+///     data: The content of a file
+///     label: The label expected for a PEM block
+/// Output:
+///     The decoded bytes
+pub fn decode_data(data: Vec<u8>, label: &str) -> CryptumResult<ByteArray> {
+    let text = match std::str::from_utf8(&data) {
+        Ok(text) => text.trim(),
+        Err(_) => return Ok(data.into())
+    };
+
+    if text.starts_with("-----BEGIN ") {
+        return decode_pem(text, label);
+    }
+
+    if !text.is_empty() && text.len().is_multiple_of(2) && text.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return ByteArray::from_hex(text.to_string());
+    }
+
+    if let Some(bytes) = decode_base64(text) {
+        return Ok(bytes.into());
+    }
+
+    Ok(data.into())
+}
+
+/// This function decodes a KEM public key. Besides the formats detected by decode_data, the key
+/// of an ML-KEM parameter set can be a SubjectPublicKeyInfo structure encoded with DER or PEM,
+/// whose OID must match the spec.
+pub fn decode_kem_public_key(data: Vec<u8>, spec: &str) -> CryptumResult<ByteArray> {
+    let data = ByteArray::from(data);
+
+    if let Ok((version, public_key)) = decode_public_key_der(&data) {
+        check_parameter_set(version, spec)?;
+        return Ok(public_key);
+    }
+
+    match std::str::from_utf8(data.get_bytes()) {
+        Ok(text) if has_pem_label(text, PUBLIC_KEY_PEM_LABEL) => {
+            let (version, public_key) = decode_public_key_pem(text)?;
+            check_parameter_set(version, spec)?;
+            Ok(public_key)
+        },
+        _ => decode_data(data.get_bytes().to_vec(), PUBLIC_KEY_LABEL)
+    }
+}
+
+/// This function decodes a KEM private key. Besides the formats detected by decode_data, the key
+/// of an ML-KEM parameter set can be a PKCS#8 structure encoded with DER or PEM, whose OID must
+/// match the spec. A key stored as its seed is returned as is.
+pub fn decode_kem_private_key(data: Vec<u8>, spec: &str) -> CryptumResult<ByteArray> {
+    let data = ByteArray::from(data);
+
+    if let Ok((version, private_key)) = decode_private_key_der(&data) {
+        check_parameter_set(version, spec)?;
+        return Ok(private_key);
+    }
+
+    match std::str::from_utf8(data.get_bytes()) {
+        Ok(text) if has_pem_label(text, PRIVATE_KEY_PEM_LABEL) => {
+            let (version, private_key) = decode_private_key_pem(text)?;
+            check_parameter_set(version, spec)?;
+            Ok(private_key)
+        },
+        _ => decode_data(data.get_bytes().to_vec(), PRIVATE_KEY_LABEL)
+    }
+}
+
+fn decode_base64(text: &str) -> Option<Vec<u8>> {
+    if text.is_empty() || !text.len().is_multiple_of(4) || !text.bytes().all(is_base64_character) {
+        return None;
+    }
+
+    STANDARD.decode(text).ok()
+}
+
+/// The number of bytes encoded on each line of a PEM block (64 base64 characters)
+const PEM_LINE_BYTES: usize = 48;
+/// The number of bytes read at once from the input of a StreamDecoder
+const DECODER_BLOCK_LENGTH: usize = 8 * 1024;
+/// The number of bytes the format of a stream is detected from
+const DECODER_PEEK_LENGTH: usize = 64 * 1024;
+/// The largest text accepted after the base64 content of a PEM block
+const PEM_TRAILER_MAX_LENGTH: usize = 1024;
+
+/// This structure encodes data written chunk by chunk in the given format. It produces the same
+/// output as encode_data on the whole data while only keeping a few bytes in memory.
+pub struct StreamEncoder<W: Write> {
+    writer: W,
+    format: DataFormat,
+    label: String,
+    pending: Vec<u8>
+}
+
+impl <W: Write> StreamEncoder<W> {
+    /// This function creates an encoder. The BEGIN line of a PEM block is written immediately.
+    ///
+    /// Input:
+    ///     writer: Where to write the encoded data
+    ///     format: The format to use
+    ///     label: The label of the PEM block, e.g. "KYBER CIPHERTEXT"
+    pub fn new(writer: W, format: DataFormat, label: &str) -> CryptumResult<Self> {
+        let mut encoder = Self {
+            writer,
+            format,
+            label: label.to_string(),
+            pending: vec![]
+        };
+
+        match format {
+            DataFormat::DER => return Err(CryptumError::UnsupportedFormat("der is only available for the KEM keys".to_string())),
+            DataFormat::PEM => encoder.write_bytes(format!("-----BEGIN {}-----\n", label).as_bytes())?,
+            _ => {}
+        }
+
+        Ok(encoder)
+    }
+
+    /// This function encodes the next chunk of data.
+    pub fn write(&mut self, data: &[u8]) -> CryptumResult<()> {
+        match self.format {
+            DataFormat::HEX => self.write_bytes(hex::encode(data).as_bytes()),
+            DataFormat::BASE64 => self.write_base64(data, 3, false),
+            DataFormat::PEM => self.write_base64(data, PEM_LINE_BYTES, true),
+            _ => self.write_bytes(data)
+        }
+    }
+
+    /// This function encodes the bytes left, writes the END line of a PEM block and flushes the
+    /// writer.
+    pub fn finish(mut self) -> CryptumResult<()> {
+        let pending = std::mem::take(&mut self.pending);
+
+        match self.format {
+            DataFormat::BASE64 => self.write_bytes(STANDARD.encode(pending).as_bytes())?,
+            DataFormat::PEM => {
+                if !pending.is_empty() {
+                    self.write_bytes(format!("{}\n", STANDARD.encode(pending)).as_bytes())?;
+                }
+                self.write_bytes(format!("-----END {}-----\n", self.label).as_bytes())?;
+            },
+            _ => {}
+        }
+
+        self.writer.flush().map_err(write_error)
+    }
+
+    /// This function encodes the pending bytes by groups of group_length bytes, each group being
+    /// followed by a new line for a PEM block. The bytes of an incomplete group are kept.
+    fn write_base64(&mut self, data: &[u8], group_length: usize, new_lines: bool) -> CryptumResult<()> {
+        self.pending.extend_from_slice(data);
+        let length = self.pending.len() - self.pending.len() % group_length;
+        let groups: Vec<u8> = self.pending.drain(..length).collect();
+
+        let mut encoded = String::new();
+        for group in groups.chunks(group_length) {
+            encoded.push_str(&STANDARD.encode(group));
+            if new_lines {
+                encoded.push('\n');
+            }
+        }
+
+        self.write_bytes(encoded.as_bytes())
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) -> CryptumResult<()> {
+        self.writer.write_all(bytes).map_err(write_error)
+    }
+}
+
+/// The text encodings a StreamDecoder can decode
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TextEncoding {
+    Hex,
+    Base64
+}
+
+/// This structure decodes data read chunk by chunk. As for decode_data, the format (raw, hex,
+/// base64 or PEM) is detected from the beginning of the data.
+pub struct StreamDecoder<R: Read> {
+    reader: R,
+    encoding: Option<TextEncoding>,
+    end_line: Option<String>,
+    input: Vec<u8>,
+    text: Vec<u8>,
+    trailer: Vec<u8>,
+    decoded: Vec<u8>,
+    is_input_over: bool,
+    is_text_over: bool
+}
+
+impl <R: Read> StreamDecoder<R> {
+    /// This function creates a decoder and detects the format of the data from its first
+    /// DECODER_PEEK_LENGTH bytes.
+    ///
+    /// Input:
+    ///     reader: The encoded data
+    ///     label: The label expected for a PEM block
+    pub fn new(mut reader: R, label: &str) -> CryptumResult<Self> {
+        let mut input = vec![0u8; DECODER_PEEK_LENGTH];
+        let length = read_chunk(&mut reader, &mut input)?;
+        input.truncate(length);
+
+        let mut decoder = Self {
+            reader,
+            encoding: None,
+            end_line: None,
+            is_input_over: length < DECODER_PEEK_LENGTH,
+            input,
+            text: vec![],
+            trailer: vec![],
+            decoded: vec![],
+            is_text_over: false
+        };
+
+        // Data read at once is decoded exactly as by decode_data, the detection below only being
+        // needed for long data
+        if decoder.is_input_over {
+            let input = std::mem::take(&mut decoder.input);
+            decoder.decoded = decode_data(input, label)?.get_bytes().to_vec();
+            return Ok(decoder);
+        }
+
+        let start = decoder.input.iter().position(|byte| !byte.is_ascii_whitespace()).unwrap_or(decoder.input.len());
+        let content = &decoder.input[start..];
+
+        if content.starts_with(b"-----BEGIN ") {
+            let begin = format!("-----BEGIN {}-----", label);
+            let line_length = content.iter().position(|&byte| byte == b'\n').unwrap_or(content.len());
+            let first_line = String::from_utf8_lossy(&content[..line_length]).trim().to_string();
+
+            if first_line != begin {
+                return Err(CryptumError::InvalidPem(format!("expected `{}`, found `{}`", begin, first_line)));
+            }
+
+            decoder.input.drain(..start + line_length);
+            decoder.encoding = Some(TextEncoding::Base64);
+            decoder.end_line = Some(format!("-----END {}-----", label));
+        } else if !content.is_empty() && content.iter().all(|&byte| byte.is_ascii_hexdigit() || byte.is_ascii_whitespace()) {
+            decoder.encoding = Some(TextEncoding::Hex);
+        } else if !content.is_empty() && content.iter().all(|&byte| is_base64_character(byte) || byte.is_ascii_whitespace()) {
+            decoder.encoding = Some(TextEncoding::Base64);
+        }
+
+        Ok(decoder)
+    }
+
+    /// This function reads the next decoded bytes.
+    ///
+    /// Input:
+    ///     length: The number of bytes to read
+    /// Output:
+    ///     The decoded bytes, fewer than length only at the end of the data
+    pub fn read(&mut self, length: usize) -> CryptumResult<Vec<u8>> {
+        while self.decoded.len() < length && !(self.is_input_over && self.input.is_empty()) {
+            if self.input.is_empty() {
+                let mut block = vec![0u8; DECODER_BLOCK_LENGTH];
+                let read_length = read_chunk(&mut self.reader, &mut block)?;
+                block.truncate(read_length);
+                self.input = block;
+                self.is_input_over = read_length < DECODER_BLOCK_LENGTH;
+            }
+
+            let input = std::mem::take(&mut self.input);
+            match self.encoding {
+                Some(encoding) => self.decode_text(&input, encoding)?,
+                None => self.decoded.extend_from_slice(&input)
+            }
+        }
+
+        if self.is_input_over && self.input.is_empty() {
+            self.finish_text()?;
+        }
+
+        let length = length.min(self.decoded.len());
+        Ok(self.decoded.drain(..length).collect())
+    }
+
+    fn decode_text(&mut self, input: &[u8], encoding: TextEncoding) -> CryptumResult<()> {
+        for (index, &byte) in input.iter().enumerate() {
+            if self.is_text_over || (byte == b'-' && self.end_line.is_some()) {
+                // The END line of a PEM block and what follows it
+                self.is_text_over = true;
+                self.trailer.extend_from_slice(&input[index..]);
+                if self.trailer.len() > PEM_TRAILER_MAX_LENGTH {
+                    return Err(CryptumError::InvalidPem("unexpected data after the base64 content".to_string()));
+                }
+                break;
+            }
+
+            if !byte.is_ascii_whitespace() {
+                self.text.push(byte);
+            }
+        }
+
+        let length = match encoding {
+            TextEncoding::Hex => self.text.len() - self.text.len() % 2,
+            TextEncoding::Base64 => self.text.len() - self.text.len() % 4
+        };
+        let text: Vec<u8> = self.text.drain(..length).collect();
+        self.decode_group(&text, encoding)
+    }
+
+    /// This function decodes the characters left at the end of a text and checks that a PEM
+    /// block ends with its END line.
+    fn finish_text(&mut self) -> CryptumResult<()> {
+        let Some(encoding) = self.encoding.take() else {
+            return Ok(());
+        };
+
+        let text = std::mem::take(&mut self.text);
+        self.decode_group(&text, encoding)?;
+
+        if let Some(end_line) = &self.end_line {
+            let trailer = String::from_utf8_lossy(&self.trailer);
+
+            if !self.is_text_over {
+                return Err(CryptumError::InvalidPem(format!("missing `{}`", end_line)));
+            }
+            if trailer.trim() != end_line {
+                return Err(CryptumError::InvalidPem(format!("expected `{}`, found `{}`", end_line, trailer.trim())));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn decode_group(&mut self, text: &[u8], encoding: TextEncoding) -> CryptumResult<()> {
+        match encoding {
+            TextEncoding::Hex => self.decoded.extend(hex::decode(text)?),
+            TextEncoding::Base64 => self.decoded.extend(STANDARD.decode(text)?)
+        }
+        Ok(())
+    }
+}
+
+fn is_base64_character(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || matches!(byte, b'+' | b'/' | b'=')
+}
+
+fn write_error(err: std::io::Error) -> CryptumError {
+    CryptumError::IO(format!("Error while writing the data: {}", err))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::algorithms::byte_array::ByteArray;
+    use crate::cli::format::DataFormat;
+    use crate::errors::CryptumError;
+    use crate::handler::format::{decode_data, encode_data, StreamDecoder, StreamEncoder, DECODER_PEEK_LENGTH};
+
+    const LABEL: &str = "KYBER PUBLIC KEY";
+
+    #[test]
+    fn test_encode_decode() {
+        for length in [1, 32, 48, 800] {
+            let data = ByteArray::random(length);
+
+            for format in [DataFormat::RAW, DataFormat::HEX, DataFormat::BASE64, DataFormat::PEM] {
+                let encoded = encode_data(&data, format, LABEL).unwrap();
+                assert_eq!(decode_data(encoded, LABEL).unwrap(), data, "format = {}", format);
+            }
+        }
+    }
+
+    #[test]
+    fn test_decode_text_with_trailing_newline() {
+        let data = ByteArray::random(32);
+        let hex = format!("{}\n", data.to_hex()).into_bytes();
+        assert_eq!(decode_data(hex, LABEL).unwrap(), data);
+    }
+
+    #[test]
+    fn test_decode_pem_with_another_label() {
+        let data = ByteArray::random(32);
+        let pem = encode_data(&data, DataFormat::PEM, "KYBER PRIVATE KEY").unwrap();
+        assert!(matches!(decode_data(pem, LABEL), Err(CryptumError::InvalidPem(_))));
+    }
+
+    fn encode_stream(data: &[u8], chunk_length: usize, format: DataFormat) -> Vec<u8> {
+        let mut encoded = vec![];
+        let mut encoder = StreamEncoder::new(&mut encoded, format, LABEL).unwrap();
+        for chunk in data.chunks(chunk_length) {
+            encoder.write(chunk).unwrap();
+        }
+        encoder.finish().unwrap();
+        encoded
+    }
+
+    fn decode_stream(encoded: &[u8], chunk_length: usize) -> Result<Vec<u8>, CryptumError> {
+        let mut decoder = StreamDecoder::new(encoded, LABEL)?;
+        let mut decoded = vec![];
+        loop {
+            let chunk = decoder.read(chunk_length)?;
+            decoded.extend_from_slice(&chunk);
+            if chunk.len() < chunk_length {
+                return Ok(decoded);
+            }
+        }
+    }
+
+    #[test]
+    fn test_stream_encode_decode() {
+        for length in [1, 47, 48, 49, 800, 3 * DECODER_PEEK_LENGTH + 5] {
+            let data = ByteArray::random(length);
+
+            for format in [DataFormat::RAW, DataFormat::HEX, DataFormat::BASE64, DataFormat::PEM] {
+                let expected = encode_data(&data, format, LABEL).unwrap();
+
+                for chunk_length in [1, 7, 768, 1000] {
+                    let encoded = encode_stream(data.get_bytes(), chunk_length, format);
+                    assert_eq!(encoded, expected, "format = {}, chunk length = {}", format, chunk_length);
+                }
+
+                for chunk_length in [1, 768, 5000] {
+                    assert_eq!(decode_stream(&expected, chunk_length).unwrap(), data.get_bytes(), "format = {}", format);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_stream_decode_short_raw_data() {
+        // Raw bytes that look like the beginning of an hexadecimal or base64 string
+        for data in [&b"a"[..], b"A", b"abc", b"ABCDE"] {
+            assert_eq!(decode_stream(data, 32).unwrap(), data);
+        }
+    }
+
+    #[test]
+    fn test_stream_decode_invalid_text() {
+        // Longer than the bytes the format is detected from
+        let data = ByteArray::random(DECODER_PEEK_LENGTH);
+
+        let mut hex = data.to_hex().into_bytes();
+        hex.pop();
+        assert!(matches!(decode_stream(&hex, 32), Err(CryptumError::HexParser(_))));
+
+        let pem = encode_data(&data, DataFormat::PEM, LABEL).unwrap();
+        let truncated = &pem[..pem.len() - 10];
+        assert!(matches!(decode_stream(truncated, 32), Err(CryptumError::InvalidPem(_))));
+
+        let pem = encode_data(&data, DataFormat::PEM, "KYBER PRIVATE KEY").unwrap();
+        assert!(matches!(decode_stream(&pem, 32), Err(CryptumError::InvalidPem(_))));
+    }
+}
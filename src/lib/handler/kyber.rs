@@ -1,112 +1,148 @@
+use std::io::{Read, Write};
+use std::path::PathBuf;
 use crate::algorithms::byte_array::ByteArray;
-use crate::algorithms::kyber::{KyberKEM512, KyberKEM768, KyberKEM1024, KyberKEM, KyberPKE, KyberCPAPKE512, KyberCPAPKE768, KyberCPAPKE1024, get_random_coin, KYBER_MESSAGE_LENGTH};
-use crate::cli::kyber::{KyberArgs, KyberKEMArgs, KyberKEMDecryptArgs, KyberKEMEncryptArgs, KyberKeyGenArgs, KyberPKEArgs, KyberPKEDecryptArgs, KyberPKEEncryptArgs};
+use crate::algorithms::kyber::{KyberKEM512, KyberKEM768, KyberKEM1024, KyberKEM, MLKEM512, MLKEM768, MLKEM1024, KyberPKE, KyberCPAPKE512, KyberCPAPKE768, KyberCPAPKE1024, get_random_coin, open, seal, SealHeader, KYBER_KEM_SEED_LENGTH, KYBER_MESSAGE_LENGTH};
+use crate::algorithms::utils::io::read_chunk;
+use crate::algorithms::utils::padding::{pad, unpad};
+use crate::cli::format::DataFormat;
+use crate::cli::kyber::{KyberArgs, KyberKEMArgs, KyberOpenArgs, KyberSealArgs, KyberKEMDecryptArgs, KyberKEMEncryptArgs, KyberKEMKeyGenArgs, KyberKEMPubKeyArgs, KyberKEMValidateArgs, KyberKeyGenArgs, KyberPKEArgs, KyberPKEDecryptArgs, KyberPKEEncryptArgs};
 use crate::CryptumResult;
-use crate::handler::utils::{read_data_from_file, write_data_to_disk};
+use crate::errors::CryptumError;
+use crate::handler::format::{decode_kem_private_key, decode_kem_public_key, encode_data, encode_kem_private_key, encode_kem_private_key_seed, encode_kem_public_key, StreamDecoder, StreamEncoder, CIPHERTEXT_LABEL, PLAINTEXT_LABEL, PRIVATE_KEY_LABEL, PUBLIC_KEY_LABEL, SHARED_KEY_LABEL};
+use crate::handler::utils::{create_writer, is_standard_stream, open_reader, read_bytes_from_file, read_encoded_data_from_file, remove_partial_output, write_data_to_disk, write_data_to_stdout};
 
-pub fn get_pke_kyber(spec: u16) -> Box<dyn KyberPKE> {
+/// The number of blocks of the PKE encrypted or decrypted at once, which bounds the memory used
+/// whatever the length of the data
+const PKE_CHUNK_BLOCKS: usize = 1024;
+
+pub fn get_pke_kyber(spec: &str) -> CryptumResult<Box<dyn KyberPKE>> {
     match spec {
-        512 => {
-            Box::new(KyberCPAPKE512::init())
+        "512" => {
+            Ok(Box::new(KyberCPAPKE512::init()))
         },
-        768 => {
-            Box::new(KyberCPAPKE768::init())
+        "768" => {
+            Ok(Box::new(KyberCPAPKE768::init()))
         },
-        1024 => {
-            Box::new(KyberCPAPKE1024::init())
+        "1024" => {
+            Ok(Box::new(KyberCPAPKE1024::init()))
         },
         _ => {
-            panic!("Received invalid value for the Kyber PKE version to be used")
+            Err(CryptumError::UnsupportedParameterSet(format!("Kyber PKE {}", spec)))
         }
     }
 }
 
 pub fn kyber_pke_keygen(args: KyberKeyGenArgs) -> CryptumResult<()> {
-    let kyber = get_pke_kyber(args.spec);
+    let kyber = get_pke_kyber(&args.spec)?;
     let seed = ByteArray::random(32);
-    let (public_key, private_key) = kyber.keygen(seed);
+    let (public_key, private_key) = kyber.keygen(seed)?;
 
-    write_data_to_disk(public_key.to_hex(), args.out_pubkey)?;
-    write_data_to_disk(private_key.to_hex(), args.out_privkey)?;
+    write_data_to_disk(encode_data(&public_key, args.format, PUBLIC_KEY_LABEL)?, args.out_pubkey)?;
+    write_data_to_disk(encode_data(&private_key, args.format, PRIVATE_KEY_LABEL)?, args.out_privkey)?;
 
     Ok(())
 }
 
 pub fn kyber_pke_encrypt(args: KyberPKEEncryptArgs) -> CryptumResult<()> {
-    let kyber = get_pke_kyber(args.spec);
-
-    let plaintext_raw = read_data_from_file(args.in_plaintext)?;
-    let plaintext = ByteArray::from(plaintext_raw.into_bytes());
-
-    let public_key_raw = read_data_from_file(args.in_pubkey)?;
-    let public_key = ByteArray::from_hex(public_key_raw)?;
-
-    let mut cipher_text_str = String::new();
-
-    for chunk in plaintext.get_bytes().chunks(KYBER_MESSAGE_LENGTH) {
-        let chunk_length = chunk.len();
-        let mut chunk_vec = chunk.to_vec();
+    let kyber = get_pke_kyber(&args.spec)?;
+    let public_key = read_encoded_data_from_file(args.in_pubkey, PUBLIC_KEY_LABEL)?;
+
+    let mut reader = open_reader(args.in_plaintext)?;
+    let mut encoder = StreamEncoder::new(create_writer(args.out_ciphertext)?, args.format, CIPHERTEXT_LABEL)?;
+    let mut chunk = vec![0u8; PKE_CHUNK_BLOCKS * KYBER_MESSAGE_LENGTH];
+
+    loop {
+        let length = read_chunk(&mut reader, &mut chunk)?;
+        let is_last = length < chunk.len();
+
+        // The plaintext is padded so that its last block can be told apart from the padding,
+        // even for binary data ending with 0x00 bytes. Only the last chunk is padded, the other
+        // ones being made of whole blocks
+        let blocks = if is_last {
+            pad(&chunk[..length], KYBER_MESSAGE_LENGTH)
+        } else {
+            chunk.clone()
+        };
+
+        for block in blocks.chunks(KYBER_MESSAGE_LENGTH) {
+            let random_coin = get_random_coin();
+
+            let ciphertext = kyber.encrypt(
+                public_key.clone(),
+                block.into(),
+                random_coin
+            )?;
+
+            encoder.write(ciphertext.get_bytes())?;
+        }
 
-        if chunk_length != KYBER_MESSAGE_LENGTH {
-            chunk_vec.extend(vec![0; KYBER_MESSAGE_LENGTH - chunk_length])
+        if is_last {
+            break;
         }
+    }
+
+    encoder.finish()
+}
 
-        let random_coin = get_random_coin();
+pub fn kyber_pke_decrypt(args: KyberPKEDecryptArgs) -> CryptumResult<()> {
+    let kyber = get_pke_kyber(&args.spec)?;
+    let private_key = read_encoded_data_from_file(args.in_privkey, PRIVATE_KEY_LABEL)?;
 
-        let ciphertext = kyber.encrypt(
-            public_key.clone(),
-            chunk_vec.into(),
-            random_coin
-        );
+    let mut decoder = StreamDecoder::new(open_reader(args.in_ciphertext)?, CIPHERTEXT_LABEL)?;
+    let encoder = StreamEncoder::new(create_writer(args.out_plaintext.clone())?, args.format, PLAINTEXT_LABEL)?;
 
-        cipher_text_str.push_str(ciphertext.to_hex().as_str());
-    }
+    // The encoder, and the file it writes to, is dropped when the function returns
+    let result = pke_decrypt_stream(kyber.as_ref(), &private_key, &mut decoder, encoder);
 
-    if args.out_ciphertext.is_none() {
-        print!("{}", cipher_text_str);
-    } else {
-        write_data_to_disk(cipher_text_str, args.out_ciphertext.unwrap())?;
+    if result.is_err() {
+        remove_partial_output(args.out_plaintext);
     }
 
-    Ok(())
+    result
 }
 
-pub fn kyber_pke_decrypt(args: KyberPKEDecryptArgs) -> CryptumResult<()> {
-    let kyber = get_pke_kyber(args.spec);
+/// This function decrypts a ciphertext block by block. The last plaintext block is held back
+/// until the end of the ciphertext is reached as it holds the padding.
+fn pke_decrypt_stream(kyber: &dyn KyberPKE, private_key: &ByteArray, decoder: &mut StreamDecoder<impl Read>, mut encoder: StreamEncoder<impl Write>) -> CryptumResult<()> {
     let ciphertext_length = kyber.get_ciphertext_length();
+    let mut last_block: Option<ByteArray> = None;
+    let mut total_length = 0;
 
-    let ciphertext_raw = read_data_from_file(args.in_ciphertext)?;
-    let ciphertext = ByteArray::from_hex(ciphertext_raw)?;
+    loop {
+        let chunk = decoder.read(PKE_CHUNK_BLOCKS * ciphertext_length)?;
+        total_length += chunk.len();
 
-    let private_key_raw = read_data_from_file(args.in_privkey)?;
-    let private_key = ByteArray::from_hex(private_key_raw)?;
+        // The ciphertext must be made of whole blocks, at least one for the padding
+        if !chunk.len().is_multiple_of(ciphertext_length) || total_length == 0 {
+            let block_count = total_length.div_ceil(ciphertext_length).max(1);
 
-    let mut plaintext_str = String::new();
+            return Err(CryptumError::InvalidCiphertextLength {
+                expected: block_count * ciphertext_length,
+                found: total_length
+            });
+        }
 
-    for chunk in ciphertext.get_bytes().chunks(ciphertext_length) {
-        let chunk_length = chunk.len();
-        let mut chunk_vec = chunk.to_vec();
+        for block in chunk.chunks(ciphertext_length) {
+            let plaintext = kyber.decrypt(
+                private_key.clone(),
+                block.into()
+            )?;
 
-        if chunk_length != ciphertext_length {
-            chunk_vec.extend(vec![0; ciphertext_length - chunk_length])
+            if let Some(previous_block) = last_block.replace(plaintext) {
+                encoder.write(previous_block.get_bytes())?;
+            }
         }
 
-        let plaintext = kyber.decrypt(
-            private_key.clone(),
-            chunk_vec.into()
-        );
-
-        let plaintext_bytes: Vec<u8> = plaintext.get_bytes().to_vec().iter().map(|&val| val.clone()).filter(|&val| val != 0).collect();
-        plaintext_str.push_str(String::from_utf8_lossy(plaintext_bytes.as_slice()).as_ref());
+        if chunk.len() < PKE_CHUNK_BLOCKS * ciphertext_length {
+            break;
+        }
     }
 
-    if args.out_plaintext.is_none() {
-        println!("{}", plaintext_str)
-    } else {
-        write_data_to_disk(plaintext_str, args.out_plaintext.unwrap())?;
-    }
+    let last_block = last_block.ok_or(CryptumError::InvalidPadding)?;
+    let plaintext = unpad(last_block.get_bytes(), KYBER_MESSAGE_LENGTH)?;
 
-    Ok(())
+    encoder.write(&plaintext)?;
+    encoder.finish()
 }
 
 
@@ -124,40 +160,65 @@ pub fn kyber_pke_handler(args: KyberPKEArgs) -> CryptumResult<()> {
     }
 }
 
-pub fn get_kem_kyber(spec: u16) -> Box<dyn KyberKEM> {
+pub fn get_kem_kyber(spec: &str) -> CryptumResult<Box<dyn KyberKEM>> {
     match spec {
-        512 => {
-            Box::new(KyberKEM512::init())
+        "512" => {
+            Ok(Box::new(KyberKEM512::init()))
         },
-        768 => {
-           Box::new( KyberKEM768::init())
+        "768" => {
+            Ok(Box::new(KyberKEM768::init()))
         },
-        1024 => {
-            Box::new(KyberKEM1024::init())
+        "1024" => {
+            Ok(Box::new(KyberKEM1024::init()))
+        },
+        "ml-kem-512" => {
+            Ok(Box::new(MLKEM512::init()))
+        },
+        "ml-kem-768" => {
+            Ok(Box::new(MLKEM768::init()))
+        },
+        "ml-kem-1024" => {
+            Ok(Box::new(MLKEM1024::init()))
         }
         _ => {
-            panic!("Received invalid value for the Kyber KEM version to be used");
+            Err(CryptumError::UnsupportedParameterSet(format!("Kyber KEM {}", spec)))
         }
     }
 }
 
 
-pub fn kyber_kem_keygen(args: KyberKeyGenArgs) -> CryptumResult<()> {
-    let kyber = get_kem_kyber(args.spec);
-    let seed = ByteArray::random(64);
-    let (public_key, private_key) = kyber.keygen(seed);
+pub fn kyber_kem_keygen(args: KyberKEMKeyGenArgs) -> CryptumResult<()> {
+    let kyber = get_kem_kyber(&args.spec)?;
+    let seed = ByteArray::random(KYBER_KEM_SEED_LENGTH);
+    let (public_key, private_key) = kyber.keygen(seed.clone())?;
 
-    write_data_to_disk(public_key.to_hex(), args.out_pubkey)?;
-    write_data_to_disk(private_key.to_hex(), args.out_privkey)?;
+    let private_key_encoded = if args.seed_only {
+        encode_kem_private_key_seed(&seed, &public_key, args.format, &args.spec)?
+    } else {
+        encode_kem_private_key(&private_key, args.format, &args.spec)?
+    };
+
+    write_data_to_disk(encode_kem_public_key(&public_key, args.format, &args.spec)?, args.out_pubkey)?;
+    write_data_to_disk(private_key_encoded, args.out_privkey)?;
 
     Ok(())
 }
 
+/// This function reads a KEM private key and expands it when only its seed has been stored.
+fn read_kem_private_key(kyber: &dyn KyberKEM, spec: &str, path: PathBuf) -> CryptumResult<ByteArray> {
+    let private_key = decode_kem_private_key(read_bytes_from_file(path)?, spec)?;
+
+    if private_key.length() == KYBER_KEM_SEED_LENGTH {
+        return kyber.expand_private_key(private_key);
+    }
+
+    Ok(private_key)
+}
+
 
 pub fn kyber_kem_encrypt(args: KyberKEMEncryptArgs) -> CryptumResult<()> {
-    let kyber = get_kem_kyber(args.spec);
-    let pub_key_hex = read_data_from_file(args.in_pubkey)?;
-    let pub_key = ByteArray::from_hex(pub_key_hex)?;
+    let kyber = get_kem_kyber(&args.spec)?;
+    let pub_key = decode_kem_public_key(read_bytes_from_file(args.in_pubkey)?, &args.spec)?;
 
     let seed = ByteArray::random(32);
 
@@ -165,36 +226,78 @@ pub fn kyber_kem_encrypt(args: KyberKEMEncryptArgs) -> CryptumResult<()> {
         pub_key,
         seed,
         args.key_size
-    );
+    )?;
 
-    write_data_to_disk(ciphertext.to_hex(), args.out_ciphertext)?;
-    write_data_to_disk(shared_key.to_hex(), args.out_shared)?;
+    write_data_to_disk(encode_data(&ciphertext, args.format, CIPHERTEXT_LABEL)?, args.out_ciphertext)?;
+    write_data_to_disk(encode_data(&shared_key, args.format, SHARED_KEY_LABEL)?, args.out_shared)?;
 
     Ok(())
 }
 
 pub fn kyber_kem_decrypt(args: KyberKEMDecryptArgs) -> CryptumResult<()> {
-    let kyber = get_kem_kyber(args.spec);
-    let ciphertext_hex = read_data_from_file(args.in_ciphertext)?;
-    let ciphertext = ByteArray::from_hex(ciphertext_hex)?;
-
-    let priv_key_raw = read_data_from_file(args.in_privkey)?;
-    let priv_key = ByteArray::from_hex(priv_key_raw)?;
+    let kyber = get_kem_kyber(&args.spec)?;
+    let ciphertext = read_encoded_data_from_file(args.in_ciphertext, CIPHERTEXT_LABEL)?;
+    let priv_key = read_kem_private_key(kyber.as_ref(), &args.spec, args.in_privkey)?;
 
     let shared_key = kyber.decrypt(
         ciphertext,
         priv_key,
         args.key_size
-    );
+    )?;
 
-    if args.out_shared.is_none() {
-        println!("{}", shared_key.to_hex())
-    } else {
-        write_data_to_disk(shared_key.to_hex(), args.out_shared.unwrap())?;
+    let shared_key_encoded = encode_data(&shared_key, args.format, SHARED_KEY_LABEL)?;
+    write_encoded_output(shared_key_encoded, args.out_shared, args.format)
+}
+
+/// This function writes encoded data to a file, or prints it to the standard output when no path
+/// or `-` is given.
+fn write_encoded_output(mut data: Vec<u8>, path: Option<PathBuf>, format: DataFormat) -> CryptumResult<()> {
+    match path {
+        Some(path) if !is_standard_stream(&path) => write_data_to_disk(data, path),
+        _ => {
+            // The hexadecimal and base64 strings are printed on their own line
+            if matches!(format, DataFormat::HEX | DataFormat::BASE64) {
+                data.push(b'\n');
+            }
+            write_data_to_stdout(data)
+        }
+    }
+}
+
+/// This function performs the checks of FIPS 203 on the given keys: the modulus check of the
+/// public key, the hash check of the private key and, when both are given, that the private key
+/// embeds the public key.
+pub fn kyber_kem_validate(args: KyberKEMValidateArgs) -> CryptumResult<()> {
+    let kyber = get_kem_kyber(&args.spec)?;
+
+    let public_key = match args.in_pubkey {
+        Some(path) => Some(kyber.prepare_public_key(decode_kem_public_key(read_bytes_from_file(path)?, &args.spec)?)?),
+        None => None
+    };
+
+    if let Some(path) = args.in_privkey {
+        let private_key = kyber.prepare_private_key(read_kem_private_key(kyber.as_ref(), &args.spec, path)?)?;
+
+        if let Some(public_key) = public_key {
+            if private_key.public_key().as_bytes() != public_key.as_bytes() {
+                return Err(CryptumError::KeyPairMismatch);
+            }
+        }
     }
+
     Ok(())
 }
 
+/// This function recovers the public key embedded in a private key.
+pub fn kyber_kem_pubkey(args: KyberKEMPubKeyArgs) -> CryptumResult<()> {
+    let kyber = get_kem_kyber(&args.spec)?;
+    let priv_key = read_kem_private_key(kyber.as_ref(), &args.spec, args.in_privkey)?;
+
+    let public_key = kyber.extract_public_key(priv_key)?;
+    let public_key_encoded = encode_kem_public_key(&public_key, args.format, &args.spec)?;
+    write_encoded_output(public_key_encoded, args.out_pubkey, args.format)
+}
+
 
 pub fn kyber_kem_handler(args: KyberKEMArgs) -> CryptumResult<()> {
     match args {
@@ -206,10 +309,47 @@ pub fn kyber_kem_handler(args: KyberKEMArgs) -> CryptumResult<()> {
         },
         KyberKEMArgs::DECRYPT(args) => {
             kyber_kem_decrypt(args)
+        },
+        KyberKEMArgs::VALIDATE(args) => {
+            kyber_kem_validate(args)
+        },
+        KyberKEMArgs::PUBKEY(args) => {
+            kyber_kem_pubkey(args)
         }
     }
 }
 
+pub fn kyber_seal(args: KyberSealArgs) -> CryptumResult<()> {
+    let kyber = get_kem_kyber(&args.spec)?;
+    let pub_keys = args.in_pubkey.into_iter()
+        .map(|path| decode_kem_public_key(read_bytes_from_file(path)?, &args.spec))
+        .collect::<CryptumResult<Vec<_>>>()?;
+
+    let mut reader = open_reader(args.in_plaintext)?;
+    let mut writer = create_writer(args.out_sealed)?;
+
+    seal(kyber.as_ref(), &args.spec, &pub_keys, &mut reader, &mut writer)
+}
+
+pub fn kyber_open(args: KyberOpenArgs) -> CryptumResult<()> {
+    let mut reader = open_reader(args.in_sealed)?;
+    let header = SealHeader::read_from(&mut reader)?;
+
+    let kyber = get_kem_kyber(header.parameter_set())?;
+    let priv_key = read_kem_private_key(kyber.as_ref(), header.parameter_set(), args.in_privkey)?;
+
+    let mut writer = create_writer(args.out_plaintext.clone())?;
+    let result = open(kyber.as_ref(), priv_key, &header, &mut reader, &mut writer);
+
+    // The chunks decrypted before an error are authentic but the file is incomplete
+    if result.is_err() {
+        drop(writer);
+        remove_partial_output(args.out_plaintext);
+    }
+
+    result
+}
+
 
 pub fn kyber_handler(args: KyberArgs) -> CryptumResult<()> {
     match args {
@@ -218,6 +358,12 @@ pub fn kyber_handler(args: KyberArgs) -> CryptumResult<()> {
         },
         KyberArgs::PKE(args) => {
             kyber_pke_handler(args)
+        },
+        KyberArgs::SEAL(args) => {
+            kyber_seal(args)
+        },
+        KyberArgs::OPEN(args) => {
+            kyber_open(args)
         }
     }
 }
\ No newline at end of file
@@ -1,4 +1,7 @@
 mod kyber;
+mod dilithium;
 mod utils;
+mod format;
 
-pub use kyber::kyber_handler;
\ No newline at end of file
+pub use kyber::kyber_handler;
+pub use dilithium::dilithium_handler;
\ No newline at end of file
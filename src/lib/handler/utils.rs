@@ -1,9 +1,25 @@
 use std::fs;
-use std::path::PathBuf;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use crate::algorithms::byte_array::ByteArray;
 use crate::CryptumResult;
 use crate::errors::CryptumError;
+use crate::handler::format::decode_data;
+
+/// The path standing for the standard input or output
+const STANDARD_STREAM: &str = "-";
+
+/// This function returns true if the path is `-`, which stands for the standard input or output.
+pub fn is_standard_stream(path: &Path) -> bool {
+    path.as_os_str() == STANDARD_STREAM
+}
+
+pub fn write_data_to_disk(data: impl AsRef<[u8]>, path: PathBuf) -> CryptumResult<()> {
+    if is_standard_stream(&path) {
+        return write_data_to_stdout(data);
+    }
 
-pub fn write_data_to_disk(data: String, path: PathBuf) -> CryptumResult<()> {
     fs::write(path.clone(), data).map_err(|err| CryptumError::IO(
         format!("Error while writing to `{}`: {}",
                 path.display(),
@@ -12,11 +28,72 @@ pub fn write_data_to_disk(data: String, path: PathBuf) -> CryptumResult<()> {
     Ok(())
 }
 
-pub fn read_data_from_file(path: PathBuf) -> CryptumResult<String> {
-    let data = fs::read_to_string(path.clone()).map_err(|err| CryptumError::IO(
+pub fn write_data_to_stdout(data: impl AsRef<[u8]>) -> CryptumResult<()> {
+    let mut stdout = std::io::stdout();
+    stdout.write_all(data.as_ref())
+        .and_then(|_| stdout.flush())
+        .map_err(|err| CryptumError::IO(format!("Error while writing to the standard output: {}", err)))?;
+    Ok(())
+}
+
+/// This function opens a file to be read chunk by chunk, or uses the standard input when no path
+/// or `-` is given.
+pub fn open_reader(path: Option<PathBuf>) -> CryptumResult<Box<dyn Read>> {
+    match path {
+        Some(path) if !is_standard_stream(&path) => {
+            let file = File::open(path.clone()).map_err(|err| CryptumError::IO(
+                format!("An error occurred while trying to read `{}`: {}",
+                        path.display(),
+                        err)
+            ))?;
+            Ok(Box::new(BufReader::new(file)))
+        },
+        _ => Ok(Box::new(std::io::stdin().lock()))
+    }
+}
+
+/// This function creates a file to be written chunk by chunk, or uses the standard output when
+/// no path or `-` is given.
+pub fn create_writer(path: Option<PathBuf>) -> CryptumResult<Box<dyn Write>> {
+    match path {
+        Some(path) if !is_standard_stream(&path) => {
+            let file = File::create(path.clone()).map_err(|err| CryptumError::IO(
+                format!("Error while writing to `{}`: {}",
+                        path.display(),
+                        err)
+            ))?;
+            Ok(Box::new(BufWriter::new(file)))
+        },
+        _ => Ok(Box::new(BufWriter::new(std::io::stdout())))
+    }
+}
+
+/// This function removes the file written by a command which failed, as its content is
+/// incomplete. Nothing is done for the standard output.
+pub fn remove_partial_output(path: Option<PathBuf>) {
+    if let Some(path) = path.filter(|path| !is_standard_stream(path)) {
+        let _ = fs::remove_file(path);
+    }
+}
+
+pub fn read_bytes_from_file(path: PathBuf) -> CryptumResult<Vec<u8>> {
+    if is_standard_stream(&path) {
+        let mut data = vec![];
+        std::io::stdin().read_to_end(&mut data)
+            .map_err(|err| CryptumError::IO(format!("Error while reading the standard input: {}", err)))?;
+        return Ok(data);
+    }
+
+    let data = fs::read(path.clone()).map_err(|err| CryptumError::IO(
         format!("An error occurred while trying to read `{}`: {}",
                 path.display(),
                 err)
     ))?;
     Ok(data)
-}
\ No newline at end of file
+}
+
+/// This function reads a key, a ciphertext or a shared key whose format (raw, hex, base64 or PEM)
+/// is detected from the content of the file.
+pub fn read_encoded_data_from_file(path: PathBuf, label: &str) -> CryptumResult<ByteArray> {
+    decode_data(read_bytes_from_file(path)?, label)
+}
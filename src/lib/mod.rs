@@ -1,3 +1,20 @@
+//! Cryptum is a pure Rust implementation of the Kyber and Dilithium post-quantum algorithms.
+//!
+//! Besides the command line program, the algorithms can be used in memory through the `kyber` and
+//! `dilithium` modules:
+//!
+//! ```
+//! use cryptum::ByteArray;
+//! use cryptum::kyber::{KyberKEM, KyberKEM768};
+//!
+//! let kyber = KyberKEM768::init();
+//! let (public_key, private_key) = kyber.keygen(ByteArray::random(64)).unwrap();
+//!
+//! let (ciphertext, shared_secret) = kyber.encrypt(public_key, ByteArray::random(32), 32).unwrap();
+//! let shared_secret_prime = kyber.decrypt(ciphertext, private_key, 32).unwrap();
+//!
+//! assert_eq!(shared_secret, shared_secret_prime);
+//! ```
 mod algorithms;
 pub mod cli;
 pub mod handler;
@@ -7,3 +24,5 @@ use crate::errors::CryptumError;
 pub type CryptumResult<T> = Result<T, CryptumError>;
 
 pub use cli::{CryptumArgs};
+pub use algorithms::{dilithium, kyber};
+pub use algorithms::byte_array::ByteArray;
@@ -1,18 +1,34 @@
+use std::process;
 use structopt::StructOpt;
-use cryptum::{CryptumArgs, CryptumResult};
-use cryptum::handler::kyber_handler;
+use structopt::clap::ErrorKind;
+use cryptum::CryptumArgs;
+use cryptum::handler::{dilithium_handler, kyber_handler};
 
-fn main() -> CryptumResult<()> {
-    let args = CryptumArgs::from_args();
+/// The exit code used when the arguments cannot be parsed, as for the UnsupportedParameterSet and
+/// UnsupportedFormat errors
+const INVALID_ARGUMENTS_EXIT_CODE: i32 = 2;
 
-    match args {
+fn main() {
+    let args = match CryptumArgs::from_args_safe() {
+        Ok(args) => args,
+        Err(err) if matches!(err.kind, ErrorKind::HelpDisplayed | ErrorKind::VersionDisplayed) => err.exit(),
+        Err(err) => {
+            eprintln!("{}", err.message);
+            process::exit(INVALID_ARGUMENTS_EXIT_CODE);
+        }
+    };
+
+    let result = match args {
         CryptumArgs::KYBER(args) => {
-            let result = kyber_handler(args);
-            if let Err(e) = result {
-                println!("{}", e.to_string())
-            }
+            kyber_handler(args)
+        },
+        CryptumArgs::DILITHIUM(args) => {
+            dilithium_handler(args)
         }
-    }
+    };
 
-    Ok(())
+    if let Err(e) = result {
+        eprintln!("Error: {}", e);
+        process::exit(e.exit_code());
+    }
 }